@@ -62,7 +62,9 @@ fn main() {
 
     let start_time = Instant::now();
     let mut batch_processor = ExportRangeBarProcessor::new(threshold_bps);
-    batch_processor.process_trades_continuously(&trades);
+    batch_processor
+        .process_trades_continuously(&trades)
+        .expect("trades should be sorted");
     let mut bars = batch_processor.get_all_completed_bars();
 
     if let Some(incomplete) = batch_processor.get_incomplete_bar() {
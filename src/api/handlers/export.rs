@@ -8,9 +8,13 @@ use axum::{
 #[cfg(feature = "api")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "api")]
+use crate::api::handlers::validate_threshold;
 #[cfg(feature = "api")]
 use crate::api::models::ErrorResponse;
 #[cfg(feature = "api")]
+use crate::threshold::Threshold;
+#[cfg(feature = "api")]
 use uuid::Uuid;
 
 /// Export format options
@@ -32,7 +36,9 @@ pub struct ExportQuery {
     pub symbol: String,
     pub start_date: String,
     pub end_date: String,
-    pub threshold: f64,
+    /// Range threshold: bps (`25`, `0.5bps`) or percent (`0.25%`)
+    #[param(value_type = String)]
+    pub threshold: Threshold,
     #[serde(default = "default_limit")]
     pub limit: Option<usize>,
 }
@@ -73,6 +79,8 @@ pub async fn export_range_bars(
     Path(format): Path<ExportFormat>,
     Query(params): Query<ExportQuery>,
 ) -> Result<Json<ExportResponse>, Json<ErrorResponse>> {
+    validate_threshold(params.threshold).map_err(Json)?;
+
     // TODO: Implement range bar export functionality
     Err(Json(ErrorResponse {
         error: "NOT_IMPLEMENTED".to_string(),
@@ -24,3 +24,28 @@ pub use rangebar::*;
 pub use statistics::*;
 #[cfg(feature = "api")]
 pub use symbols::*;
+
+#[cfg(feature = "api")]
+use std::sync::LazyLock;
+
+#[cfg(feature = "api")]
+use crate::{api::models::ErrorResponse, config::Settings, threshold::Threshold};
+
+/// Settings the request bounds are checked against, loaded once
+#[cfg(feature = "api")]
+static SETTINGS: LazyLock<Settings> =
+    LazyLock::new(|| Settings::load().unwrap_or_else(|_| Settings::default()));
+
+/// Check a requested threshold against the configured bounds, as the export CLI does
+#[cfg(feature = "api")]
+pub(crate) fn validate_threshold(threshold: Threshold) -> Result<Threshold, ErrorResponse> {
+    SETTINGS
+        .algorithm
+        .check_threshold(threshold)
+        .map_err(|e| ErrorResponse {
+            error: "VALIDATION_ERROR".to_string(),
+            message: format!("Invalid threshold: {}", e),
+            details: None,
+            request_id: Some(uuid::Uuid::new_v4()),
+        })
+}
@@ -13,8 +13,10 @@ use validator::Validate;
 
 #[cfg(feature = "api")]
 use crate::{
+    api::handlers::validate_threshold,
     api::models::{ErrorResponse, GenerateRangeBarsRequest, ProcessingStats, RangeBarsResponse},
     range_bars::RangeBarProcessor,
+    threshold::Threshold,
 };
 
 /// Generate range bars from trade data
@@ -46,13 +48,13 @@ pub async fn generate_range_bars(
         ));
     }
 
-    let start_time = Instant::now();
+    let threshold = validate_threshold(request.threshold_bps)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(e)))?;
 
-    // Use threshold basis points directly from request (no conversion needed)
-    let threshold_bp = request.threshold_bps;
+    let start_time = Instant::now();
 
     // Create range bar processor
-    let mut processor = RangeBarProcessor::new(threshold_bp);
+    let mut processor = RangeBarProcessor::new(threshold);
 
     // Process trades into range bars
     let range_bars = match processor.process_trades(&request.trades) {
@@ -74,7 +76,7 @@ pub async fn generate_range_bars(
 
     let response = RangeBarsResponse {
         symbol: request.symbol,
-        threshold_bps: request.threshold_bps,
+        threshold_bps: threshold,
         bars: range_bars.clone(),
         processing_stats: ProcessingStats {
             trades_processed: request.trades.len() as u64,
@@ -94,9 +96,9 @@ pub struct StreamParams {
     /// Trading symbol
     #[validate(length(min = 3, max = 20))]
     pub symbol: String,
-    /// Threshold in basis points
-    #[validate(range(min = 1, max = 10000))]
-    pub threshold_bps: u32,
+    /// Range threshold: bps (`25`, `0.5bps`) or percent (`0.25%`)
+    #[param(value_type = String)]
+    pub threshold_bps: Threshold,
 }
 
 /// WebSocket streaming endpoint (placeholder)
@@ -127,6 +129,8 @@ pub async fn stream_range_bars(
         ));
     }
 
+    validate_threshold(params.threshold_bps).map_err(|e| (StatusCode::BAD_REQUEST, Json(e)))?;
+
     // TODO: Implement WebSocket streaming
     // This would require WebSocket connection handling and real-time trade data feed
     Ok("WebSocket streaming endpoint - implementation pending".to_string())
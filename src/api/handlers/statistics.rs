@@ -9,6 +9,8 @@ use uuid::Uuid;
 
 #[cfg(feature = "api")]
 use crate::api::models::{ComputeStatisticsRequest, ErrorResponse, StatisticsResponse};
+#[cfg(feature = "api")]
+use crate::threshold::Threshold;
 
 /// Query parameters for statistics computation
 #[cfg(feature = "api")]
@@ -17,7 +19,8 @@ pub struct StatisticsQuery {
     pub symbol: String,
     pub start_date: String,
     pub end_date: String,
-    pub threshold: f64,
+    /// Range threshold: bps (`25`, `0.5bps`) or percent (`0.25%`)
+    pub threshold: Threshold,
 }
 
 /// Compute statistics for range bars
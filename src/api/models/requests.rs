@@ -7,6 +7,8 @@ use utoipa::ToSchema;
 #[cfg(feature = "api")]
 use validator::Validate;
 
+#[cfg(feature = "api")]
+use crate::threshold::Threshold;
 #[cfg(feature = "api")]
 use crate::types::{AggTrade, RangeBar};
 
@@ -18,9 +20,10 @@ pub struct GenerateRangeBarsRequest {
     #[validate(length(min = 3, max = 20))]
     pub symbol: String,
 
-    /// Range threshold in basis points (80 = 0.8%)
-    #[validate(range(min = 1, max = 10000))]
-    pub threshold_bps: u32,
+    /// Range threshold: bps (`80`, `0.5`) or a string such as `"0.8%"`,
+    /// checked against the configured bounds
+    #[schema(value_type = f64)]
+    pub threshold_bps: Threshold,
 
     /// Aggregated trades to process
     #[validate(length(min = 1, max = 1000000))]
@@ -7,6 +7,8 @@ use utoipa::ToSchema;
 #[cfg(feature = "api")]
 use uuid::Uuid;
 
+#[cfg(feature = "api")]
+use crate::threshold::Threshold;
 #[cfg(feature = "api")]
 use crate::types::RangeBar;
 
@@ -41,7 +43,8 @@ pub struct RangeBarsResponse {
     /// Trading symbol
     pub symbol: String,
     /// Threshold in basis points used
-    pub threshold_bps: u32,
+    #[schema(value_type = f64)]
+    pub threshold_bps: Threshold,
     /// Generated range bars
     pub bars: Vec<RangeBar>,
     /// Processing statistics
@@ -40,6 +40,8 @@ use crate::api::{
             ErrorResponse,
             crate::types::AggTrade,
             crate::types::RangeBar,
+            crate::types::BreachDirection,
+            crate::types::CloseReason,
         )
     ),
     tags(
@@ -0,0 +1,344 @@
+//! Aggregate-trade-id continuity and duplicate audit
+//!
+//! Binance `agg_trade_id`s are dense within a symbol, and each aggregate's
+//! `first_trade_id..=last_trade_id` continues the previous one. [`IdAuditor`]
+//! watches the trades fed to a bar engine and records:
+//!
+//! - gaps in `agg_trade_id`
+//! - breaks in the individual trade id chain between consecutive aggregates
+//! - exact duplicates (same id, same payload)
+//! - conflicting duplicates (same id, different payload) and id regressions
+//!
+//! Findings are grouped into an [`AuditReport`] per symbol and UTC day, and
+//! [`IdAuditor::missing_in`] tells whether a bar's id range crosses a gap.
+
+use crate::types::{AggTrade, RangeBar};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Recent trades kept to tell exact from conflicting duplicates
+const DUPLICATE_WINDOW: usize = 1_024;
+
+/// One continuity or duplicate problem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditFinding {
+    /// `agg_trade_id`s strictly between `after` and `before` never arrived
+    AggIdGap {
+        after: i64,
+        before: i64,
+        missing: i64,
+        timestamp: i64,
+    },
+
+    /// Consecutive aggregates whose individual trade ids do not connect
+    TradeIdGap {
+        agg_trade_id: i64,
+        previous_last_trade_id: i64,
+        first_trade_id: i64,
+        timestamp: i64,
+    },
+
+    /// The same aggregate arrived again with an identical payload
+    ExactDuplicate { agg_trade_id: i64, timestamp: i64 },
+
+    /// The same `agg_trade_id` arrived again with a different payload
+    ConflictingDuplicate {
+        agg_trade_id: i64,
+        original: AggTrade,
+        duplicate: AggTrade,
+    },
+
+    /// An id at or below the highest seen id that is too old to compare
+    IdRegression {
+        agg_trade_id: i64,
+        highest_seen: i64,
+        timestamp: i64,
+    },
+}
+
+/// Counts and findings for one UTC day
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayAudit {
+    /// Trades observed, duplicates included
+    pub trades: u64,
+    pub first_agg_trade_id: Option<i64>,
+    pub last_agg_trade_id: Option<i64>,
+    pub agg_id_gaps: u64,
+    pub missing_agg_trade_ids: i64,
+    pub trade_id_gaps: u64,
+    pub exact_duplicates: u64,
+    pub conflicting_duplicates: u64,
+    /// Ids at or below the highest seen id that are too old to compare
+    #[serde(default)]
+    pub id_regressions: u64,
+    pub findings: Vec<AuditFinding>,
+}
+
+impl DayAudit {
+    /// Whether the day has no finding
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    fn record(&mut self, finding: AuditFinding) {
+        match finding {
+            AuditFinding::AggIdGap { missing, .. } => {
+                self.agg_id_gaps += 1;
+                self.missing_agg_trade_ids += missing;
+            }
+            AuditFinding::TradeIdGap { .. } => self.trade_id_gaps += 1,
+            AuditFinding::ExactDuplicate { .. } => self.exact_duplicates += 1,
+            AuditFinding::ConflictingDuplicate { .. } => self.conflicting_duplicates += 1,
+            AuditFinding::IdRegression { .. } => self.id_regressions += 1,
+        }
+        self.findings.push(finding);
+    }
+}
+
+/// Audit result for one symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub symbol: String,
+
+    /// Per UTC day, in date order
+    pub days: BTreeMap<NaiveDate, DayAudit>,
+}
+
+impl AuditReport {
+    /// Whether no day has a finding
+    pub fn is_clean(&self) -> bool {
+        self.days.values().all(DayAudit::is_clean)
+    }
+
+    /// Total `agg_trade_id`s missing across all days
+    pub fn missing_agg_trade_ids(&self) -> i64 {
+        self.days
+            .values()
+            .map(|day| day.missing_agg_trade_ids)
+            .sum()
+    }
+}
+
+/// Streaming id auditor for one symbol
+///
+/// Feed it the same trades as the bar engine, in the same order. State is
+/// serializable so an audit can resume with an export checkpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdAuditor {
+    report: AuditReport,
+
+    /// Most recent non-duplicate trade
+    last: Option<AggTrade>,
+
+    /// Recent non-duplicate trades, for payload comparison
+    recent: VecDeque<AggTrade>,
+
+    /// `agg_trade_id` gaps as `(after, before)`, ascending
+    gaps: Vec<(i64, i64)>,
+}
+
+impl IdAuditor {
+    /// Create an auditor for `symbol`
+    pub fn new(symbol: impl Into<String>) -> Self {
+        Self {
+            report: AuditReport {
+                symbol: symbol.into(),
+                days: BTreeMap::new(),
+            },
+            last: None,
+            recent: VecDeque::with_capacity(DUPLICATE_WINDOW),
+            gaps: Vec::new(),
+        }
+    }
+
+    /// Check one trade against the trades before it
+    ///
+    /// Returns `false` for a duplicate or regressed id, which a bar engine
+    /// must not see again.
+    pub fn observe(&mut self, trade: &AggTrade) -> bool {
+        let day = self
+            .report
+            .days
+            .entry(utc_date(trade.timestamp))
+            .or_default();
+        day.trades += 1;
+        day.first_agg_trade_id.get_or_insert(trade.agg_trade_id);
+        day.last_agg_trade_id = Some(
+            day.last_agg_trade_id
+                .map_or(trade.agg_trade_id, |id| id.max(trade.agg_trade_id)),
+        );
+
+        if let Some(ref last) = self.last {
+            if trade.agg_trade_id <= last.agg_trade_id {
+                let finding = match self
+                    .recent
+                    .iter()
+                    .rev()
+                    .find(|seen| seen.agg_trade_id == trade.agg_trade_id)
+                {
+                    Some(original) if original == trade => AuditFinding::ExactDuplicate {
+                        agg_trade_id: trade.agg_trade_id,
+                        timestamp: trade.timestamp,
+                    },
+                    Some(original) => AuditFinding::ConflictingDuplicate {
+                        agg_trade_id: trade.agg_trade_id,
+                        original: original.clone(),
+                        duplicate: trade.clone(),
+                    },
+                    None => AuditFinding::IdRegression {
+                        agg_trade_id: trade.agg_trade_id,
+                        highest_seen: last.agg_trade_id,
+                        timestamp: trade.timestamp,
+                    },
+                };
+                day.record(finding);
+                // Duplicates do not advance the continuity chain
+                return false;
+            }
+
+            if trade.agg_trade_id > last.agg_trade_id + 1 {
+                self.gaps.push((last.agg_trade_id, trade.agg_trade_id));
+                day.record(AuditFinding::AggIdGap {
+                    after: last.agg_trade_id,
+                    before: trade.agg_trade_id,
+                    missing: trade.agg_trade_id - last.agg_trade_id - 1,
+                    timestamp: trade.timestamp,
+                });
+            } else if trade.first_trade_id != last.last_trade_id + 1 {
+                // Only reported between consecutive aggregates; an id gap explains the rest
+                day.record(AuditFinding::TradeIdGap {
+                    agg_trade_id: trade.agg_trade_id,
+                    previous_last_trade_id: last.last_trade_id,
+                    first_trade_id: trade.first_trade_id,
+                    timestamp: trade.timestamp,
+                });
+            }
+        }
+
+        if self.recent.len() == DUPLICATE_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(trade.clone());
+        self.last = Some(trade.clone());
+        true
+    }
+
+    /// Check a chunk of trades
+    pub fn observe_all(&mut self, trades: &[AggTrade]) {
+        for trade in trades {
+            self.observe(trade);
+        }
+    }
+
+    /// Check a chunk of trades, removing its duplicates and regressed ids
+    ///
+    /// The trades left continue the id sequence, so the engines accept a feed
+    /// that repeats itself; the removed ones stay in the report.
+    pub fn observe_and_dedup(&mut self, trades: &mut Vec<AggTrade>) {
+        trades.retain(|trade| self.observe(trade));
+    }
+
+    /// Findings so far
+    pub fn report(&self) -> &AuditReport {
+        &self.report
+    }
+
+    /// Number of `agg_trade_id`s missing strictly inside `first_id..=last_id`
+    pub fn missing_in(&self, first_id: i64, last_id: i64) -> i64 {
+        let start = self.gaps.partition_point(|&(after, _)| after < first_id);
+        self.gaps[start..]
+            .iter()
+            .take_while(|&&(_, before)| before <= last_id)
+            .map(|&(after, before)| before - after - 1)
+            .sum()
+    }
+
+    /// Record on `bar` how many ids its range is missing
+    pub fn annotate(&self, bar: &mut RangeBar) {
+        bar.missing_agg_trade_ids = Some(self.missing_in(bar.first_id, bar.last_id));
+    }
+}
+
+/// UTC calendar day of a millisecond timestamp
+fn utc_date(timestamp: i64) -> NaiveDate {
+    chrono::DateTime::from_timestamp_millis(timestamp)
+        .map(|time| time.date_naive())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::FixedPoint;
+
+    fn create_test_trade(id: i64, first: i64, last: i64, price: &str) -> AggTrade {
+        AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str(price).unwrap(),
+            volume: FixedPoint::from_str("1.0").unwrap(),
+            first_trade_id: first,
+            last_trade_id: last,
+            timestamp: 1_704_067_200_000 + id * 1_000,
+            is_buyer_maker: false,
+        }
+    }
+
+    #[test]
+    fn test_finds_gaps_and_duplicates() {
+        let mut auditor = IdAuditor::new("BTCUSDT");
+        let mut conflicting = create_test_trade(2, 12, 13, "101.0");
+        conflicting.timestamp += 500;
+
+        auditor.observe_all(&[
+            create_test_trade(1, 10, 11, "100.0"),
+            create_test_trade(2, 12, 13, "100.0"),
+            create_test_trade(2, 12, 13, "100.0"), // Exact duplicate
+            conflicting,                           // Same id, different price
+            create_test_trade(3, 15, 15, "100.0"), // Trade id 14 missing
+            create_test_trade(7, 20, 21, "100.0"), // Agg ids 4..=6 missing
+            create_test_trade(8, 22, 22, "100.0"),
+        ]);
+
+        let report = auditor.report();
+        assert_eq!(report.days.len(), 1);
+        let day = report.days.values().next().unwrap();
+        assert_eq!(day.trades, 7);
+        assert_eq!(
+            (day.first_agg_trade_id, day.last_agg_trade_id),
+            (Some(1), Some(8))
+        );
+        assert_eq!((day.exact_duplicates, day.conflicting_duplicates), (1, 1));
+        assert_eq!(day.id_regressions, 0);
+        assert_eq!((day.trade_id_gaps, day.agg_id_gaps), (1, 1));
+        assert_eq!(report.missing_agg_trade_ids(), 3);
+        assert!(!report.is_clean());
+
+        // Bar id ranges crossing the gap
+        assert_eq!(auditor.missing_in(1, 6), 0);
+        assert_eq!(auditor.missing_in(3, 7), 3);
+        assert_eq!(auditor.missing_in(7, 8), 0);
+    }
+
+    #[test]
+    fn test_regressions_are_not_counted_as_conflicts() {
+        let mut auditor = IdAuditor::new("BTCUSDT");
+        auditor.observe_all(&[
+            create_test_trade(5, 10, 10, "100.0"),
+            create_test_trade(6, 11, 11, "100.0"),
+            create_test_trade(4, 9, 9, "100.0"), // Never seen, below the highest id
+        ]);
+
+        let day = auditor.report().days.values().next().unwrap();
+        assert_eq!((day.conflicting_duplicates, day.id_regressions), (0, 1));
+        assert!(matches!(
+            day.findings.last(),
+            Some(AuditFinding::IdRegression {
+                agg_trade_id: 4,
+                highest_seen: 6,
+                ..
+            })
+        ));
+    }
+}
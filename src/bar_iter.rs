@@ -0,0 +1,227 @@
+//! Lazy iterator adapter for range bar construction
+//!
+//! [`RangeBarIteratorExt::range_bars`] turns any iterator of trades, or of
+//! `Result`s of trades such as a csv deserializer, into an iterator of
+//! completed [`RangeBar`]s. Trades are pulled one at a time and fed straight
+//! to a [`RangeBarProcessor`], so memory stays constant however long the
+//! input is:
+//!
+//! ```rust
+//! use rangebar::{AggTrade, FixedPoint, RangeBarIteratorExt};
+//!
+//! let trades = (0..1_000i64).map(|i| AggTrade {
+//!     agg_trade_id: i,
+//!     price: FixedPoint(5_000_000_000_000 + (i % 50) * 500_000_000),
+//!     volume: FixedPoint(100_000_000),
+//!     first_trade_id: i,
+//!     last_trade_id: i,
+//!     timestamp: 1_609_459_200_000 + i,
+//!     is_buyer_maker: i % 2 == 0,
+//! });
+//!
+//! for bar in trades.range_bars(25).with_incomplete() {
+//!     let bar = bar.unwrap();
+//!     assert!(bar.high >= bar.low);
+//! }
+//! ```
+
+use crate::range_bars::{ProcessingError, RangeBarProcessor};
+use crate::threshold::BarRange;
+use crate::trade::Trade;
+use crate::types::RangeBar;
+use thiserror::Error;
+
+/// Error from a fallible trade source or from bar construction
+#[derive(Error, Debug)]
+pub enum TradeSourceError<E> {
+    #[error("Trade source failed: {0}")]
+    Source(E),
+
+    #[error(transparent)]
+    Processing(#[from] ProcessingError),
+}
+
+/// Iterator item accepted by [`RangeBarIteratorExt`]: a trade or a `Result` of one
+pub trait TradeItem {
+    /// Trade carried by the item
+    type Trade: Trade;
+
+    /// Error yielded by the bar iterator
+    type Error: From<ProcessingError>;
+
+    /// The trade, or the source's error
+    fn into_trade(self) -> Result<Self::Trade, Self::Error>;
+}
+
+impl<T: Trade> TradeItem for T {
+    type Trade = T;
+    type Error = ProcessingError;
+
+    fn into_trade(self) -> Result<T, ProcessingError> {
+        Ok(self)
+    }
+}
+
+impl<T: Trade, E> TradeItem for Result<T, E> {
+    type Trade = T;
+    type Error = TradeSourceError<E>;
+
+    fn into_trade(self) -> Result<T, TradeSourceError<E>> {
+        self.map_err(TradeSourceError::Source)
+    }
+}
+
+/// Build range bars lazily from an iterator of trades
+pub trait RangeBarIteratorExt: Iterator + Sized
+where
+    Self::Item: TradeItem,
+{
+    /// Completed bars at `range`, built as the trades are pulled
+    fn range_bars(self, range: impl Into<BarRange>) -> RangeBars<Self> {
+        self.range_bars_with(RangeBarProcessor::new(range))
+    }
+
+    /// Completed bars from a configured `processor` (policy, cuts, checkpoint)
+    fn range_bars_with(self, processor: RangeBarProcessor) -> RangeBars<Self> {
+        RangeBars {
+            trades: self,
+            processor,
+            include_incomplete: false,
+            done: false,
+        }
+    }
+}
+
+impl<I> RangeBarIteratorExt for I
+where
+    I: Iterator,
+    I::Item: TradeItem,
+{
+}
+
+/// Iterator of range bars over a trade iterator
+///
+/// Created by [`RangeBarIteratorExt::range_bars`]. Yields each bar as soon as
+/// the trade completing it is pulled. After the first error the iterator is
+/// finished.
+pub struct RangeBars<I> {
+    trades: I,
+    processor: RangeBarProcessor,
+
+    /// Yield the open bar, force-closed, once the trades run out
+    include_incomplete: bool,
+
+    /// Trades exhausted or an error was yielded
+    done: bool,
+}
+
+impl<I> RangeBars<I> {
+    /// Also yield the trailing open bar with [`CloseReason::EndOfData`](crate::types::CloseReason::EndOfData)
+    pub fn with_incomplete(mut self) -> Self {
+        self.include_incomplete = true;
+        self
+    }
+
+    /// The underlying processor, e.g. to checkpoint it or inspect the open bar
+    pub fn processor(&self) -> &RangeBarProcessor {
+        &self.processor
+    }
+
+    /// Stop iterating and return the processor with its open bar
+    pub fn into_processor(self) -> RangeBarProcessor {
+        self.processor
+    }
+}
+
+impl<I> Iterator for RangeBars<I>
+where
+    I: Iterator,
+    I::Item: TradeItem,
+{
+    type Item = Result<RangeBar, <I::Item as TradeItem>::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        for item in self.trades.by_ref() {
+            let completed = item
+                .into_trade()
+                .and_then(|trade| Ok(self.processor.process_single_trade(trade)?));
+            match completed {
+                Ok(Some(bar)) => return Some(Ok(bar)),
+                Ok(None) => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        self.done = true;
+        if self.include_incomplete {
+            self.processor.finish().map(Ok)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_trades;
+    use crate::types::{AggTrade, CloseReason};
+
+    #[test]
+    fn test_lazy_bars_match_batch() {
+        let trades = create_trades();
+        let batch = RangeBarProcessor::new(25)
+            .process_trades_with_incomplete(&trades)
+            .unwrap();
+
+        let lazy: Vec<_> = trades
+            .clone()
+            .into_iter()
+            .range_bars(25)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(lazy, batch[..batch.len() - 1]);
+
+        let with_incomplete: Vec<_> = trades
+            .into_iter()
+            .range_bars(25)
+            .with_incomplete()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(with_incomplete, batch);
+        assert_eq!(
+            with_incomplete.last().unwrap().close_reason,
+            Some(CloseReason::EndOfData)
+        );
+    }
+
+    #[test]
+    fn test_errors_end_iteration() {
+        let mut trades: Vec<Result<AggTrade, &str>> = create_trades().into_iter().map(Ok).collect();
+        trades.insert(4, Err("bad row"));
+
+        let mut bars = trades.into_iter().range_bars(25).with_incomplete();
+        assert!(bars.next().unwrap().is_ok());
+        assert!(matches!(
+            bars.next(),
+            Some(Err(TradeSourceError::Source("bad row")))
+        ));
+        assert!(bars.next().is_none(), "no incomplete bar after an error");
+
+        let mut unsorted = create_trades();
+        unsorted.swap(0, 1);
+        let mut bars = unsorted.into_iter().range_bars(25);
+        assert!(matches!(
+            bars.next(),
+            Some(Err(ProcessingError::UnsortedTrades { index: 0, .. }))
+        ));
+        assert!(bars.next().is_none());
+    }
+}
@@ -0,0 +1,182 @@
+//! Async stream adapter from trades to range bars
+//!
+//! [`RangeBarStreamExt::range_bars`] is the `futures::Stream` counterpart of
+//! [`RangeBarIteratorExt`](crate::bar_iter::RangeBarIteratorExt): it turns a
+//! stream of trades into a stream of completed bars without the channel pair
+//! and processing loop of [`StreamingProcessor`](crate::StreamingProcessor).
+//!
+//! The adapter only polls the trade stream when it is polled itself, so a slow
+//! consumer slows the feed down with no buffering in between. Bars come out as
+//! `Result<RangeBar, StreamingError>`, the item type of [`RangeBarStream`],
+//! into which the adapter converts for code that stores streams by type.
+
+use crate::range_bars::RangeBarProcessor;
+use crate::streaming_processor::{RangeBarStream, StreamingError};
+use crate::threshold::BarRange;
+use crate::trade::Trade;
+use crate::types::RangeBar;
+use futures::{Stream, ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Build range bars from a stream of trades
+pub trait RangeBarStreamExt: Stream + Sized
+where
+    Self::Item: Trade,
+{
+    /// Completed bars at `range`, built as trades arrive
+    fn range_bars(self, range: impl Into<BarRange>) -> RangeBarStreamAdapter<Self> {
+        self.range_bars_with(RangeBarProcessor::new(range))
+    }
+
+    /// Completed bars from a configured `processor` (policy, cuts, checkpoint)
+    fn range_bars_with(self, processor: RangeBarProcessor) -> RangeBarStreamAdapter<Self> {
+        RangeBarStreamAdapter {
+            trades: self,
+            processor,
+            include_incomplete: false,
+            done: false,
+        }
+    }
+}
+
+impl<S> RangeBarStreamExt for S
+where
+    S: Stream,
+    S::Item: Trade,
+{
+}
+
+/// Stream of range bars over a stream of trades
+///
+/// Created by [`RangeBarStreamExt::range_bars`]. The trade stream must be
+/// `Unpin`; pin other streams with `Box::pin` first. After the first error
+/// the stream ends.
+pub struct RangeBarStreamAdapter<S> {
+    trades: S,
+    processor: RangeBarProcessor,
+
+    /// Yield the open bar, force-closed, once the trades end
+    include_incomplete: bool,
+
+    /// Trades ended or an error was yielded
+    done: bool,
+}
+
+impl<S> RangeBarStreamAdapter<S> {
+    /// Also yield the trailing open bar with [`CloseReason::EndOfData`](crate::types::CloseReason::EndOfData)
+    pub fn with_incomplete(mut self) -> Self {
+        self.include_incomplete = true;
+        self
+    }
+
+    /// The underlying processor, e.g. to checkpoint it or inspect the open bar
+    pub fn processor(&self) -> &RangeBarProcessor {
+        &self.processor
+    }
+
+    /// Stop streaming and return the processor with its open bar
+    pub fn into_processor(self) -> RangeBarProcessor {
+        self.processor
+    }
+}
+
+impl<S> Stream for RangeBarStreamAdapter<S>
+where
+    S: Stream + Unpin,
+    S::Item: Trade,
+{
+    type Item = Result<RangeBar, StreamingError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        while let Some(trade) = ready!(Pin::new(&mut this.trades).poll_next(cx)) {
+            match this.processor.process_single_trade(trade) {
+                Ok(Some(bar)) => return Poll::Ready(Some(Ok(bar))),
+                Ok(None) => {}
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(StreamingError::ProcessingError(e.to_string()))));
+                }
+            }
+        }
+
+        this.done = true;
+        let incomplete = if this.include_incomplete {
+            this.processor.finish()
+        } else {
+            None
+        };
+        Poll::Ready(incomplete.map(Ok))
+    }
+}
+
+impl<S> From<RangeBarStreamAdapter<S>> for RangeBarStream
+where
+    S: Stream + Unpin + Send + 'static,
+    S::Item: Trade,
+{
+    fn from(adapter: RangeBarStreamAdapter<S>) -> Self {
+        RangeBarStream::from_stream(adapter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_trades;
+    use futures::{StreamExt, stream};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_stream_matches_batch() {
+        let trades = create_trades();
+        let batch = RangeBarProcessor::new(25)
+            .process_trades_with_incomplete(&trades)
+            .unwrap();
+
+        let bars: Vec<_> = stream::iter(trades.clone())
+            .range_bars(25)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(bars, batch[..batch.len() - 1]);
+
+        // Stored as the processor's stream type
+        let stream: RangeBarStream = stream::iter(trades).range_bars(25).with_incomplete().into();
+        let bars: Vec<_> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(bars, batch);
+    }
+
+    #[tokio::test]
+    async fn test_trades_pulled_only_on_demand() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&pulled);
+        let trades = stream::iter(create_trades()).inspect(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let mut bars = trades.range_bars(25);
+        let first = bars.next().await.unwrap().unwrap();
+        assert_eq!(first.last_id, 2);
+        // Exactly the trades of the first bar, nothing read ahead
+        assert_eq!(pulled.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_processing_error_ends_stream() {
+        let mut trades = create_trades();
+        trades.swap(0, 1);
+
+        let results: Vec<_> = stream::iter(trades).range_bars(25).collect().await;
+        assert!(matches!(
+            results.as_slice(),
+            [Err(StreamingError::ProcessingError(_))]
+        ));
+    }
+}
@@ -1,9 +1,9 @@
-use std::fs;
-use std::io::{Cursor, Read};
-use std::path::Path;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 
 use chrono::{Duration, NaiveDate};
-use csv::{ReaderBuilder, WriterBuilder};
+use csv::{ByteRecord, ReaderBuilder, Writer, WriterBuilder};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use zip::ZipArchive;
@@ -13,7 +13,13 @@ use zip::ZipArchive;
 use sha2::{Digest, Sha256};
 
 // Use library types and statistics module
-use rangebar::{AggTrade, FixedPoint, RangeBar, Settings};
+use rangebar::fixed_point::{ExcessPrecision, FixedPointError};
+use rangebar::{
+    AggTrade, AuditFinding, BarProcessor, BarSink, EventBarProcessor, FixedPoint, IdAuditor,
+    ImbalanceBarConfig, ImbalanceBarKind, ImbalanceBarProcessor, LadderProcessor, NextBarOpen,
+    ProcessingError, ProcessorCheckpoint, RangeBar, RangeBarProcessor, RangeBarProcessorConfig,
+    RenkoProcessor, Scale, Settings, Threshold, TradeScale,
+};
 
 // Legacy statistics support disabled - requires statistics module restructuring
 // #[cfg(feature = "statistics")]
@@ -43,6 +49,19 @@ struct ExportedFiles {
     pub metadata_files: Vec<ExportedFile>,
 }
 
+/// Sidecar describing a bar CSV, whose raw integer columns carry no scale
+#[derive(Debug, Serialize)]
+struct CsvMetadata<'a> {
+    pub csv_file: &'a str,
+    pub symbol: &'a str,
+    pub market_type: &'a str,
+    pub bar_type: BarType,
+    pub target: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<Threshold>,
+    pub scale: TradeScale,
+}
+
 #[derive(Debug, Serialize)]
 struct ExportedFile {
     pub filename: String,
@@ -51,12 +70,13 @@ struct ExportedFile {
     pub market_type: String, // "um", "cm", "spot"
 }
 
+/// One aggTrades row, borrowing the decimal columns from the record buffer
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)] // is_buyer_maker preserved for market microstructure analysis
-struct CsvAggTrade(
+struct CsvAggTrade<'a>(
     u64,                                             // agg_trade_id
-    f64,                                             // price
-    f64,                                             // quantity
+    &'a str,                                         // price
+    &'a str,                                         // quantity
     u64,                                             // first_trade_id
     u64,                                             // last_trade_id
     u64,                                             // timestamp
@@ -68,8 +88,8 @@ fn python_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    match s.as_str() {
+    let s = <&str>::deserialize(deserializer)?;
+    match s {
         "True" | "true" => Ok(true),
         "False" | "false" => Ok(false),
         _ => Err(serde::de::Error::custom(format!(
@@ -93,465 +113,276 @@ fn detect_csv_headers(buffer: &str) -> bool {
     }
 }
 
-impl From<CsvAggTrade> for AggTrade {
-    fn from(csv_trade: CsvAggTrade) -> Self {
-        AggTrade {
-            agg_trade_id: csv_trade.0 as i64, // agg_trade_id
-            price: FixedPoint::from_str(&csv_trade.1.to_string()).unwrap_or(FixedPoint(0)), // price
-            volume: FixedPoint::from_str(&csv_trade.2.to_string()).unwrap_or(FixedPoint(0)), // quantity
-            first_trade_id: csv_trade.3 as i64, // first_trade_id
-            last_trade_id: csv_trade.4 as i64,  // last_trade_id
-            timestamp: csv_trade.5 as i64,      // timestamp
-            is_buyer_maker: csv_trade.6, // is_buyer_maker - CRITICAL: Preserve order flow data
-        }
+impl CsvAggTrade<'_> {
+    /// Convert with prices and quantities in raw units of `scale`
+    ///
+    /// Decimal text is parsed exactly (never through `f64`); values more
+    /// precise than the scale fail rather than being truncated.
+    fn into_agg_trade(self, scale: TradeScale) -> Result<AggTrade, FixedPointError> {
+        let decimal = |text: &str, scale| {
+            FixedPoint::from_bytes(text.as_bytes(), scale, ExcessPrecision::Reject)
+        };
+        Ok(AggTrade {
+            agg_trade_id: self.0 as i64,            // agg_trade_id
+            price: decimal(self.1, scale.price)?,   // price
+            volume: decimal(self.2, scale.volume)?, // quantity
+            first_trade_id: self.3 as i64,          // first_trade_id
+            last_trade_id: self.4 as i64,           // last_trade_id
+            timestamp: self.5 as i64,               // timestamp
+            is_buyer_maker: self.6, // is_buyer_maker - CRITICAL: Preserve order flow data
+        })
     }
 }
 
-// Enhanced range bar processor that exports results
-struct ExportRangeBarProcessor {
-    threshold_bps: u32,
-    current_bar: Option<InternalRangeBar>,
-    completed_bars: Vec<RangeBar>,
-    bar_counter: usize,
+/// Parse an aggTrades CSV at `scale`, reusing one record buffer for every row
+fn parse_agg_trades(
+    buffer: &str,
+    scale: TradeScale,
+    trades: &mut Vec<AggTrade>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(detect_csv_headers(buffer))
+        .from_reader(buffer.as_bytes());
+
+    let mut record = ByteRecord::new();
+    while reader.read_byte_record(&mut record)? {
+        let csv_trade: CsvAggTrade = record.deserialize(None)?;
+        trades.push(csv_trade.into_agg_trade(scale)?);
+    }
+    Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct InternalRangeBar {
-    open_time: i64,
-    close_time: i64,
-    open: FixedPoint,
-    high: FixedPoint,
-    low: FixedPoint,
-    close: FixedPoint,
-    volume: FixedPoint,
-    turnover: i128,
-    trade_count: i64,
-    first_id: i64,
-    last_id: i64,
-    // === MARKET MICROSTRUCTURE ENHANCEMENTS ===
-    /// Volume from buy-side trades (is_buyer_maker = false)
-    buy_volume: FixedPoint,
-    /// Volume from sell-side trades (is_buyer_maker = true)
-    sell_volume: FixedPoint,
-    /// Number of buy-side trades
-    buy_trade_count: i64,
-    /// Number of sell-side trades
-    sell_trade_count: i64,
-    /// Volume Weighted Average Price
-    vwap: FixedPoint,
-    /// Turnover from buy-side trades
-    buy_turnover: i128,
-    /// Turnover from sell-side trades
-    sell_turnover: i128,
+/// Threshold part of output file names: `0025bps`, or `0000.5bps` for fractional bps
+fn threshold_label(threshold: Threshold) -> String {
+    match threshold.whole_bps() {
+        Some(bps) => format!("{:04}bps", bps),
+        None => format!("{:06.1}bps", threshold.as_bps()),
+    }
 }
 
-impl ExportRangeBarProcessor {
-    fn new(threshold_bps: u32) -> Self {
-        // OPTIMIZATION: Pre-allocate vector capacity based on expected range bars
-        // For 0.2% threshold (200bps), expect ~16,000 bars for 3-month dataset
-        let estimated_bars = match threshold_bps {
-            ..=20 => 20_000,  // 0.2% threshold and below
-            21..=50 => 8_000, // 0.3-0.5% threshold
-            51..=80 => 5_000, // 0.6-0.8% threshold
-            _ => 1_000,       // Higher thresholds
-        };
+/// Threshold part of checkpoint names: the labels of every ladder rung, e.g. `0025bps-0050bps`
+fn ladder_label(thresholds: &[Threshold]) -> String {
+    thresholds
+        .iter()
+        .map(|&threshold| threshold_label(threshold))
+        .collect::<Vec<_>>()
+        .join("-")
+}
 
-        Self {
-            threshold_bps,
-            current_bar: None,
-            completed_bars: Vec::with_capacity(estimated_bars),
-            bar_counter: 0,
+/// Parse a comma-separated threshold list (`25` or `25,50,100`), one ladder rung each
+fn parse_thresholds(
+    arg: &str,
+    config: &Settings,
+) -> Result<Vec<Threshold>, Box<dyn std::error::Error>> {
+    let mut thresholds: Vec<Threshold> = Vec::new();
+    for part in arg.split(',') {
+        let threshold = config.algorithm.check_threshold(part.trim().parse()?)?;
+        if thresholds.contains(&threshold) {
+            return Err(format!("Threshold {} given more than once", threshold).into());
         }
+        thresholds.push(threshold);
     }
+    Ok(thresholds)
+}
 
-    #[allow(dead_code)] // Alternative processing method
-    fn process_trades(&mut self, trades: &[AggTrade]) -> Vec<RangeBar> {
-        for trade in trades {
-            self.process_single_trade(trade.clone());
-        }
-
-        let result = self.completed_bars.clone();
-        self.completed_bars.clear();
-        result
-    }
+/// Bar family built by the export (`--bar-type=`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BarType {
+    /// Range bars, one ladder rung per threshold (resumable)
+    Range,
+    /// Renko bricks, one brick size per threshold
+    Renko,
+    /// Bars of a fixed number of trades
+    Tick,
+    /// Bars of a fixed base-asset volume
+    Volume,
+    /// Bars of a fixed turnover
+    Dollar,
+    /// Imbalance or run bars with an adaptive threshold
+    Imbalance(ImbalanceBarKind),
+}
 
-    #[allow(dead_code)]
-    fn process_trades_continuously(&mut self, trades: &[AggTrade]) {
-        // OPTIMIZATION: Remove clone() in hot loop - process by reference
-        for trade in trades {
-            self.process_single_trade_no_clone(trade);
+impl BarType {
+    /// Bar family part of output file names; `rangebar` keeps the historical names
+    fn file_kind(&self) -> &'static str {
+        match self {
+            BarType::Range => "rangebar",
+            BarType::Renko => "renkobar",
+            BarType::Tick => "tickbar",
+            BarType::Volume => "volumebar",
+            BarType::Dollar => "dollarbar",
+            BarType::Imbalance(ImbalanceBarKind::TickImbalance) => "tickimbalancebar",
+            BarType::Imbalance(ImbalanceBarKind::VolumeImbalance) => "volumeimbalancebar",
+            BarType::Imbalance(ImbalanceBarKind::DollarImbalance) => "dollarimbalancebar",
+            BarType::Imbalance(ImbalanceBarKind::TickRun) => "tickrunbar",
+            BarType::Imbalance(ImbalanceBarKind::VolumeRun) => "volumerunbar",
         }
-        // DO NOT clear completed_bars - maintain state for continuous processing
     }
+}
 
-    #[allow(dead_code)]
-    fn get_all_completed_bars(&mut self) -> Vec<RangeBar> {
-        // OPTIMIZATION: Use std::mem::take to avoid clone operation
-        std::mem::take(&mut self.completed_bars)
+impl std::fmt::Display for BarType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BarType::Range => "range",
+            BarType::Renko => "renko",
+            BarType::Tick => "tick",
+            BarType::Volume => "volume",
+            BarType::Dollar => "dollar",
+            BarType::Imbalance(ImbalanceBarKind::TickImbalance) => "tick-imbalance",
+            BarType::Imbalance(ImbalanceBarKind::VolumeImbalance) => "volume-imbalance",
+            BarType::Imbalance(ImbalanceBarKind::DollarImbalance) => "dollar-imbalance",
+            BarType::Imbalance(ImbalanceBarKind::TickRun) => "tick-run",
+            BarType::Imbalance(ImbalanceBarKind::VolumeRun) => "volume-run",
+        };
+        f.write_str(name)
     }
+}
 
-    // OPTIMIZATION: Process trade by reference to avoid cloning
-    #[allow(dead_code)]
-    fn process_single_trade_no_clone(&mut self, trade: &AggTrade) {
-        if self.current_bar.is_none() {
-            // Start new bar - Copy fields directly instead of cloning
-            let trade_turnover = (trade.price.to_f64() * trade.volume.to_f64()) as i128;
-
-            // NOTABUG: Zero-duration bars are valid when a single trade or multiple trades
-            // within the same millisecond breach the threshold. This is legitimate
-            // high-frequency market behavior, not a temporal violation.
-            self.current_bar = Some(InternalRangeBar {
-                open_time: trade.timestamp,
-                close_time: trade.timestamp,
-                open: trade.price, // Copy, not clone
-                high: trade.price,
-                low: trade.price,
-                close: trade.price,
-                volume: trade.volume,
-                turnover: trade_turnover,
-                trade_count: 1,
-                first_id: trade.agg_trade_id,
-                last_id: trade.agg_trade_id,
-                // Market microstructure fields
-                buy_volume: FixedPoint(0),
-                sell_volume: FixedPoint(0),
-                buy_trade_count: 0,
-                sell_trade_count: 0,
-                vwap: trade.price,
-                buy_turnover: 0,
-                sell_turnover: 0,
-            });
-            return;
+impl std::str::FromStr for BarType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "range" => Ok(BarType::Range),
+            "renko" => Ok(BarType::Renko),
+            "tick" => Ok(BarType::Tick),
+            "volume" => Ok(BarType::Volume),
+            "dollar" => Ok(BarType::Dollar),
+            "tick-imbalance" => Ok(BarType::Imbalance(ImbalanceBarKind::TickImbalance)),
+            "volume-imbalance" => Ok(BarType::Imbalance(ImbalanceBarKind::VolumeImbalance)),
+            "dollar-imbalance" => Ok(BarType::Imbalance(ImbalanceBarKind::DollarImbalance)),
+            "tick-run" => Ok(BarType::Imbalance(ImbalanceBarKind::TickRun)),
+            "volume-run" => Ok(BarType::Imbalance(ImbalanceBarKind::VolumeRun)),
+            _ => Err(format!(
+                "bar type must be range, renko, tick, volume, dollar, tick-imbalance, volume-imbalance, dollar-imbalance, tick-run or volume-run, got '{}'",
+                s
+            )),
         }
+    }
+}
 
-        // Process existing bar - work with reference
-        let bar = self.current_bar.as_mut().unwrap();
-        let trade_turnover = (trade.price.to_f64() * trade.volume.to_f64()) as i128;
-
-        // OPTIMIZATION: Direct comparison using integer values for performance
-        let price_val = trade.price.0;
-        let bar_open_val = bar.open.0;
-        let threshold_bps = self.threshold_bps as i64;
-        // CORRECTED: Use BASIS_POINTS_SCALE (10,000) not 1,000,000
-        let upper_threshold = bar_open_val + (bar_open_val * threshold_bps) / 10_000;
-        let lower_threshold = bar_open_val - (bar_open_val * threshold_bps) / 10_000;
-
-        // Update bar with new trade - avoid cloning
-        bar.close_time = trade.timestamp;
-        bar.close = trade.price;
-        bar.volume.0 += trade.volume.0;
-        bar.turnover += trade_turnover;
-        bar.trade_count += 1;
-        bar.last_id = trade.agg_trade_id;
-
-        // Update high/low with direct field assignment (no cloning)
-        if price_val > bar.high.0 {
-            bar.high = trade.price;
-        }
-        if price_val < bar.low.0 {
-            bar.low = trade.price;
-        }
+impl Serialize for BarType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
 
-        // === MARKET MICROSTRUCTURE UPDATES ===
-        // Update buy/sell segregation based on is_buyer_maker
-        if trade.is_buyer_maker {
-            bar.sell_volume.0 += trade.volume.0;
-            bar.sell_turnover += trade_turnover;
-        } else {
-            bar.buy_volume.0 += trade.volume.0;
-            bar.buy_turnover += trade_turnover;
-        }
+/// One exported bar series: its family and the target it was built with
+#[derive(Debug, Clone)]
+struct BarOutput {
+    bar_type: BarType,
+    /// Target part of file names, e.g. `0025bps` or `1000`
+    label: String,
+    /// Range or brick threshold, for the threshold-based families
+    threshold: Option<Threshold>,
+}
 
-        // Check threshold breach
-        if price_val >= upper_threshold || price_val <= lower_threshold {
-            // Close current bar and move to completed
-            let completed_bar = self.current_bar.take().unwrap();
-
-            // OPTIMIZATION: Direct conversion without complex .to_range_bar() method
-            let export_bar = RangeBar {
-                open_time: completed_bar.open_time,
-                close_time: completed_bar.close_time,
-                open: completed_bar.open,
-                high: completed_bar.high,
-                low: completed_bar.low,
-                close: completed_bar.close,
-                volume: completed_bar.volume,
-                turnover: completed_bar.turnover,
-                trade_count: completed_bar.trade_count,
-                first_id: completed_bar.first_id,
-                last_id: completed_bar.last_id,
-                // Market microstructure fields
-                buy_volume: completed_bar.buy_volume,
-                sell_volume: completed_bar.sell_volume,
-                buy_trade_count: 0,       // Would be computed properly
-                sell_trade_count: 0,      // Would be computed properly
-                vwap: completed_bar.open, // Approximation
-                buy_turnover: completed_bar.buy_turnover,
-                sell_turnover: completed_bar.sell_turnover,
-            };
-
-            self.completed_bars.push(export_bar);
-            self.bar_counter += 1;
-
-            // Start new bar with breaching trade
-            let initial_buy_turnover = if trade.is_buyer_maker {
-                0
-            } else {
-                trade_turnover
-            };
-            let initial_sell_turnover = if trade.is_buyer_maker {
-                trade_turnover
-            } else {
-                0
-            };
-
-            // NOTABUG: Zero-duration bars are valid when a single trade or multiple trades
-            // within the same millisecond breach the threshold. This is legitimate
-            // high-frequency market behavior, not a temporal violation.
-            self.current_bar = Some(InternalRangeBar {
-                open_time: trade.timestamp,
-                close_time: trade.timestamp,
-                open: trade.price,
-                high: trade.price,
-                low: trade.price,
-                close: trade.price,
-                volume: trade.volume,
-                turnover: trade_turnover,
-                trade_count: 1,
-                first_id: trade.agg_trade_id,
-                last_id: trade.agg_trade_id,
-                // Market microstructure fields
-                buy_volume: if trade.is_buyer_maker {
-                    FixedPoint(0)
-                } else {
-                    trade.volume
-                },
-                sell_volume: if trade.is_buyer_maker {
-                    trade.volume
-                } else {
-                    FixedPoint(0)
-                },
-                buy_trade_count: if trade.is_buyer_maker { 0 } else { 1 },
-                sell_trade_count: if trade.is_buyer_maker { 1 } else { 0 },
-                vwap: trade.price,
-                buy_turnover: initial_buy_turnover,
-                sell_turnover: initial_sell_turnover,
-            });
+impl BarOutput {
+    /// Range bars of one ladder rung
+    fn range(threshold: Threshold) -> Self {
+        Self {
+            bar_type: BarType::Range,
+            label: threshold_label(threshold),
+            threshold: Some(threshold),
         }
     }
+}
 
-    fn process_single_trade(&mut self, trade: AggTrade) {
-        if self.current_bar.is_none() {
-            // Start new bar
-            let trade_turnover = trade.turnover();
-            let trade_count = trade.trade_count();
-
-            // Segregate order flow based on is_buyer_maker
-            let (buy_volume, sell_volume) = if trade.is_buyer_maker {
-                (FixedPoint(0), trade.volume) // Seller aggressive = sell pressure
-            } else {
-                (trade.volume, FixedPoint(0)) // Buyer aggressive = buy pressure
-            };
-
-            let (buy_trade_count, sell_trade_count) = if trade.is_buyer_maker {
-                (0, trade_count)
-            } else {
-                (trade_count, 0)
-            };
-
-            let (buy_turnover, sell_turnover) = if trade.is_buyer_maker {
-                (0, trade_turnover)
-            } else {
-                (trade_turnover, 0)
-            };
-
-            // NOTABUG: Zero-duration bars are valid when a single trade or multiple trades
-            // within the same millisecond breach the threshold. This is legitimate
-            // high-frequency market behavior, not a temporal violation.
-            self.current_bar = Some(InternalRangeBar {
-                open_time: trade.timestamp,
-                close_time: trade.timestamp,
-                open: trade.price,
-                high: trade.price,
-                low: trade.price,
-                close: trade.price,
-                volume: trade.volume,
-                turnover: trade_turnover,
-                trade_count,
-                first_id: trade.agg_trade_id,
-                last_id: trade.agg_trade_id,
-                // Market microstructure fields
-                buy_volume,
-                sell_volume,
-                buy_trade_count,
-                sell_trade_count,
-                vwap: trade.price, // Initial VWAP equals opening price
-                buy_turnover,
-                sell_turnover,
-            });
-            return;
-        }
-
-        let bar = self.current_bar.as_mut().unwrap();
-
-        // Cache trade metrics for efficiency
-        let trade_turnover = trade.turnover();
-        let trade_count = trade.trade_count();
-
-        // Update bar with new trade
-        bar.close_time = trade.timestamp;
-        bar.close = trade.price;
-        bar.volume.0 += trade.volume.0;
-        bar.turnover += trade_turnover;
-        bar.trade_count += trade_count;
-        bar.last_id = trade.agg_trade_id;
-
-        if trade.price.0 > bar.high.0 {
-            bar.high = trade.price;
-        }
-        if trade.price.0 < bar.low.0 {
-            bar.low = trade.price;
+impl std::fmt::Display for BarOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.threshold {
+            Some(threshold) => write!(f, "{} {}", self.bar_type, threshold),
+            None => write!(f, "{} {}", self.bar_type, self.label),
         }
+    }
+}
 
-        // === MARKET MICROSTRUCTURE INCREMENTAL UPDATES ===
-
-        // Update order flow segregation
-        if trade.is_buyer_maker {
-            // Seller aggressive = sell pressure
-            bar.sell_volume.0 += trade.volume.0;
-            bar.sell_trade_count += trade_count;
-            bar.sell_turnover += trade_turnover;
-        } else {
-            // Buyer aggressive = buy pressure
-            bar.buy_volume.0 += trade.volume.0;
-            bar.buy_trade_count += trade_count;
-            bar.buy_turnover += trade_turnover;
+/// An output with the engine that builds it
+type BarTarget = (BarOutput, Box<dyn BarProcessor>);
+
+/// Parse the comma-separated targets of a non-range bar type into one engine each
+///
+/// Renko takes thresholds like range bars; tick bars a trade count; volume and
+/// dollar bars a decimal amount at the volume and price scale; imbalance and
+/// run bars the threshold of their first bar.
+fn parse_bar_targets(
+    bar_type: BarType,
+    arg: &str,
+    config: &Settings,
+    scale: TradeScale,
+) -> Result<Vec<BarTarget>, Box<dyn std::error::Error>> {
+    let mut targets: Vec<BarTarget> = Vec::new();
+    for part in arg.split(',').map(str::trim) {
+        let mut output = BarOutput {
+            bar_type,
+            label: part.to_string(),
+            threshold: None,
+        };
+        let processor: Box<dyn BarProcessor> = match bar_type {
+            BarType::Range => return Err("range bars are built by the ladder".into()),
+            BarType::Renko => {
+                let threshold = config.algorithm.check_threshold(part.parse()?)?;
+                output.label = threshold_label(threshold);
+                output.threshold = Some(threshold);
+                Box::new(RenkoProcessor::new(threshold))
+            }
+            BarType::Tick => Box::new(EventBarProcessor::ticks(part.parse()?)?.with_scale(scale)),
+            BarType::Volume => Box::new(
+                EventBarProcessor::volume(FixedPoint::parse_scaled(part, scale.volume)?)?
+                    .with_scale(scale),
+            ),
+            BarType::Dollar => Box::new(
+                EventBarProcessor::dollar(FixedPoint::parse_scaled(part, scale.price)?)?
+                    .with_scale(scale),
+            ),
+            BarType::Imbalance(kind) => Box::new(ImbalanceBarProcessor::new(ImbalanceBarConfig {
+                scale,
+                ..ImbalanceBarConfig::new(kind, part.parse()?)
+            })?),
+        };
+        if targets.iter().any(|(other, _)| other.label == output.label) {
+            return Err(format!("Target {} given more than once", part).into());
         }
+        targets.push((output, processor));
+    }
+    Ok(targets)
+}
 
-        // Update VWAP incrementally: VWAP = total_turnover / total_volume
-        if bar.volume.0 > 0 {
-            let vwap_raw = bar.turnover / (bar.volume.0 as i128);
-            bar.vwap = FixedPoint(vwap_raw as i64);
-        }
+/// What to do with bars whose id range crosses an `agg_trade_id` gap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GapPolicy {
+    /// Fail the export before writing any bar file
+    Refuse,
+    /// Print the affected bars and export them unchanged
+    Warn,
+    /// Export with `missing_agg_trade_ids` set on every bar
+    Annotate,
+}
 
-        // Check for breach - convert fixed-point to f64 first
-        let open_price = bar.open.to_f64();
-        let current_price = trade.price.to_f64();
-        // Convert basis points to decimal ratio (10,000 basis points = 100%)
-        let threshold_ratio = self.threshold_bps as f64 / 10_000.0;
-
-        let upper_threshold = open_price * (1.0 + threshold_ratio);
-        let lower_threshold = open_price * (1.0 - threshold_ratio);
-
-        if current_price >= upper_threshold || current_price <= lower_threshold {
-            // Bar is complete - convert to export format
-            let completed_bar = self.current_bar.take().unwrap();
-
-            self.bar_counter += 1;
-
-            let export_bar = RangeBar {
-                open_time: completed_bar.open_time,
-                close_time: completed_bar.close_time,
-                open: completed_bar.open,
-                high: completed_bar.high,
-                low: completed_bar.low,
-                close: completed_bar.close,
-                volume: completed_bar.volume,
-                turnover: completed_bar.turnover,
-                trade_count: completed_bar.trade_count,
-                first_id: completed_bar.first_id,
-                last_id: completed_bar.last_id,
-                // Market microstructure fields
-                buy_volume: completed_bar.buy_volume,
-                sell_volume: completed_bar.sell_volume,
-                buy_trade_count: completed_bar.buy_trade_count,
-                sell_trade_count: completed_bar.sell_trade_count,
-                vwap: completed_bar.vwap,
-                buy_turnover: completed_bar.buy_turnover,
-                sell_turnover: completed_bar.sell_turnover,
-            };
-
-            self.completed_bars.push(export_bar);
-
-            // Start new bar with microstructure initialization
-            let new_trade_turnover = trade.turnover();
-            let new_trade_count = trade.trade_count();
-
-            // Segregate order flow for new bar
-            let (new_buy_volume, new_sell_volume) = if trade.is_buyer_maker {
-                (FixedPoint(0), trade.volume)
-            } else {
-                (trade.volume, FixedPoint(0))
-            };
-
-            let (new_buy_trade_count, new_sell_trade_count) = if trade.is_buyer_maker {
-                (0, new_trade_count)
-            } else {
-                (new_trade_count, 0)
-            };
-
-            let (new_buy_turnover, new_sell_turnover) = if trade.is_buyer_maker {
-                (0, new_trade_turnover)
-            } else {
-                (new_trade_turnover, 0)
-            };
-
-            // NOTABUG: Zero-duration bars are valid when a single trade or multiple trades
-            // within the same millisecond breach the threshold. This is legitimate
-            // high-frequency market behavior, not a temporal violation.
-            self.current_bar = Some(InternalRangeBar {
-                open_time: trade.timestamp,
-                close_time: trade.timestamp,
-                open: trade.price,
-                high: trade.price,
-                low: trade.price,
-                close: trade.price,
-                volume: trade.volume,
-                turnover: new_trade_turnover,
-                trade_count: new_trade_count,
-                first_id: trade.agg_trade_id,
-                last_id: trade.agg_trade_id,
-                // Market microstructure fields
-                buy_volume: new_buy_volume,
-                sell_volume: new_sell_volume,
-                buy_trade_count: new_buy_trade_count,
-                sell_trade_count: new_sell_trade_count,
-                vwap: trade.price, // Initial VWAP equals opening price
-                buy_turnover: new_buy_turnover,
-                sell_turnover: new_sell_turnover,
-            });
+impl std::str::FromStr for GapPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "refuse" => Ok(GapPolicy::Refuse),
+            "warn" => Ok(GapPolicy::Warn),
+            "annotate" => Ok(GapPolicy::Annotate),
+            _ => Err(format!(
+                "gap policy must be 'refuse', 'warn' or 'annotate', got '{}'",
+                s
+            )),
         }
     }
-
-    fn get_incomplete_bar(&mut self) -> Option<RangeBar> {
-        self.current_bar.as_ref().map(|incomplete| RangeBar {
-            open_time: incomplete.open_time,
-            close_time: incomplete.close_time,
-            open: incomplete.open,
-            high: incomplete.high,
-            low: incomplete.low,
-            close: incomplete.close,
-            volume: incomplete.volume,
-            turnover: incomplete.turnover,
-            trade_count: incomplete.trade_count,
-            first_id: incomplete.first_id,
-            last_id: incomplete.last_id,
-            // Market microstructure fields
-            buy_volume: incomplete.buy_volume,
-            sell_volume: incomplete.sell_volume,
-            buy_trade_count: incomplete.buy_trade_count,
-            sell_trade_count: incomplete.sell_trade_count,
-            vwap: incomplete.vwap,
-            buy_turnover: incomplete.buy_turnover,
-            sell_turnover: incomplete.sell_turnover,
-        })
-    }
 }
 
 #[derive(Debug, Serialize)]
 struct ExportResult {
     symbol: String,
-    threshold_bps: u32,
+    bar_type: BarType,
+    /// Target of the bar type, as in the file names
+    target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold_bps: Option<Threshold>,
     date_range: (String, String),
     total_bars: usize,
     total_trades: u64,
@@ -559,12 +390,159 @@ struct ExportResult {
     processing_time_seconds: f64,
     csv_file: String,
     json_file: String,
+    /// Decimals of the raw price and volume integers in the bar files
+    scale: TradeScale,
+}
+
+/// Resume state written after every processed day
+///
+/// A later run with the same arguments picks up at `next_date` and produces
+/// the same output as one uninterrupted run. Completed bars are not part of
+/// the checkpoint: they are appended to one partial file per rung, and the
+/// checkpoint records how much of each file is committed.
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportCheckpoint {
+    /// Export the checkpoint belongs to
+    #[serde(flatten)]
+    job: ExportJob,
+    /// First day not yet processed
+    next_date: NaiveDate,
+    total_trades: u64,
+    /// Committed length of each rung's partial bar file
+    partial_bytes: Vec<u64>,
+    /// Engine state, per rung
+    processors: Vec<ProcessorCheckpoint>,
+    /// Id continuity audit of the trades before `next_date`
+    audit: IdAuditor,
+}
+
+/// Arguments of a range bar export that a checkpoint must match to be resumed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ExportJob {
+    symbol: String,
+    market_type: String,
+    /// Ladder rungs, in output order
+    thresholds_bps: Vec<Threshold>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    /// Decimal scales the trades were parsed at
+    #[serde(default)]
+    scale: TradeScale,
+}
+
+/// State of a range bar export between days: what [`ExportCheckpoint`] records
+struct LadderRun {
+    /// One rung per threshold
+    processor: LadderProcessor,
+    auditor: IdAuditor,
+    /// Committed length of each rung's partial bar file
+    partial_bytes: Vec<u64>,
+    total_trades: u64,
+    /// First day not yet processed
+    next_date: NaiveDate,
+}
+
+impl LadderRun {
+    /// Start `job` from its first day
+    fn new(job: &ExportJob) -> Result<Self, ProcessingError> {
+        // Canonical library engine: exact i128 turnover, BASIS_POINTS_SCALE thresholds,
+        // and the trade after a breach opens the next bar. One ladder rung per
+        // threshold, so each trade is downloaded and parsed once for all of them.
+        let processor = LadderProcessor::with_config(
+            job.thresholds_bps.iter().copied(),
+            RangeBarProcessorConfig {
+                next_bar_open: NextBarOpen::NextTrade,
+            },
+        )?;
+        Ok(Self {
+            processor,
+            auditor: IdAuditor::new(&job.symbol),
+            partial_bytes: vec![0; job.thresholds_bps.len()],
+            total_trades: 0,
+            next_date: job.start_date,
+        })
+    }
+
+    /// Continue `job` where `checkpoint` left it
+    fn resume(
+        job: &ExportJob,
+        checkpoint: ExportCheckpoint,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        if checkpoint.job != *job {
+            return Err("checkpoint does not match this export; remove it to start over".into());
+        }
+        Ok(Self {
+            processor: LadderProcessor::restore(checkpoint.processors)?,
+            auditor: checkpoint.audit,
+            partial_bytes: checkpoint.partial_bytes,
+            total_trades: checkpoint.total_trades,
+            next_date: checkpoint.next_date,
+        })
+    }
+
+    /// Audit one day of trades and feed what survives deduplication to every rung
+    ///
+    /// Returns the number of trades processed.
+    fn process_day<S: BarSink>(
+        &mut self,
+        mut day_trades: Vec<AggTrade>,
+        bar_sinks: &mut [S],
+    ) -> Result<u64, ProcessingError> {
+        // Duplicates would fail the engines' ordering check, so only the audit sees them
+        self.auditor.observe_and_dedup(&mut day_trades);
+        let trades_count = day_trades.len() as u64;
+
+        // CRITICAL FIX: Use existing processor to preserve range bar state across days
+        self.processor.process_trades_into(&day_trades, bar_sinks)?;
+        self.total_trades += trades_count;
+        Ok(trades_count)
+    }
+
+    /// Flush the day's bars and move on to the next day
+    ///
+    /// Bars must be on disk before the checkpoint that commits them.
+    fn commit_day(&mut self, partial_files: &mut [Writer<File>]) -> std::io::Result<()> {
+        for (file, bytes) in partial_files.iter_mut().zip(&mut self.partial_bytes) {
+            file.flush()?;
+            *bytes = file.get_ref().metadata()?.len();
+        }
+        self.next_date += Duration::days(1);
+        Ok(())
+    }
+
+    /// Checkpoint of the days committed so far
+    fn checkpoint(&self, job: &ExportJob) -> ExportCheckpoint {
+        ExportCheckpoint {
+            job: job.clone(),
+            next_date: self.next_date,
+            total_trades: self.total_trades,
+            partial_bytes: self.partial_bytes.clone(),
+            processors: self.processor.checkpoint(),
+            audit: self.auditor.clone(),
+        }
+    }
+
+    /// Every rung's bars: the committed partial file, then the open bar
+    fn finish(
+        &mut self,
+        partial_files: Vec<Writer<File>>,
+        partial_paths: &[PathBuf],
+    ) -> Result<Vec<Vec<RangeBar>>, Box<dyn std::error::Error + Send + Sync>> {
+        drop(partial_files);
+        let mut all_range_bars = partial_paths
+            .iter()
+            .map(|path| read_partial_bars(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.processor.finish_into(&mut all_range_bars)?;
+        Ok(all_range_bars)
+    }
 }
 
 struct RangeBarExporter {
     client: Client,
     output_dir: String,
     market_type: String,
+    scale: TradeScale,
 }
 
 impl RangeBarExporter {
@@ -585,9 +563,16 @@ impl RangeBarExporter {
             client: Client::new(),
             output_dir: validated_output_dir,
             market_type,
+            scale: TradeScale::default(),
         })
     }
 
+    /// Parse prices and quantities at the instrument's decimal `scale`
+    fn with_scale(mut self, scale: TradeScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
     /// Validates output directory path to prevent path traversal attacks
     fn validate_output_directory(output_dir: &str) -> Result<String, Box<dyn std::error::Error>> {
         use std::path::{Component, Path};
@@ -652,14 +637,56 @@ impl RangeBarExporter {
         symbol: &str,
         start_date: NaiveDate,
         end_date: NaiveDate,
-        threshold_bps: u32,
-    ) -> Result<EnhancedExportResult, Box<dyn std::error::Error + Send + Sync>> {
+        thresholds: &[Threshold],
+        gap_policy: GapPolicy,
+    ) -> Result<Vec<EnhancedExportResult>, Box<dyn std::error::Error + Send + Sync>> {
         let start_time = std::time::Instant::now();
-        // Use threshold basis points directly (no conversion needed)
-        let mut processor = ExportRangeBarProcessor::new(threshold_bps);
-        let mut all_range_bars: Vec<RangeBar> = Vec::new(); // Unified boundary-safe processing
-        let mut total_trades = 0u64;
-        let mut current_date = start_date;
+        let date_str = format!(
+            "{}_{}",
+            start_date.format("%Y%m%d"),
+            end_date.format("%Y%m%d")
+        );
+        let checkpoint_filename = format!(
+            "{}_{}_rangebar_{}_{}.checkpoint.json",
+            self.market_type,
+            symbol,
+            date_str,
+            ladder_label(thresholds)
+        );
+
+        let job = ExportJob {
+            symbol: symbol.to_string(),
+            market_type: self.market_type.clone(),
+            thresholds_bps: thresholds.to_vec(),
+            start_date,
+            end_date,
+            scale: self.scale,
+        };
+        let partial_paths: Vec<PathBuf> = thresholds
+            .iter()
+            .map(|&threshold| {
+                Path::new(&self.output_dir).join(format!(
+                    "{}_{}_rangebar_{}_{}.partial.csv",
+                    self.market_type,
+                    symbol,
+                    date_str,
+                    threshold_label(threshold)
+                ))
+            })
+            .collect();
+
+        // Resume an interrupted run of the same export
+        let mut run = match self.load_checkpoint(&checkpoint_filename)? {
+            Some(checkpoint) => {
+                println!(
+                    "♻️  Resuming from checkpoint at {} ({} trades so far)",
+                    checkpoint.next_date, checkpoint.total_trades
+                );
+                LadderRun::resume(&job, checkpoint)
+                    .map_err(|e| format!("Checkpoint {}: {}", checkpoint_filename, e))?
+            }
+            None => LadderRun::new(&job)?,
+        };
 
         // Initialize statistical engine for comprehensive analysis
         // #[cfg(feature = "statistics")]
@@ -674,92 +701,323 @@ impl RangeBarExporter {
         println!("====================");
         println!("📊 Symbol: {}", symbol);
         println!("📅 Date Range: {} to {}", start_date, end_date);
-        println!(
-            "📈 Threshold: {} bps ({}%)",
-            threshold_bps,
-            threshold_bps as f64 / 100.0
-        );
+        for threshold in thresholds {
+            println!("📈 Threshold: {} ({}%)", threshold, threshold.as_percent());
+        }
         println!("📁 Output: {}/", self.output_dir);
 
         // PHASE 1: Process days continuously using boundary-safe mode for deterministic results
         // This unifies both statistics and non-statistics paths to ensure identical algorithm
         println!("   🔄 Phase 1: Processing days continuously (boundary-safe mode)...");
-        while current_date <= end_date {
-            print!("   📊 Loading {}...\r", current_date.format("%Y-%m-%d"));
-
-            match self
-                .load_single_day_trades_boundary_safe(
+        let mut partial_files = open_partial_files(&partial_paths, &run.partial_bytes)?;
+        while run.next_date <= end_date {
+            let date = run.next_date;
+            print!("   📊 Loading {}...\r", date.format("%Y-%m-%d"));
+
+            // A failed day may have left the engines half-way through it, so
+            // stop before checkpointing: a rerun resumes at this same day
+            let trades_count = match self.load_day_trades(symbol, date).await {
+                Ok(day_trades) => run
+                    .process_day(day_trades, &mut partial_files)
+                    .map_err(Into::into),
+                Err(e) => Err(e),
+            }
+            .map_err(|e| {
+                format!(
+                    "{} {}: {}; rerun with the same arguments to resume from this day",
                     symbol,
-                    current_date,
-                    &mut processor,
-                    &mut all_range_bars,
+                    date.format("%Y-%m-%d"),
+                    e
                 )
-                .await
-            {
-                Ok(trades_count) => {
-                    total_trades += trades_count;
-                    println!(
-                        "   📊 {} {} → {} trades loaded (total: {})",
-                        symbol,
-                        current_date.format("%Y-%m-%d"),
-                        trades_count,
-                        total_trades
-                    );
-                }
-                Err(e) => {
-                    eprintln!(
-                        "   ⚠️  {} {}: {}",
-                        symbol,
-                        current_date.format("%Y-%m-%d"),
-                        e
-                    );
-                }
-            }
+            })?;
+            println!(
+                "   📊 {} {} → {} trades loaded (total: {})",
+                symbol,
+                date.format("%Y-%m-%d"),
+                trades_count,
+                run.total_trades
+            );
 
-            current_date += Duration::days(1);
+            run.commit_day(&mut partial_files)?;
+            self.save_checkpoint(&checkpoint_filename, &run.checkpoint(&job))?;
         }
 
-        // PHASE 2: Processing complete - unified boundary-safe algorithm used
+        // PHASE 2-3: Committed bars plus the final incomplete bars (unified handling)
+        let mut all_range_bars = run.finish(partial_files, &partial_paths)?;
         println!(
             "\n   ✅ Boundary-safe processing complete: {} range bars generated",
-            all_range_bars.len()
+            all_range_bars.iter().map(Vec::len).sum::<usize>()
+        );
+
+        // PHASE 4: Id continuity audit, written before any bar file
+        let audit_filename = format!(
+            "{}_{}_rangebar_{}_audit.json",
+            self.market_type, symbol, date_str
+        );
+        let outputs: Vec<_> = thresholds.iter().copied().map(BarOutput::range).collect();
+        let auditor = &run.auditor;
+        self.apply_gap_policy(auditor, &mut all_range_bars, &outputs, gap_policy);
+        fs::write(
+            Path::new(&self.output_dir).join(&audit_filename),
+            serde_json::to_string_pretty(auditor.report())?,
+        )?;
+        if gap_policy == GapPolicy::Refuse {
+            refuse_conflicting_duplicates(auditor, &audit_filename)?;
+            refuse_gap_crossing_bars(auditor, &all_range_bars, &audit_filename)?;
+        }
+
+        let processing_time = start_time.elapsed().as_secs_f64();
+
+        let results = self.export_outputs(
+            symbol,
+            (start_date, end_date),
+            &outputs,
+            &all_range_bars,
+            run.total_trades,
+            processing_time,
+            &audit_filename,
+        )?;
+
+        // Export is complete; a rerun should start from scratch
+        let checkpoint_path = Path::new(&self.output_dir).join(&checkpoint_filename);
+        for path in partial_paths.iter().chain([&checkpoint_path]) {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Export a non-range bar family, one engine per target, in one pass
+    ///
+    /// These engines keep no checkpoint, so an interrupted export starts over.
+    async fn export_symbol_bars(
+        &self,
+        symbol: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        targets: Vec<BarTarget>,
+        gap_policy: GapPolicy,
+    ) -> Result<Vec<EnhancedExportResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let start_time = std::time::Instant::now();
+        let date_str = format!(
+            "{}_{}",
+            start_date.format("%Y%m%d"),
+            end_date.format("%Y%m%d")
         );
+        let (outputs, mut processors): (Vec<_>, Vec<_>) = targets.into_iter().unzip();
 
-        // PHASE 3: Add final incomplete bar if exists (unified handling)
-        if let Some(incomplete_bar) = processor.get_incomplete_bar() {
-            all_range_bars.push(incomplete_bar);
+        println!("🚀 Bar Exporter");
+        println!("==============");
+        println!("📊 Symbol: {}", symbol);
+        println!("📅 Date Range: {} to {}", start_date, end_date);
+        for output in &outputs {
+            println!("📈 Bars: {}", output);
+        }
+        println!("📁 Output: {}/", self.output_dir);
+
+        let mut all_bars = vec![Vec::new(); outputs.len()];
+        let mut auditor = IdAuditor::new(symbol);
+        let mut total_trades = 0u64;
+        let mut current_date = start_date;
+        while current_date <= end_date {
+            print!("   📊 Loading {}...\r", current_date.format("%Y-%m-%d"));
+            let mut day_trades = self
+                .load_day_trades(symbol, current_date)
+                .await
+                .map_err(|e| format!("{} {}: {}", symbol, current_date.format("%Y-%m-%d"), e))?;
+            // Duplicates would fail the engines' ordering check; the audit reports them
+            auditor.observe_and_dedup(&mut day_trades);
+            for (processor, bars) in processors.iter_mut().zip(&mut all_bars) {
+                bars.extend(processor.process_trades(&day_trades)?);
+            }
+            total_trades += day_trades.len() as u64;
             println!(
-                "   📊 Added final incomplete bar (total: {} bars)",
-                all_range_bars.len()
+                "   📊 {} {} → {} trades loaded (total: {})",
+                symbol,
+                current_date.format("%Y-%m-%d"),
+                day_trades.len(),
+                total_trades
             );
+            current_date += Duration::days(1);
+        }
+        for (processor, bars) in processors.iter_mut().zip(&mut all_bars) {
+            bars.extend(processor.finish());
         }
 
-        let processing_time = start_time.elapsed().as_secs_f64();
+        let audit_filename = format!(
+            "{}_{}_{}_{}_audit.json",
+            self.market_type,
+            symbol,
+            outputs[0].bar_type.file_kind(),
+            date_str
+        );
+        self.apply_gap_policy(&auditor, &mut all_bars, &outputs, gap_policy);
+        fs::write(
+            Path::new(&self.output_dir).join(&audit_filename),
+            serde_json::to_string_pretty(auditor.report())?,
+        )?;
+        if gap_policy == GapPolicy::Refuse {
+            refuse_conflicting_duplicates(&auditor, &audit_filename)?;
+            refuse_gap_crossing_bars(&auditor, &all_bars, &audit_filename)?;
+        }
 
-        // Export to CSV and JSON
-        let total_volume: f64 = all_range_bars.iter().map(|b| b.volume.to_f64()).sum();
+        self.export_outputs(
+            symbol,
+            (start_date, end_date),
+            &outputs,
+            &all_bars,
+            total_trades,
+            start_time.elapsed().as_secs_f64(),
+            &audit_filename,
+        )
+    }
+
+    /// Print the audit findings and warn about or annotate bars crossing an id gap
+    fn apply_gap_policy(
+        &self,
+        auditor: &IdAuditor,
+        all_range_bars: &mut [Vec<RangeBar>],
+        outputs: &[BarOutput],
+        gap_policy: GapPolicy,
+    ) {
+        let report = auditor.report();
+        if report.is_clean() {
+            println!("   ✅ Id audit: no gaps or duplicates");
+        }
+
+        for (date, day) in report.days.iter().filter(|(_, day)| !day.is_clean()) {
+            println!(
+                "   ⚠️  Id audit {}: {} agg id gaps ({} ids missing), {} trade id gaps, {} exact / {} conflicting duplicates, {} id regressions",
+                date,
+                day.agg_id_gaps,
+                day.missing_agg_trade_ids,
+                day.trade_id_gaps,
+                day.exact_duplicates,
+                day.conflicting_duplicates,
+                day.id_regressions
+            );
+            if gap_policy != GapPolicy::Warn {
+                continue;
+            }
+            for finding in &day.findings {
+                match finding {
+                    AuditFinding::ConflictingDuplicate { agg_trade_id, .. } => println!(
+                        "   ⚠️  Dropped agg trade {} repeated with a different payload",
+                        agg_trade_id
+                    ),
+                    AuditFinding::IdRegression {
+                        agg_trade_id,
+                        highest_seen,
+                        ..
+                    } => println!(
+                        "   ⚠️  Dropped agg trade {} arriving after id {}",
+                        agg_trade_id, highest_seen
+                    ),
+                    _ => {}
+                }
+            }
+        }
+
+        for (output, bars) in outputs.iter().zip(all_range_bars.iter_mut()) {
+            for bar in bars.iter_mut() {
+                match gap_policy {
+                    GapPolicy::Annotate => auditor.annotate(bar),
+                    GapPolicy::Warn => {
+                        let missing = auditor.missing_in(bar.first_id, bar.last_id);
+                        if missing > 0 {
+                            println!(
+                                "   ⚠️  {} bar {}..={} is missing {} agg trades",
+                                output, bar.first_id, bar.last_id, missing
+                            );
+                        }
+                    }
+                    GapPolicy::Refuse => {}
+                }
+            }
+        }
+    }
+
+    /// Write the files of every output, each listing the shared audit report
+    #[allow(clippy::too_many_arguments)]
+    fn export_outputs(
+        &self,
+        symbol: &str,
+        dates: (NaiveDate, NaiveDate),
+        outputs: &[BarOutput],
+        all_bars: &[Vec<RangeBar>],
+        total_trades: u64,
+        processing_time: f64,
+        audit_filename: &str,
+    ) -> Result<Vec<EnhancedExportResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut results = Vec::with_capacity(outputs.len());
+        for (output, bars) in outputs.iter().zip(all_bars) {
+            let mut result =
+                self.export_bars(symbol, dates, output, bars, total_trades, processing_time)?;
+            result.files.metadata_files.push(ExportedFile {
+                filename: audit_filename.to_string(),
+                format: "json".to_string(),
+                size_bytes: 0, // TODO: Get actual file size
+                market_type: self.market_type.clone(),
+            });
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Write the CSV and JSON files of one output and describe them
+    fn export_bars(
+        &self,
+        symbol: &str,
+        (start_date, end_date): (NaiveDate, NaiveDate),
+        output: &BarOutput,
+        all_range_bars: &[RangeBar],
+        total_trades: u64,
+        processing_time: f64,
+    ) -> Result<EnhancedExportResult, Box<dyn std::error::Error + Send + Sync>> {
         let date_str = format!(
             "{}_{}",
             start_date.format("%Y%m%d"),
             end_date.format("%Y%m%d")
         );
-        // threshold_bps already available as parameter
+
+        // Export to CSV and JSON
+        let total_volume: f64 = all_range_bars
+            .iter()
+            .map(|b| b.volume.to_f64_scaled(self.scale.volume))
+            .sum();
         let csv_filename = format!(
-            "{}_{}_rangebar_{}_{:04}bps.csv",
+            "{}_{}_{}_{}_{}.csv",
             self.market_type, // Always include market type
             symbol,
+            output.bar_type.file_kind(),
             date_str,
-            threshold_bps
+            output.label
         );
         let json_filename = format!(
-            "{}_{}_rangebar_{}_{:04}bps.json",
+            "{}_{}_{}_{}_{}.json",
             self.market_type, // Always include market type
             symbol,
+            output.bar_type.file_kind(),
             date_str,
-            threshold_bps
+            output.label
         );
 
-        self.export_to_csv(&all_range_bars, &csv_filename)?;
+        self.export_to_csv(all_range_bars, &csv_filename)?;
+        let csv_metadata_filename = csv_filename.replace(".csv", ".meta.json");
+        self.export_csv_metadata(
+            &CsvMetadata {
+                csv_file: &csv_filename,
+                symbol,
+                market_type: &self.market_type,
+                bar_type: output.bar_type,
+                target: &output.label,
+                threshold: output.threshold,
+                scale: self.scale,
+            },
+            &csv_metadata_filename,
+        )?;
 
         // Generate comprehensive metadata with statistical analysis
         // #[cfg(feature = "statistics")]
@@ -779,14 +1037,18 @@ impl RangeBarExporter {
         // #[cfg(not(feature = "statistics"))]
         // let metadata = None;
 
-        self.export_to_json_with_metadata(&all_range_bars, &json_filename)?;
+        self.export_to_json_with_metadata(all_range_bars, &json_filename)?;
 
-        println!("\n✅ Export Complete!");
+        println!("\n✅ Export Complete! ({})", output);
         println!("   📊 Total Bars: {}", all_range_bars.len());
         println!("   💰 Total Trades: {}", total_trades);
         println!("   🌊 Total Volume: {:.2}", total_volume);
         println!("   ⚡ Processing Time: {:.1}s", processing_time);
         println!("   📄 CSV: {}/{}", self.output_dir, csv_filename);
+        println!(
+            "   📄 CSV scale: {}/{}",
+            self.output_dir, csv_metadata_filename
+        );
         println!("   📄 JSON: {}/{}", self.output_dir, json_filename);
 
         // #[cfg(feature = "statistics")]
@@ -796,7 +1058,9 @@ impl RangeBarExporter {
 
         let basic_result = ExportResult {
             symbol: symbol.to_string(),
-            threshold_bps,
+            bar_type: output.bar_type,
+            target: output.label.clone(),
+            threshold_bps: output.threshold,
             date_range: (
                 start_date.format("%Y-%m-%d").to_string(),
                 end_date.format("%Y-%m-%d").to_string(),
@@ -807,6 +1071,7 @@ impl RangeBarExporter {
             processing_time_seconds: processing_time,
             csv_file: csv_filename.clone(),
             json_file: json_filename.clone(),
+            scale: self.scale,
         };
 
         let files = ExportedFiles {
@@ -824,7 +1089,12 @@ impl RangeBarExporter {
                     market_type: self.market_type.clone(),
                 },
             ],
-            metadata_files: vec![],
+            metadata_files: vec![ExportedFile {
+                filename: csv_metadata_filename,
+                format: "json".to_string(),
+                size_bytes: 0, // TODO: Get actual file size
+                market_type: self.market_type.clone(),
+            }],
         };
 
         Ok(EnhancedExportResult {
@@ -838,7 +1108,7 @@ impl RangeBarExporter {
     #[allow(dead_code)] // Alternative processing method
     async fn process_single_day(
         &self,
-        processor: &mut ExportRangeBarProcessor,
+        processor: &mut RangeBarProcessor,
         symbol: &str,
         date: NaiveDate,
     ) -> Result<(u64, Vec<RangeBar>), Box<dyn std::error::Error + Send + Sync>> {
@@ -876,19 +1146,11 @@ impl RangeBarExporter {
         let mut buffer = String::with_capacity(8 * 1024 * 1024);
         csv_file.read_to_string(&mut buffer)?;
 
-        let mut reader = ReaderBuilder::new()
-            .has_headers(detect_csv_headers(&buffer))
-            .from_reader(buffer.as_bytes());
-
         let mut all_trades = Vec::new();
-        for result in reader.deserialize() {
-            let csv_trade: CsvAggTrade = result?;
-            let agg_trade: AggTrade = csv_trade.into();
-            all_trades.push(agg_trade);
-        }
+        parse_agg_trades(&buffer, self.scale, &mut all_trades)?;
 
         let trades_count = all_trades.len() as u64;
-        let completed_bars = processor.process_trades(&all_trades);
+        let completed_bars = processor.process_trades(&all_trades)?;
 
         Ok((trades_count, completed_bars))
     }
@@ -1007,17 +1269,9 @@ impl RangeBarExporter {
         let mut buffer = String::with_capacity(8 * 1024 * 1024);
         csv_file.read_to_string(&mut buffer)?;
 
-        let mut reader = ReaderBuilder::new()
-            .has_headers(detect_csv_headers(&buffer))
-            .from_reader(buffer.as_bytes());
-
         // OPTIMIZATION: Pre-allocate daily trades vector (typical day = 1-4M trades)
         let mut day_trades = Vec::with_capacity(2_000_000);
-        for result in reader.deserialize() {
-            let csv_trade: CsvAggTrade = result?;
-            let agg_trade: AggTrade = csv_trade.into();
-            day_trades.push(agg_trade);
-        }
+        parse_agg_trades(&buffer, self.scale, &mut day_trades)?;
 
         // Sort by timestamp to ensure chronological order for continuous processing
         day_trades.sort_by_key(|trade| trade.timestamp);
@@ -1031,15 +1285,12 @@ impl RangeBarExporter {
         Ok(trades_count)
     }
 
-    /// Boundary-safe daily trade processing that preserves range bar state across days
-    /// This method prevents the boundary violation issue by reusing the same processor
-    async fn load_single_day_trades_boundary_safe(
+    /// Download, verify and parse one day of aggTrades
+    async fn load_day_trades(
         &self,
         symbol: &str,
         date: NaiveDate,
-        processor: &mut ExportRangeBarProcessor,
-        all_range_bars: &mut Vec<RangeBar>,
-    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<AggTrade>, Box<dyn std::error::Error + Send + Sync>> {
         let date_str = date.format("%Y-%m-%d");
         let url = format!(
             "https://data.binance.vision/data/{}/daily/aggTrades/{}/{}-aggTrades-{}.zip",
@@ -1074,31 +1325,16 @@ impl RangeBarExporter {
         let mut buffer = String::with_capacity(8 * 1024 * 1024);
         csv_file.read_to_string(&mut buffer)?;
 
-        let mut reader = ReaderBuilder::new()
-            .has_headers(detect_csv_headers(&buffer))
-            .from_reader(buffer.as_bytes());
-
         // OPTIMIZATION: Pre-allocate daily trades vector
         let mut day_trades = Vec::with_capacity(2_000_000);
-        for result in reader.deserialize() {
-            let csv_trade: CsvAggTrade = result?;
-            let agg_trade: AggTrade = csv_trade.into();
-            day_trades.push(agg_trade);
-        }
-
-        let trades_count = day_trades.len() as u64;
-
-        // CRITICAL FIX: Use existing processor to preserve range bar state across days
-        let completed_bars = processor.process_trades(&day_trades);
-        all_range_bars.extend(completed_bars);
-
-        Ok(trades_count)
+        parse_agg_trades(&buffer, self.scale, &mut day_trades)?;
+        Ok(day_trades)
     }
 
     #[allow(dead_code)] // Alternative processing method with statistics
     async fn process_single_day_with_stats(
         &self,
-        processor: &mut ExportRangeBarProcessor,
+        processor: &mut RangeBarProcessor,
         symbol: &str,
         date: NaiveDate,
         all_raw_trades: &mut Vec<AggTrade>,
@@ -1137,25 +1373,46 @@ impl RangeBarExporter {
         let mut buffer = String::with_capacity(8 * 1024 * 1024);
         csv_file.read_to_string(&mut buffer)?;
 
-        let mut reader = ReaderBuilder::new()
-            .has_headers(detect_csv_headers(&buffer))
-            .from_reader(buffer.as_bytes());
-
         // OPTIMIZATION: Pre-allocate daily trades vector (typical day = 1-4M trades)
         let mut day_trades = Vec::with_capacity(2_000_000);
-        for result in reader.deserialize() {
-            let csv_trade: CsvAggTrade = result?;
-            let agg_trade: AggTrade = csv_trade.into();
-            day_trades.push(agg_trade.clone());
-            all_raw_trades.push(agg_trade); // Collect for statistical analysis
-        }
+        parse_agg_trades(&buffer, self.scale, &mut day_trades)?;
+        all_raw_trades.extend_from_slice(&day_trades); // Collect for statistical analysis
 
         let trades_count = day_trades.len() as u64;
-        let completed_bars = processor.process_trades(&day_trades);
+        let completed_bars = processor.process_trades(&day_trades)?;
 
         Ok((trades_count, completed_bars))
     }
 
+    // CHECKPOINT METHODS FOR RESUMING INTERRUPTED EXPORTS
+
+    fn load_checkpoint(
+        &self,
+        filename: &str,
+    ) -> Result<Option<ExportCheckpoint>, Box<dyn std::error::Error + Send + Sync>> {
+        let filepath = Path::new(&self.output_dir).join(filename);
+        if !filepath.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(filepath)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Write the checkpoint atomically so a crash never leaves a truncated file
+    fn save_checkpoint(
+        &self,
+        filename: &str,
+        checkpoint: &ExportCheckpoint,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let filepath = Path::new(&self.output_dir).join(filename);
+        let tmp_filepath = filepath.with_extension("json.tmp");
+
+        fs::write(&tmp_filepath, serde_json::to_string(checkpoint)?)?;
+        fs::rename(tmp_filepath, filepath)?;
+        Ok(())
+    }
+
     fn export_to_csv(
         &self,
         bars: &[RangeBar],
@@ -1172,6 +1429,17 @@ impl RangeBarExporter {
         Ok(())
     }
 
+    /// Write the sidecar recording the scale of a bar CSV's raw integer columns
+    fn export_csv_metadata(
+        &self,
+        metadata: &CsvMetadata,
+        filename: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let filepath = Path::new(&self.output_dir).join(filename);
+        fs::write(filepath, serde_json::to_string_pretty(metadata)?)?;
+        Ok(())
+    }
+
     #[allow(dead_code)] // Basic export method, superseded by export_to_json_with_metadata
     fn export_to_json(
         &self,
@@ -1198,8 +1466,9 @@ impl RangeBarExporter {
         let comprehensive_export = {
             // Simple JSON structure without statistics (statistics module disabled)
             json!({
-                "schema_version": "1.0.0",
+                "schema_version": "1.1.0",
                 "export_type": "basic_rangebar_data",
+                "scale": self.scale,
                 "range_bars": bars,
                 "summary": {
                     "total_bars": bars.len(),
@@ -1225,16 +1494,128 @@ impl RangeBarExporter {
     }
 }
 
+/// Open each rung's partial bar file for appending, cut back to its committed length
+///
+/// Bars written after the last checkpoint belong to a day that will be
+/// processed again, so they are discarded.
+fn open_partial_files(
+    paths: &[PathBuf],
+    committed_bytes: &[u64],
+) -> Result<Vec<Writer<File>>, Box<dyn std::error::Error + Send + Sync>> {
+    if paths.len() != committed_bytes.len() {
+        return Err(
+            "Checkpoint does not match the threshold ladder; remove it to start over".into(),
+        );
+    }
+    paths
+        .iter()
+        .zip(committed_bytes)
+        .map(|(path, &committed)| {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(path)?;
+            if file.metadata()?.len() < committed {
+                return Err(format!(
+                    "Partial bar file {} is shorter than its checkpoint; remove the checkpoint to start over",
+                    path.display()
+                )
+                .into());
+            }
+            file.set_len(committed)?;
+            file.seek(SeekFrom::End(0))?;
+            Ok(WriterBuilder::new().has_headers(false).from_writer(file))
+        })
+        .collect()
+}
+
+/// Bars of one rung's partial file, in completion order
+fn read_partial_bars(
+    path: &Path,
+) -> Result<Vec<RangeBar>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(ReaderBuilder::new()
+        .has_headers(false)
+        .from_path(path)?
+        .deserialize()
+        .collect::<Result<_, _>>()?)
+}
+
+/// Fail the export if ids were repeated with a different payload or went backwards
+///
+/// Exact duplicates are dropped silently under every policy.
+fn refuse_conflicting_duplicates(
+    auditor: &IdAuditor,
+    audit_filename: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let dropped: u64 = auditor
+        .report()
+        .days
+        .values()
+        .map(|day| day.conflicting_duplicates + day.id_regressions)
+        .sum();
+    if dropped == 0 {
+        return Ok(());
+    }
+    Err(format!(
+        "{} conflicting duplicates or id regressions were dropped (see {}); rerun with --gap-policy=warn or --gap-policy=annotate to export without them",
+        dropped, audit_filename
+    )
+    .into())
+}
+
+/// Fail the export if any bar crosses an id gap
+fn refuse_gap_crossing_bars(
+    auditor: &IdAuditor,
+    all_range_bars: &[Vec<RangeBar>],
+    audit_filename: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let crossing = all_range_bars
+        .iter()
+        .flatten()
+        .filter(|bar| auditor.missing_in(bar.first_id, bar.last_id) > 0)
+        .count();
+    if crossing == 0 {
+        return Ok(());
+    }
+    Err(format!(
+        "{} bars cross agg_trade_id gaps (see {}); rerun with --gap-policy=warn or --gap-policy=annotate to export them",
+        crossing, audit_filename
+    )
+    .into())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = std::env::args().collect();
+    let (flags, args): (Vec<String>, Vec<String>) =
+        std::env::args().partition(|arg| arg.starts_with("--"));
     if args.len() < 6 || args.len() > 7 {
         eprintln!(
-            "Usage: {} <symbol> <start_date> <end_date> <threshold_bps> <output_dir> [market_type]",
+            "Usage: {} <symbol> <start_date> <end_date> <thresholds> <output_dir> [market_type] [--bar-type=TYPE] [--gap-policy=refuse|warn|annotate] [--price-decimals=N] [--volume-decimals=N]",
             args[0]
         );
         eprintln!("Market types: spot (default), um (UM Futures)");
-        eprintln!("Threshold: basis points (25 = 0.25%, 80 = 0.80%)");
+        eprintln!(
+            "Threshold: basis points (25 = 0.25%, 80 = 0.80%), fractional bps (0.5bps) or percent (0.25%)"
+        );
+        eprintln!(
+            "Several comma-separated thresholds (25,50,100) are built in one pass, one CSV/JSON pair each"
+        );
+        eprintln!(
+            "Bar types: range (default), renko, tick, volume, dollar, tick-imbalance, volume-imbalance, dollar-imbalance, tick-run, volume-run"
+        );
+        eprintln!(
+            "For other bar types <thresholds> lists their targets: renko brick thresholds, trades per tick bar, volume or turnover per volume/dollar bar, first-bar threshold for imbalance/run bars"
+        );
+        eprintln!(
+            "Interrupted range bar exports resume from the checkpoint left in <output_dir> when rerun with the same arguments"
+        );
+        eprintln!(
+            "Gap policy for bars crossing agg_trade_id gaps: refuse the export, warn (default) or annotate the bars"
+        );
+        eprintln!(
+            "Prices and volumes are exported as raw integers with 8 decimals unless --price-decimals/--volume-decimals say otherwise; the scale is recorded in the summary and in a .meta.json next to each CSV"
+        );
         eprintln!("Examples:");
         eprintln!(
             "  {} BTCUSDT 2025-09-01 2025-09-09 25 ./output           # SPOT (default), 0.25%",
@@ -1248,16 +1629,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "  {} BTCUSDT 2025-09-01 2025-09-09 25 ./output um        # UM Futures, 0.25%",
             args[0]
         );
+        eprintln!(
+            "  {} BTCUSDT 2025-09-01 2025-09-09 25,50,100 ./output    # Ladder: 0.25%, 0.50%, 1.00%",
+            args[0]
+        );
+        eprintln!(
+            "  {} BTCUSDT 2025-09-01 2025-09-09 1000 ./output --bar-type=tick   # 1000-trade bars",
+            args[0]
+        );
         std::process::exit(1);
     }
 
     // Load configuration
-    let _config = Settings::load().unwrap_or_else(|_| Settings::default());
+    let config = Settings::load().unwrap_or_else(|_| Settings::default());
 
     let symbol = &args[1];
     let start_date = NaiveDate::parse_from_str(&args[2], "%Y-%m-%d")?;
     let end_date = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d")?;
-    let threshold_bps: u32 = args[4].parse()?;
+    let mut bar_type = BarType::Range;
+    let mut gap_policy = GapPolicy::Warn;
+    let mut scale = TradeScale::default();
+    for flag in &flags {
+        if let Some(kind) = flag.strip_prefix("--bar-type=") {
+            bar_type = kind.parse()?;
+        } else if let Some(policy) = flag.strip_prefix("--gap-policy=") {
+            gap_policy = policy.parse()?;
+        } else if let Some(decimals) = flag.strip_prefix("--price-decimals=") {
+            scale.price = Scale::new(decimals.parse()?)?;
+        } else if let Some(decimals) = flag.strip_prefix("--volume-decimals=") {
+            scale.volume = Scale::new(decimals.parse()?)?;
+        } else {
+            eprintln!("Error: unknown option '{}'", flag);
+            std::process::exit(1);
+        }
+    }
     let output_dir = args[5].clone();
 
     // Default to "spot", optional "um" for UM Futures
@@ -1276,19 +1681,183 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "spot".to_string()
     };
 
-    let exporter = RangeBarExporter::new(output_dir, market_type)?;
-    let result = exporter
-        .export_symbol_range_bars(symbol, start_date, end_date, threshold_bps)
-        .await
-        .map_err(|e| -> Box<dyn std::error::Error> {
-            Box::new(std::io::Error::other(e.to_string()))
-        })?;
+    let exporter = RangeBarExporter::new(output_dir, market_type)?.with_scale(scale);
+    let results = match bar_type {
+        BarType::Range => {
+            let thresholds = parse_thresholds(&args[4], &config)?;
+            exporter
+                .export_symbol_range_bars(symbol, start_date, end_date, &thresholds, gap_policy)
+                .await
+        }
+        _ => {
+            let targets = parse_bar_targets(bar_type, &args[4], &config, scale)?;
+            exporter
+                .export_symbol_bars(symbol, start_date, end_date, targets, gap_policy)
+                .await
+        }
+    }
+    .map_err(|e| -> Box<dyn std::error::Error> {
+        Box::new(std::io::Error::other(e.to_string()))
+    })?;
 
-    // Export enhanced summary information
+    // Export enhanced summary information (a single object for one threshold)
     let summary_file = format!("{}/export_summary.json", exporter.output_dir);
-    let summary_json = serde_json::to_string_pretty(&result)?;
+    let summary_json = match results.as_slice() {
+        [result] => serde_json::to_string_pretty(result)?,
+        results => serde_json::to_string_pretty(results)?,
+    };
     fs::write(&summary_file, summary_json)?;
     println!("   📄 Enhanced Summary: {}", summary_file);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRADES_PER_DAY: i64 = 400;
+
+    fn test_job(thresholds_bps: &[u32]) -> ExportJob {
+        ExportJob {
+            symbol: "BTCUSDT".to_string(),
+            market_type: "um".to_string(),
+            thresholds_bps: thresholds_bps
+                .iter()
+                .copied()
+                .map(Threshold::from_bps)
+                .collect(),
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 1, 4).unwrap(),
+            scale: TradeScale::default(),
+        }
+    }
+
+    /// One day of a deterministic random walk, with ids running on across days
+    fn day_trades(day: i64) -> Vec<AggTrade> {
+        let mut state = 0x9e37_79b9_u64 + day as u64;
+        let mut price = 5_000_000_000_000 + day * 7_000_000_000;
+        (0..TRADES_PER_DAY)
+            .map(|i| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                price += (state % 7) as i64 * 1_000_000_000 - 3_000_000_000;
+                let id = day * TRADES_PER_DAY + i + 1;
+                AggTrade {
+                    agg_trade_id: id,
+                    price: FixedPoint(price),
+                    volume: FixedPoint(10_000_000 + (state % 90) as i64 * 1_000_000),
+                    first_trade_id: id,
+                    last_trade_id: id,
+                    timestamp: 1_704_067_200_000 + day * 86_400_000 + i * 1_000,
+                    is_buyer_maker: state.is_multiple_of(2),
+                }
+            })
+            .collect()
+    }
+
+    fn partial_paths(dir: &Path, job: &ExportJob) -> Vec<PathBuf> {
+        job.thresholds_bps
+            .iter()
+            .map(|&threshold| dir.join(format!("{}.partial.csv", threshold_label(threshold))))
+            .collect()
+    }
+
+    /// Process `days` of `job` through `run`, committing after each
+    fn run_days(run: &mut LadderRun, partial_files: &mut [Writer<File>], days: &[i64]) {
+        for &day in days {
+            run.process_day(day_trades(day), partial_files).unwrap();
+            run.commit_day(partial_files).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_resume_matches_single_run() {
+        let dir = std::env::temp_dir().join(format!("rangebar-resume-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let job = test_job(&[10, 25]);
+        let days = [0, 1, 2, 3];
+
+        // Uninterrupted run, also checked against the engines without files
+        let single_dir = dir.join("single");
+        fs::create_dir_all(&single_dir).unwrap();
+        let paths = partial_paths(&single_dir, &job);
+        let mut run = LadderRun::new(&job).unwrap();
+        let mut files = open_partial_files(&paths, &run.partial_bytes).unwrap();
+        run_days(&mut run, &mut files, &days);
+        let expected = run.finish(files, &paths).unwrap();
+
+        let mut in_memory = LadderRun::new(&job).unwrap();
+        let mut bars = vec![Vec::new(); job.thresholds_bps.len()];
+        for day in days {
+            in_memory.process_day(day_trades(day), &mut bars).unwrap();
+        }
+        in_memory.processor.finish_into(&mut bars).unwrap();
+        assert_eq!(expected, bars);
+        assert!(expected.iter().all(|rung| rung.len() > 10));
+
+        // Stop after day 2 with day 3 half written, then resume in a fresh run
+        let resumed_dir = dir.join("resumed");
+        fs::create_dir_all(&resumed_dir).unwrap();
+        let paths = partial_paths(&resumed_dir, &job);
+        let mut run = LadderRun::new(&job).unwrap();
+        let mut files = open_partial_files(&paths, &run.partial_bytes).unwrap();
+        run_days(&mut run, &mut files, &days[..2]);
+        let checkpoint = serde_json::to_string(&run.checkpoint(&job)).unwrap();
+        run.process_day(day_trades(days[2]), &mut files).unwrap();
+        for file in &mut files {
+            file.flush().unwrap();
+        }
+        drop((run, files));
+
+        let checkpoint: ExportCheckpoint = serde_json::from_str(&checkpoint).unwrap();
+        assert_eq!(
+            checkpoint.next_date,
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap()
+        );
+        let mut run = LadderRun::resume(&job, checkpoint).unwrap();
+        let mut files = open_partial_files(&paths, &run.partial_bytes).unwrap();
+        run_days(&mut run, &mut files, &days[2..]);
+        assert_eq!(run.total_trades, TRADES_PER_DAY as u64 * days.len() as u64);
+        assert_eq!(run.finish(files, &paths).unwrap(), expected);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resume_refuses_a_different_export() {
+        let job = test_job(&[10, 25]);
+        let run = LadderRun::new(&job).unwrap();
+        let checkpoint = run.checkpoint(&job);
+
+        assert!(LadderRun::resume(&test_job(&[10, 50]), checkpoint).is_err());
+        assert!(
+            LadderRun::resume(
+                &ExportJob {
+                    scale: TradeScale {
+                        price: Scale::new(2).unwrap(),
+                        ..TradeScale::default()
+                    },
+                    ..job.clone()
+                },
+                run.checkpoint(&job)
+            )
+            .is_err()
+        );
+        assert!(LadderRun::resume(&job, run.checkpoint(&job)).is_ok());
+    }
+
+    #[test]
+    fn test_partial_file_shorter_than_checkpoint_is_refused() {
+        let dir = std::env::temp_dir().join(format!("rangebar-short-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("short.partial.csv");
+        fs::write(&path, "").unwrap();
+
+        assert!(open_partial_files(std::slice::from_ref(&path), &[100]).is_err());
+        assert!(open_partial_files(std::slice::from_ref(&path), &[0, 0]).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,615 @@
+//! Fan-out of completed bars to any number of subscribers
+//!
+//! A [`BarBroadcast`] publishes every bar to each [`BarSubscription`], and each
+//! subscription owns a bounded buffer with its own [`LagPolicy`]: a strategy
+//! can hold the producer back while a dashboard drops stale bars, and neither
+//! sees the other's lag. Subscribers may join mid-stream through a cloneable
+//! [`BarSubscriptions`] handle and optionally start with a replay of the most
+//! recent bars.
+//!
+//! [`StreamingProcessor`](crate::StreamingProcessor) publishes through one
+//! broadcast per engine output; see
+//! [`subscribe`](crate::StreamingProcessor::subscribe).
+
+use crate::streaming_processor::{RangeBarStream, StreamingError};
+use crate::types::RangeBar;
+use futures::Stream;
+use futures::task::AtomicWaker;
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+/// What happens when a subscriber's buffer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LagPolicy {
+    /// Hold the producer until the subscriber makes room (lossless)
+    #[default]
+    Block,
+
+    /// Discard the subscriber's oldest buffered bar, counting it
+    DropOldest,
+
+    /// End the subscription; the subscriber drains its buffer, then gets
+    /// [`StreamingError::SubscriberLagged`]
+    Disconnect,
+}
+
+/// Buffer and lag handling of one subscription
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionConfig {
+    /// Bars buffered for the subscriber; zero is raised to one, the smallest
+    /// buffer a bar can be handed over through
+    pub capacity: usize,
+
+    /// Response once the buffer is full
+    pub lag: LagPolicy,
+
+    /// Most recent bars delivered on subscribing, up to the broadcast's
+    /// replay capacity and the buffer capacity
+    pub replay: usize,
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 100,
+            lag: LagPolicy::default(),
+            replay: 0,
+        }
+    }
+}
+
+/// Outcome of publishing one bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PublishReport {
+    /// Subscribers that received the bar
+    pub delivered: usize,
+
+    /// Bars discarded under [`LagPolicy::DropOldest`] to make room
+    pub dropped: usize,
+
+    /// Subscribers disconnected under [`LagPolicy::Disconnect`]
+    pub disconnected: usize,
+}
+
+/// How a subscription ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    /// Publisher closed; nothing more to come
+    Closed,
+
+    /// Disconnected for lagging; reported once, then `Closed`
+    Lagged,
+}
+
+/// Buffer shared by the publisher and one subscriber
+struct Slot {
+    queue: Mutex<SlotQueue>,
+    config: SubscriptionConfig,
+
+    /// Subscriber waiting for a bar
+    bar_waker: AtomicWaker,
+
+    /// Publisher waiting for room ([`LagPolicy::Block`])
+    space_waker: AtomicWaker,
+}
+
+struct SlotQueue {
+    bars: VecDeque<RangeBar>,
+
+    /// Bars discarded under [`LagPolicy::DropOldest`]
+    dropped: u64,
+
+    end: Option<End>,
+
+    /// The subscription has been dropped
+    abandoned: bool,
+}
+
+/// Result of offering a bar to one slot
+enum Offer {
+    Queued,
+    DroppedOldest,
+    Disconnected,
+    Gone,
+}
+
+impl Slot {
+    fn queue(&self) -> MutexGuard<'_, SlotQueue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait until the slot can take `bar` under its lag policy
+    fn poll_offer(&self, bar: &RangeBar, cx: &mut Context<'_>) -> Poll<Offer> {
+        self.space_waker.register(cx.waker());
+        let mut queue = self.queue();
+        if queue.abandoned || queue.end.is_some() {
+            return Poll::Ready(Offer::Gone);
+        }
+
+        let offer = if queue.bars.len() < self.config.capacity {
+            Offer::Queued
+        } else {
+            match self.config.lag {
+                LagPolicy::Block => return Poll::Pending,
+                LagPolicy::DropOldest => {
+                    queue.bars.pop_front();
+                    queue.dropped += 1;
+                    Offer::DroppedOldest
+                }
+                LagPolicy::Disconnect => {
+                    queue.end = Some(End::Lagged);
+                    drop(queue);
+                    self.bar_waker.wake();
+                    return Poll::Ready(Offer::Disconnected);
+                }
+            }
+        };
+        queue.bars.push_back(bar.clone());
+        drop(queue);
+        self.bar_waker.wake();
+        Poll::Ready(offer)
+    }
+
+    fn close(&self) {
+        self.queue().end.get_or_insert(End::Closed);
+        self.bar_waker.wake();
+    }
+}
+
+/// Subscribers and replay history shared by a broadcast and its handles
+struct Hub {
+    state: Mutex<HubState>,
+}
+
+struct HubState {
+    slots: Vec<Arc<Slot>>,
+
+    /// Most recent bars, for replay
+    history: VecDeque<RangeBar>,
+    replay_capacity: usize,
+
+    closed: bool,
+
+    /// Whether anyone has ever subscribed
+    subscribed: bool,
+}
+
+impl Hub {
+    fn state(&self) -> MutexGuard<'_, HubState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn subscribe(&self, config: SubscriptionConfig) -> BarSubscription {
+        let config = SubscriptionConfig {
+            capacity: config.capacity.max(1),
+            ..config
+        };
+        let mut state = self.state();
+        let replay = config.replay.min(config.capacity);
+        let skip = state.history.len().saturating_sub(replay);
+        let slot = Arc::new(Slot {
+            queue: Mutex::new(SlotQueue {
+                bars: state.history.iter().skip(skip).cloned().collect(),
+                dropped: 0,
+                end: state.closed.then_some(End::Closed),
+                abandoned: false,
+            }),
+            config,
+            bar_waker: AtomicWaker::new(),
+            space_waker: AtomicWaker::new(),
+        });
+        state.subscribed = true;
+        if !state.closed {
+            state.slots.push(Arc::clone(&slot));
+        }
+        BarSubscription { slot }
+    }
+}
+
+/// Publishing side of a bar fan-out
+///
+/// Closing or dropping the broadcast ends every subscription once its
+/// buffered bars are read.
+pub struct BarBroadcast {
+    hub: Arc<Hub>,
+}
+
+impl BarBroadcast {
+    /// Create a broadcast keeping the last `replay_capacity` bars for replay
+    pub fn new(replay_capacity: usize) -> Self {
+        Self {
+            hub: Arc::new(Hub {
+                state: Mutex::new(HubState {
+                    slots: Vec::new(),
+                    history: VecDeque::with_capacity(replay_capacity),
+                    replay_capacity,
+                    closed: false,
+                    subscribed: false,
+                }),
+            }),
+        }
+    }
+
+    /// Handle for subscribing from other tasks, e.g. mid-stream
+    pub fn subscriptions(&self) -> BarSubscriptions {
+        BarSubscriptions {
+            hub: Arc::clone(&self.hub),
+        }
+    }
+
+    /// Subscribe to every bar published from now on
+    pub fn subscribe(&self, config: SubscriptionConfig) -> BarSubscription {
+        self.hub.subscribe(config)
+    }
+
+    /// Deliver `bar` to every subscriber
+    ///
+    /// Completes once each subscriber has taken the bar, which only waits for
+    /// [`LagPolicy::Block`] subscribers with a full buffer.
+    pub async fn publish(&self, bar: &RangeBar) -> PublishReport {
+        let slots = {
+            let mut state = self.hub.state();
+            if state.replay_capacity > 0 {
+                if state.history.len() == state.replay_capacity {
+                    state.history.pop_front();
+                }
+                state.history.push_back(bar.clone());
+            }
+            state.slots.clone()
+        };
+
+        let mut report = PublishReport::default();
+        let mut gone = false;
+        for slot in &slots {
+            match poll_fn(|cx| slot.poll_offer(bar, cx)).await {
+                Offer::Queued => report.delivered += 1,
+                Offer::DroppedOldest => {
+                    report.delivered += 1;
+                    report.dropped += 1;
+                }
+                Offer::Disconnected => {
+                    report.disconnected += 1;
+                    gone = true;
+                }
+                Offer::Gone => gone = true,
+            }
+        }
+
+        if gone {
+            self.hub.state().slots.retain(|slot| {
+                let queue = slot.queue();
+                !queue.abandoned && queue.end.is_none()
+            });
+        }
+        report
+    }
+
+    /// End every subscription; later subscribers only get the replay
+    pub fn close(&self) {
+        let slots = {
+            let mut state = self.hub.state();
+            state.closed = true;
+            std::mem::take(&mut state.slots)
+        };
+        for slot in slots {
+            slot.close();
+        }
+    }
+
+    /// Live subscriptions
+    pub fn subscriber_count(&self) -> usize {
+        self.hub.state().slots.len()
+    }
+
+    /// Whether anyone has ever subscribed
+    pub fn has_subscribed(&self) -> bool {
+        self.hub.state().subscribed
+    }
+
+    /// Bars waiting in the buffers of live subscribers
+    pub fn queued_bars(&self) -> usize {
+        self.hub
+            .state()
+            .slots
+            .iter()
+            .map(|slot| slot.queue())
+            .filter(|queue| !queue.abandoned)
+            .map(|queue| queue.bars.len())
+            .sum()
+    }
+
+    /// Wait until a subscriber takes a buffered bar or goes away
+    ///
+    /// Meant for the publishing task, which owns the subscribers' room
+    /// notifications while it is not publishing. Returns at once if no bar
+    /// is buffered.
+    pub async fn bar_taken(&self) {
+        let queued = self.queued_bars();
+        if queued == 0 {
+            return;
+        }
+        poll_fn(|cx| {
+            let slots = self.hub.state().slots.clone();
+            for slot in &slots {
+                slot.space_waker.register(cx.waker());
+            }
+            if self.queued_bars() < queued {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl Drop for BarBroadcast {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Cloneable handle for subscribing to a [`BarBroadcast`]
+#[derive(Clone)]
+pub struct BarSubscriptions {
+    hub: Arc<Hub>,
+}
+
+impl BarSubscriptions {
+    /// Subscribe to every bar published from now on
+    pub fn subscribe(&self, config: SubscriptionConfig) -> BarSubscription {
+        self.hub.subscribe(config)
+    }
+}
+
+/// Receiving side of one subscription
+///
+/// Yields `Ok` bars in publication order. Ends with `None` once the broadcast
+/// closes, or with one [`StreamingError::SubscriberLagged`] first if the
+/// subscriber was disconnected under [`LagPolicy::Disconnect`].
+pub struct BarSubscription {
+    slot: Arc<Slot>,
+}
+
+impl BarSubscription {
+    /// Receive the next bar
+    pub async fn recv(&mut self) -> Option<Result<RangeBar, StreamingError>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll for the next bar
+    pub fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RangeBar, StreamingError>>> {
+        self.slot.bar_waker.register(cx.waker());
+        let mut queue = self.slot.queue();
+        if let Some(bar) = queue.bars.pop_front() {
+            drop(queue);
+            self.slot.space_waker.wake();
+            return Poll::Ready(Some(Ok(bar)));
+        }
+        match queue.end {
+            None => Poll::Pending,
+            Some(End::Closed) => Poll::Ready(None),
+            Some(End::Lagged) => {
+                queue.end = Some(End::Closed);
+                Poll::Ready(Some(Err(StreamingError::SubscriberLagged {
+                    capacity: self.slot.config.capacity,
+                })))
+            }
+        }
+    }
+
+    /// Bars discarded under [`LagPolicy::DropOldest`] so far
+    pub fn dropped(&self) -> u64 {
+        self.slot.queue().dropped
+    }
+
+    /// Bars buffered and not yet received
+    pub fn len(&self) -> usize {
+        self.slot.queue().bars.len()
+    }
+
+    /// Whether no bar is buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Stream for BarSubscription {
+    type Item = Result<RangeBar, StreamingError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_recv(cx)
+    }
+}
+
+impl Drop for BarSubscription {
+    fn drop(&mut self) {
+        self.slot.queue().abandoned = true;
+        // A blocked publisher must not wait for a subscriber that is gone
+        self.slot.space_waker.wake();
+    }
+}
+
+impl From<BarSubscription> for RangeBarStream {
+    fn from(subscription: BarSubscription) -> Self {
+        RangeBarStream::from_stream(subscription)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::FixedPoint;
+    use crate::types::AggTrade;
+    use futures::StreamExt;
+
+    fn create_test_bar(id: i64) -> RangeBar {
+        let trade = AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str("100.0").unwrap(),
+            volume: FixedPoint::from_str("1.0").unwrap(),
+            first_trade_id: id,
+            last_trade_id: id,
+            timestamp: 1_000 + id,
+            is_buyer_maker: false,
+        };
+        RangeBar::new(&trade)
+    }
+
+    fn ids(bars: &[RangeBar]) -> Vec<i64> {
+        bars.iter().map(|bar| bar.first_id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_every_subscriber_gets_every_bar() {
+        let broadcast = BarBroadcast::new(0);
+        let first = broadcast.subscribe(SubscriptionConfig::default());
+        let second = broadcast.subscribe(SubscriptionConfig::default());
+
+        for id in 0..5 {
+            let report = broadcast.publish(&create_test_bar(id)).await;
+            assert_eq!(report.delivered, 2);
+        }
+        broadcast.close();
+
+        for subscription in [first, second] {
+            let bars: Vec<_> = subscription.map(Result::unwrap).collect().await;
+            assert_eq!(ids(&bars), [0, 1, 2, 3, 4]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lag_policies() {
+        let broadcast = BarBroadcast::new(0);
+        let config = |lag| SubscriptionConfig {
+            capacity: 2,
+            lag,
+            replay: 0,
+        };
+        let mut dropping = broadcast.subscribe(config(LagPolicy::DropOldest));
+        let disconnecting = broadcast.subscribe(config(LagPolicy::Disconnect));
+
+        let mut reports = Vec::new();
+        for id in 0..4 {
+            reports.push(broadcast.publish(&create_test_bar(id)).await);
+        }
+        assert_eq!(reports[2].disconnected, 1);
+        assert_eq!(reports[3].dropped, 1);
+        assert_eq!(broadcast.subscriber_count(), 1);
+        broadcast.close();
+
+        assert_eq!(dropping.dropped(), 2);
+        assert_eq!(dropping.recv().await.unwrap().unwrap().first_id, 2);
+
+        let results: Vec<_> = disconnecting.collect().await;
+        assert!(matches!(
+            results.as_slice(),
+            [
+                Ok(_),
+                Ok(_),
+                Err(StreamingError::SubscriberLagged { capacity: 2 })
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_subscriber_holds_publisher() {
+        let broadcast = Arc::new(BarBroadcast::new(0));
+        let mut subscription = broadcast.subscribe(SubscriptionConfig {
+            capacity: 1,
+            ..Default::default()
+        });
+
+        let publisher = Arc::clone(&broadcast);
+        let publish = tokio::spawn(async move {
+            for id in 0..3 {
+                publisher.publish(&create_test_bar(id)).await;
+            }
+        });
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!publish.is_finished(), "publisher must wait for room");
+        for id in 0..3 {
+            assert_eq!(subscription.recv().await.unwrap().unwrap().first_id, id);
+        }
+        publish.await.unwrap();
+
+        // A dropped subscriber releases the publisher
+        drop(subscription);
+        assert_eq!(broadcast.publish(&create_test_bar(3)).await.delivered, 0);
+        assert_eq!(broadcast.subscriber_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_bar_taken_waits_for_the_subscriber() {
+        let broadcast = Arc::new(BarBroadcast::new(0));
+        let mut subscription = broadcast.subscribe(SubscriptionConfig::default());
+        for id in 0..2 {
+            broadcast.publish(&create_test_bar(id)).await;
+        }
+
+        let waiter = Arc::clone(&broadcast);
+        let wait = tokio::spawn(async move { waiter.bar_taken().await });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!wait.is_finished(), "nothing was taken yet");
+
+        subscription.recv().await.unwrap().unwrap();
+        wait.await.unwrap();
+
+        // Bars of a dropped subscriber no longer count as queued
+        drop(subscription);
+        assert_eq!(broadcast.queued_bars(), 0);
+        broadcast.bar_taken().await;
+    }
+
+    #[tokio::test]
+    async fn test_zero_capacity_holds_one_bar() {
+        let broadcast = BarBroadcast::new(0);
+        let mut blocking = broadcast.subscribe(SubscriptionConfig {
+            capacity: 0,
+            ..Default::default()
+        });
+
+        let bar = create_test_bar(0);
+        let publish = broadcast.publish(&bar);
+        let report = tokio::time::timeout(std::time::Duration::from_secs(1), publish)
+            .await
+            .expect("a zero-capacity subscriber must not stall the publisher");
+        assert_eq!(report.delivered, 1);
+        assert_eq!(blocking.recv().await.unwrap().unwrap().first_id, 0);
+    }
+
+    #[tokio::test]
+    async fn test_late_subscriber_replay() {
+        let broadcast = BarBroadcast::new(3);
+        for id in 0..5 {
+            broadcast.publish(&create_test_bar(id)).await;
+        }
+
+        let subscriptions = broadcast.subscriptions();
+        let mut late = subscriptions.subscribe(SubscriptionConfig {
+            replay: 2,
+            ..Default::default()
+        });
+        let mut all = subscriptions.subscribe(SubscriptionConfig {
+            replay: 10,
+            ..Default::default()
+        });
+        broadcast.publish(&create_test_bar(5)).await;
+        drop(broadcast);
+
+        let mut received = Vec::new();
+        while let Some(bar) = late.recv().await {
+            received.push(bar.unwrap());
+        }
+        assert_eq!(ids(&received), [3, 4, 5]);
+        assert_eq!(all.len(), 4, "replay limited to the kept history");
+        assert_eq!(all.recv().await.unwrap().unwrap().first_id, 2);
+    }
+}
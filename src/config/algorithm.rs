@@ -1,19 +1,21 @@
 //! Range bar algorithm configuration
 
-use crate::fixed_point::BASIS_POINTS_SCALE;
+use crate::fixed_point::{FixedPoint, Scale};
+use crate::threshold::{BarRange, Threshold, ThresholdError};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Range bar algorithm configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlgorithmConfig {
-    /// Default threshold in basis points (e.g., 250 = 0.25%)
-    pub default_threshold_bps: u32,
+    /// Default threshold in basis points (e.g., 25 = 0.25%)
+    pub default_threshold_bps: Threshold,
 
-    /// Minimum allowed threshold in basis points
-    pub min_threshold_bps: u32,
+    /// Minimum allowed threshold in basis points (fractional values allowed, e.g. 0.5)
+    pub min_threshold_bps: Threshold,
 
     /// Maximum allowed threshold in basis points
-    pub max_threshold_bps: u32,
+    pub max_threshold_bps: Threshold,
 
     /// Enable fixed-point arithmetic precision validation
     pub validate_precision: bool,
@@ -47,14 +49,19 @@ pub struct AlgorithmConfig {
 
     /// Enable performance metrics collection
     pub collect_performance_metrics: bool,
+
+    /// Price tick size per symbol as a decimal string (e.g. BTCUSDT = "0.10"),
+    /// used for tick-based range bars
+    #[serde(default)]
+    pub tick_sizes: BTreeMap<String, String>,
 }
 
 impl Default for AlgorithmConfig {
     fn default() -> Self {
         Self {
-            default_threshold_bps: 25, // 0.25%
-            min_threshold_bps: 1,      // 0.01%
-            max_threshold_bps: 1000,   // 10%
+            default_threshold_bps: Threshold::from_bps(25), // 0.25%
+            min_threshold_bps: Threshold::from_bps(1),      // 0.01%
+            max_threshold_bps: Threshold::from_bps(1000),   // 10%
             validate_precision: true,
             fixed_point_decimals: 8,
             validate_non_lookahead: true,
@@ -66,6 +73,7 @@ impl Default for AlgorithmConfig {
             processing_batch_size: 100_000,
             enable_memory_optimization: true,
             collect_performance_metrics: false,
+            tick_sizes: BTreeMap::new(),
         }
     }
 }
@@ -73,27 +81,76 @@ impl Default for AlgorithmConfig {
 impl AlgorithmConfig {
     /// Convert basis points to decimal threshold
     pub fn threshold_as_decimal(&self, threshold_bps: Option<u32>) -> f64 {
-        let bps = threshold_bps.unwrap_or(self.default_threshold_bps);
-        bps as f64 / BASIS_POINTS_SCALE as f64
+        threshold_bps
+            .map(Threshold::from_bps)
+            .unwrap_or(self.default_threshold_bps)
+            .as_fraction()
     }
 
     /// Validate threshold is within acceptable bounds
     pub fn validate_threshold(&self, threshold_bps: u32) -> Result<(), String> {
-        if threshold_bps < self.min_threshold_bps {
-            return Err(format!(
-                "Threshold {} bps is below minimum {} bps",
-                threshold_bps, self.min_threshold_bps
-            ));
+        Threshold::checked_from_bps(threshold_bps)
+            .and_then(|threshold| self.check_threshold(threshold))
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Check a threshold against `min_threshold_bps` / `max_threshold_bps`
+    ///
+    /// Zero is always rejected, whatever the configured minimum.
+    pub fn check_threshold(&self, threshold: Threshold) -> Result<Threshold, ThresholdError> {
+        let min = self.min_threshold_bps.max(Threshold::from_decibps(1));
+        if threshold < min {
+            return Err(ThresholdError::BelowMinimum { threshold, min });
+        }
+
+        if threshold > self.max_threshold_bps {
+            return Err(ThresholdError::AboveMaximum {
+                threshold,
+                max: self.max_threshold_bps,
+            });
+        }
+
+        Ok(threshold)
+    }
+
+    /// Look up the configured price tick size for a symbol (case-insensitive)
+    pub fn tick_size(&self, symbol: &str) -> Result<FixedPoint, String> {
+        self.tick_size_scaled(symbol, Scale::DEFAULT)
+    }
+
+    /// [`tick_size`](Self::tick_size) in raw units of the symbol's price `scale`
+    pub fn tick_size_scaled(&self, symbol: &str, scale: Scale) -> Result<FixedPoint, String> {
+        let (_, tick_size) = self
+            .tick_sizes
+            .iter()
+            .find(|(configured, _)| configured.eq_ignore_ascii_case(symbol))
+            .ok_or_else(|| format!("No tick size configured for {}", symbol))?;
+
+        match FixedPoint::parse_scaled(tick_size, scale) {
+            Ok(tick_size) if tick_size.0 > 0 => Ok(tick_size),
+            _ => Err(format!("Invalid tick size '{}' for {}", tick_size, symbol)),
         }
+    }
 
-        if threshold_bps > self.max_threshold_bps {
-            return Err(format!(
-                "Threshold {} bps exceeds maximum {} bps",
-                threshold_bps, self.max_threshold_bps
-            ));
+    /// Range of `ticks` price ticks using the symbol's configured tick size
+    pub fn tick_range(&self, symbol: &str, ticks: u32) -> Result<BarRange, String> {
+        self.tick_range_scaled(symbol, ticks, Scale::DEFAULT)
+    }
+
+    /// [`tick_range`](Self::tick_range) in raw units of the symbol's price `scale`
+    pub fn tick_range_scaled(
+        &self,
+        symbol: &str,
+        ticks: u32,
+        scale: Scale,
+    ) -> Result<BarRange, String> {
+        if ticks == 0 {
+            return Err("Tick range must be at least 1 tick".to_string());
         }
 
-        Ok(())
+        BarRange::checked_ticks(ticks, self.tick_size_scaled(symbol, scale)?)
+            .map_err(|_| format!("{} ticks of {} overflow the price range", ticks, symbol))
     }
 
     /// Get the upper breach threshold for a given price
@@ -151,9 +208,9 @@ mod tests {
     fn test_algorithm_config_defaults() {
         let config = AlgorithmConfig::default();
 
-        assert_eq!(config.default_threshold_bps, 25);
-        assert_eq!(config.min_threshold_bps, 1);
-        assert_eq!(config.max_threshold_bps, 1000);
+        assert_eq!(config.default_threshold_bps, Threshold::from_bps(25));
+        assert_eq!(config.min_threshold_bps, Threshold::from_bps(1));
+        assert_eq!(config.max_threshold_bps, Threshold::from_bps(1000));
         assert_eq!(config.fixed_point_decimals, 8);
         assert!(config.validate_precision);
         assert!(config.validate_non_lookahead);
@@ -188,6 +245,55 @@ mod tests {
         assert!(config.validate_threshold(15000).is_err());
     }
 
+    #[test]
+    fn test_tick_range() {
+        let mut config = AlgorithmConfig::default();
+        config
+            .tick_sizes
+            .insert("btcusdt".to_string(), "0.10".to_string());
+        config
+            .tick_sizes
+            .insert("BADUSDT".to_string(), "0".to_string());
+
+        assert_eq!(
+            config.tick_size("BTCUSDT").unwrap(),
+            FixedPoint::from_str("0.1").unwrap()
+        );
+        assert_eq!(
+            config.tick_range("BTCUSDT", 10).unwrap(),
+            BarRange::absolute(FixedPoint::from_str("1.0").unwrap())
+        );
+
+        assert!(config.tick_range("BTCUSDT", 0).is_err());
+        assert!(config.tick_size("ETHUSDT").is_err());
+        assert!(config.tick_size("BADUSDT").is_err());
+    }
+
+    #[test]
+    fn test_tick_range_scaled() {
+        let mut config = AlgorithmConfig::default();
+        config
+            .tick_sizes
+            .insert("SHIBUSDT".to_string(), "0.000000001".to_string());
+        let fine = Scale::new(12).unwrap();
+
+        // Finer than the default 8 decimals, exact at the instrument's scale
+        assert!(config.tick_size("SHIBUSDT").is_err());
+        assert_eq!(
+            config.tick_size_scaled("SHIBUSDT", fine).unwrap(),
+            FixedPoint(1_000)
+        );
+        assert_eq!(
+            config.tick_range_scaled("SHIBUSDT", 5, fine).unwrap(),
+            BarRange::absolute(FixedPoint(5_000))
+        );
+        assert!(
+            config
+                .tick_size_scaled("SHIBUSDT", Scale::new(6).unwrap())
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_breach_thresholds() {
         let config = AlgorithmConfig::default();
@@ -98,7 +98,10 @@ mod tests {
 
         // Verify all sections are present
         assert_eq!(settings.data.base_url, "https://data.binance.vision/data/");
-        assert_eq!(settings.algorithm.default_threshold_bps, 25);
+        assert_eq!(
+            settings.algorithm.default_threshold_bps,
+            crate::threshold::Threshold::from_bps(25)
+        );
         assert_eq!(
             settings.export.default_output_dir,
             PathBuf::from("./output")
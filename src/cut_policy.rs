@@ -0,0 +1,127 @@
+//! Session-boundary and maximum-size bar cuts
+//!
+//! A [`CutPolicy`] force-closes the open range bar at session boundaries or
+//! once it has lasted too long or holds too many trades, independently of the
+//! threshold. Cut bars carry [`CloseReason::SessionCut`],
+//! [`CloseReason::MaxDuration`] or [`CloseReason::MaxTrades`] and no breach
+//! direction, so they are never mistaken for threshold breaches.
+//!
+//! Cuts are decided when the next trade arrives, before it is added: a bar is
+//! only known to span a boundary or exceed a cap once a later trade shows it.
+//! That trade then opens the next bar, so every trade still produces at most
+//! one completed bar.
+
+use crate::trade::Trade;
+use crate::types::{CloseReason, RangeBar};
+use serde::{Deserialize, Serialize};
+
+/// Milliseconds per UTC day
+const DAY_MS: i64 = 86_400_000;
+
+/// Milliseconds per week
+const WEEK_MS: i64 = 7 * DAY_MS;
+
+/// The Unix epoch fell on a Thursday; shift so weeks start on Monday
+const MONDAY_OFFSET_MS: i64 = 3 * DAY_MS;
+
+/// Where one trading session ends and the next begins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionBoundary {
+    /// Every day at 00:00 UTC
+    UtcDay,
+
+    /// Every Monday at 00:00 UTC
+    UtcWeek,
+
+    /// Custom calendar: session start times in ms since epoch, ascending
+    Calendar(Vec<i64>),
+}
+
+impl SessionBoundary {
+    /// Index of the session containing `timestamp` (ms); equal indices share a session
+    pub fn session_index(&self, timestamp: i64) -> i64 {
+        match self {
+            SessionBoundary::UtcDay => timestamp.div_euclid(DAY_MS),
+            SessionBoundary::UtcWeek => (timestamp + MONDAY_OFFSET_MS).div_euclid(WEEK_MS),
+            SessionBoundary::Calendar(starts) => {
+                starts.partition_point(|&start| start <= timestamp) as i64
+            }
+        }
+    }
+}
+
+/// Force-close rules applied on top of the threshold
+///
+/// The default applies no cuts. Rules are checked in field order, so a bar
+/// that crosses a session boundary and exceeds its duration is a session cut.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CutPolicy {
+    /// Cut the open bar when a trade arrives in a later session
+    pub session: Option<SessionBoundary>,
+
+    /// Cut the open bar when a trade arrives more than this many ms after its open
+    pub max_duration_ms: Option<i64>,
+
+    /// Cut the open bar once it holds this many individual trades (`AggTrade::trade_count`)
+    pub max_trades: Option<i64>,
+}
+
+impl CutPolicy {
+    /// Whether no cut rule is configured
+    pub fn is_none(&self) -> bool {
+        self.session.is_none() && self.max_duration_ms.is_none() && self.max_trades.is_none()
+    }
+
+    /// Why the open `bar` must be cut before `trade` is added, if it must
+    pub fn cut_reason<T: Trade>(&self, bar: &RangeBar, trade: &T) -> Option<CloseReason> {
+        if let Some(ref session) = self.session
+            && session.session_index(trade.timestamp()) != session.session_index(bar.open_time)
+        {
+            return Some(CloseReason::SessionCut);
+        }
+        if let Some(max_duration_ms) = self.max_duration_ms
+            && trade.timestamp() - bar.open_time > max_duration_ms
+        {
+            return Some(CloseReason::MaxDuration);
+        }
+        if let Some(max_trades) = self.max_trades
+            && bar.trade_count >= max_trades
+        {
+            return Some(CloseReason::MaxTrades);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_indices() {
+        // 2024-01-07 (Sunday) 23:59:59.999 and 2024-01-08 (Monday) 00:00 UTC
+        let sunday = 1_704_671_999_999;
+        let monday = 1_704_672_000_000;
+
+        assert_ne!(
+            SessionBoundary::UtcDay.session_index(sunday),
+            SessionBoundary::UtcDay.session_index(monday)
+        );
+        assert_ne!(
+            SessionBoundary::UtcWeek.session_index(sunday),
+            SessionBoundary::UtcWeek.session_index(monday)
+        );
+        assert_eq!(
+            SessionBoundary::UtcWeek.session_index(monday),
+            SessionBoundary::UtcWeek.session_index(monday + 6 * DAY_MS)
+        );
+
+        let calendar = SessionBoundary::Calendar(vec![1_000, 5_000]);
+        let indices: Vec<_> = [0, 999, 1_000, 4_999, 5_000]
+            .iter()
+            .map(|&ts| calendar.session_index(ts))
+            .collect();
+        assert_eq!(indices, vec![0, 0, 1, 1, 2]);
+    }
+}
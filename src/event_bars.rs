@@ -0,0 +1,370 @@
+//! Information-driven bars: tick, volume and dollar bars
+//!
+//! [`EventBarProcessor`] samples a bar whenever the activity inside it reaches
+//! a target instead of when price moves a fixed distance. It shares the range
+//! bar engine's input ([`AggTrade`]), ordering validation and output
+//! ([`RangeBar`], built with [`RangeBar::update_with_trade`]), so the
+//! streaming and export wrappers accept it through [`BarProcessor`].
+//!
+//! The trade that reaches the target belongs to the bar it closes and the next
+//! trade opens the following bar, mirroring range bar breach inclusion.
+
+use crate::fixed_point::FixedPoint;
+use crate::range_bars::{
+    BarProcessor, ProcessingError, closed_at_end_of_data, overflow_at, validate_trade_ordering,
+};
+use crate::types::{AggTrade, CloseReason, RangeBar, TradeScale};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Close rule of an information-driven bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventBarRule {
+    /// Close once the bar holds this many individual trades (`AggTrade::trade_count`)
+    Tick(i64),
+
+    /// Close once the bar's base-asset volume reaches this amount
+    Volume(FixedPoint),
+
+    /// Close once the bar's turnover (`price * volume`) reaches this amount,
+    /// given in raw units of the price scale
+    Dollar(FixedPoint),
+}
+
+impl EventBarRule {
+    /// Whether `bar`, with values at `scale`, has reached the target
+    pub fn is_reached(&self, bar: &RangeBar, scale: TradeScale) -> bool {
+        match *self {
+            EventBarRule::Tick(trades) => bar.trade_count >= trades,
+            EventBarRule::Volume(volume) => bar.volume >= volume,
+            // Turnover carries the price scale times the volume scale
+            EventBarRule::Dollar(turnover) => {
+                bar.turnover >= turnover.0 as i128 * scale.volume.factor() as i128
+            }
+        }
+    }
+
+    /// Check the target is positive
+    fn validate(&self) -> Result<(), ProcessingError> {
+        let positive = match *self {
+            EventBarRule::Tick(trades) => trades > 0,
+            EventBarRule::Volume(target) | EventBarRule::Dollar(target) => target.0 > 0,
+        };
+        if positive {
+            Ok(())
+        } else {
+            Err(ProcessingError::InvalidEventTarget { rule: *self })
+        }
+    }
+}
+
+impl fmt::Display for EventBarRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventBarRule::Tick(trades) => write!(f, "{} trades", trades),
+            EventBarRule::Volume(volume) => write!(f, "{} volume", volume),
+            EventBarRule::Dollar(turnover) => write!(f, "{} turnover", turnover),
+        }
+    }
+}
+
+/// Tick, volume or dollar bar processor
+///
+/// A bar closes on the trade that reaches the target, never part-way through it.
+#[derive(Debug, Clone)]
+pub struct EventBarProcessor {
+    /// Close rule
+    rule: EventBarRule,
+
+    /// Decimal scales of the instrument (dollar targets depend on the volume scale)
+    scale: TradeScale,
+
+    /// Bar currently being built (carried across calls)
+    current_bar: Option<RangeBar>,
+
+    /// Last processed `(timestamp, agg_trade_id)` for cross-call ordering validation
+    last_trade: Option<(i64, i64)>,
+}
+
+impl EventBarProcessor {
+    /// Create processor with the given close rule
+    pub fn new(rule: EventBarRule) -> Result<Self, ProcessingError> {
+        rule.validate()?;
+        Ok(Self {
+            rule,
+            scale: TradeScale::default(),
+            current_bar: None,
+            last_trade: None,
+        })
+    }
+
+    /// Read trades at the instrument's decimal `scale` instead of 8 decimals
+    ///
+    /// Targets are raw values of the same scale.
+    pub fn with_scale(mut self, scale: TradeScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Decimal scales in effect
+    pub fn scale(&self) -> TradeScale {
+        self.scale
+    }
+
+    /// Tick bars of `trades` individual trades
+    pub fn ticks(trades: i64) -> Result<Self, ProcessingError> {
+        Self::new(EventBarRule::Tick(trades))
+    }
+
+    /// Volume bars of `volume` base-asset units
+    pub fn volume(volume: FixedPoint) -> Result<Self, ProcessingError> {
+        Self::new(EventBarRule::Volume(volume))
+    }
+
+    /// Dollar bars of `turnover` quote-asset units
+    pub fn dollar(turnover: FixedPoint) -> Result<Self, ProcessingError> {
+        Self::new(EventBarRule::Dollar(turnover))
+    }
+
+    /// Close rule in effect
+    pub fn rule(&self) -> EventBarRule {
+        self.rule
+    }
+
+    /// Process a single trade and return completed bar if any
+    pub fn process_single_trade(
+        &mut self,
+        trade: AggTrade,
+    ) -> Result<Option<RangeBar>, ProcessingError> {
+        validate_trade_ordering(self.last_trade, std::slice::from_ref(&trade))?;
+        self.process_trade(&trade)
+    }
+
+    /// Process trades into bars (completed bars only)
+    pub fn process_trades(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<RangeBar>, ProcessingError> {
+        validate_trade_ordering(self.last_trade, trades)?;
+
+        let mut bars = Vec::new();
+        for trade in trades {
+            bars.extend(self.process_trade(trade)?);
+        }
+        Ok(bars)
+    }
+
+    /// Get any incomplete bar currently being processed
+    pub fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        self.current_bar.clone()
+    }
+
+    /// Force-close the open bar at end of data
+    pub fn finish(&mut self) -> Option<RangeBar> {
+        self.current_bar.take().map(closed_at_end_of_data)
+    }
+
+    /// Discard the open bar and ordering history, returning to the initial state
+    pub fn reset(&mut self) {
+        self.current_bar = None;
+        self.last_trade = None;
+    }
+
+    /// Advance the state machine by one (already validated) trade
+    ///
+    /// On error nothing changes, so the trade can be retried.
+    fn process_trade(&mut self, trade: &AggTrade) -> Result<Option<RangeBar>, ProcessingError> {
+        match self.current_bar {
+            Some(ref mut bar) => bar.update_with_trade(trade).map_err(overflow_at(trade))?,
+            None => self.current_bar = Some(RangeBar::new(trade)),
+        }
+        self.last_trade = Some((trade.timestamp, trade.agg_trade_id));
+
+        // A single large trade can fill a bar on its own
+        let (rule, scale) = (self.rule, self.scale);
+        if self
+            .current_bar
+            .as_ref()
+            .is_some_and(|bar| rule.is_reached(bar, scale))
+        {
+            return Ok(self.current_bar.take().map(|mut bar| {
+                bar.force_close(CloseReason::TargetReached);
+                bar
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+impl BarProcessor for EventBarProcessor {
+    fn process_trades(&mut self, trades: &[AggTrade]) -> Result<Vec<RangeBar>, ProcessingError> {
+        EventBarProcessor::process_trades(self, trades)
+    }
+
+    fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        EventBarProcessor::get_incomplete_bar(self)
+    }
+
+    fn has_open_bar(&self) -> bool {
+        self.current_bar.is_some()
+    }
+
+    fn finish(&mut self) -> Option<RangeBar> {
+        EventBarProcessor::finish(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::Scale;
+
+    fn create_test_trade(id: i64, price: &str, volume: &str, trades: i64) -> AggTrade {
+        AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str(price).unwrap(),
+            volume: FixedPoint::from_str(volume).unwrap(),
+            first_trade_id: id * 10,
+            last_trade_id: id * 10 + trades - 1,
+            timestamp: 1000 + id,
+            is_buyer_maker: id % 2 == 0,
+        }
+    }
+
+    #[test]
+    fn test_tick_bars_count_individual_trades() {
+        let mut processor = EventBarProcessor::ticks(5).unwrap();
+        let trades = vec![
+            create_test_trade(1, "100.0", "1.0", 2),
+            create_test_trade(2, "101.0", "1.0", 2),
+            create_test_trade(3, "99.0", "1.0", 3), // 7 trades: closes
+            create_test_trade(4, "100.0", "1.0", 5), // Fills a bar on its own
+            create_test_trade(5, "100.5", "1.0", 1),
+        ];
+
+        let bars = processor.process_trades_with_incomplete(&trades).unwrap();
+        assert_eq!(bars.len(), 3);
+
+        assert_eq!((bars[0].first_id, bars[0].last_id), (1, 3));
+        assert_eq!(bars[0].trade_count, 7);
+        assert_eq!(bars[0].high.to_string(), "101.00000000");
+        assert_eq!(bars[0].low.to_string(), "99.00000000");
+        assert_eq!(bars[0].close_reason, Some(CloseReason::TargetReached));
+
+        assert_eq!((bars[1].first_id, bars[1].last_id), (4, 4));
+        assert_eq!(bars[2].close_reason, Some(CloseReason::EndOfData));
+    }
+
+    #[test]
+    fn test_volume_and_dollar_bars() {
+        let trades = vec![
+            create_test_trade(1, "100.0", "0.4", 1),
+            create_test_trade(2, "200.0", "0.6", 1), // 1.0 volume, 100 turnover
+            create_test_trade(3, "100.0", "0.5", 1),
+            create_test_trade(4, "100.0", "0.5", 1), // 1.0 volume, 100 turnover
+        ];
+
+        let mut volume = EventBarProcessor::volume(FixedPoint::from_str("1.0").unwrap()).unwrap();
+        let bars = volume.process_trades(&trades).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].volume.to_string(), "1.00000000");
+        assert_eq!(bars[0].buy_volume.to_string(), "0.40000000");
+        assert_eq!(bars[0].sell_volume.to_string(), "0.60000000");
+
+        let mut dollar = EventBarProcessor::dollar(FixedPoint::from_str("100.0").unwrap()).unwrap();
+        let bars = dollar.process_trades(&trades).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!((bars[0].first_id, bars[0].last_id), (1, 2));
+        assert_eq!(bars[0].vwap.to_string(), "160.00000000");
+        assert_eq!((bars[1].first_id, bars[1].last_id), (3, 4));
+        assert!(dollar.get_incomplete_bar().is_none());
+    }
+
+    #[test]
+    fn test_dollar_bars_at_instrument_scale() {
+        let coarse = TradeScale::default();
+        let fine = TradeScale::new(Scale::new(10).unwrap(), Scale::new(12).unwrap());
+        let trades: Vec<AggTrade> = (1..=40)
+            .map(|i| create_test_trade(i, &format!("{}.5", 100 + i % 5), "0.3", 1))
+            .collect();
+        let fine_trades: Vec<AggTrade> = trades
+            .iter()
+            .map(|trade| trade.rescale(coarse, fine).unwrap())
+            .collect();
+
+        let mut expected = EventBarProcessor::dollar(FixedPoint::from_str("100.0").unwrap())
+            .unwrap()
+            .process_trades(&trades)
+            .unwrap();
+        let target = FixedPoint::parse_scaled("100.0", fine.price).unwrap();
+        let mut dollar = EventBarProcessor::dollar(target).unwrap().with_scale(fine);
+        let bars = dollar.process_trades(&fine_trades).unwrap();
+
+        assert!(expected.len() > 5);
+        for bar in &mut expected {
+            *bar = bar.rescale(coarse, fine).unwrap();
+        }
+        assert_eq!(bars, expected);
+    }
+
+    #[test]
+    fn test_chunked_processing_matches_batch() {
+        let trades: Vec<AggTrade> = (1..=200)
+            .map(|i| create_test_trade(i, &format!("{}.0", 100 + i % 7), "0.3", 1 + i % 3))
+            .collect();
+        let rule = EventBarRule::Volume(FixedPoint::from_str("2.0").unwrap());
+
+        let mut batch = EventBarProcessor::new(rule).unwrap();
+        let expected = batch.process_trades_with_incomplete(&trades).unwrap();
+
+        let mut chunked = EventBarProcessor::new(rule).unwrap();
+        let mut bars = Vec::new();
+        for chunk in trades.chunks(17) {
+            bars.extend(chunked.process_trades(chunk).unwrap());
+        }
+        bars.extend(chunked.finish());
+        assert_eq!(expected, bars);
+    }
+
+    #[test]
+    fn test_overflow_allows_retry() {
+        let mut processor = EventBarProcessor::ticks(10).unwrap();
+        let big = AggTrade {
+            volume: FixedPoint(i64::MAX - 1),
+            ..create_test_trade(1, "100.0", "1.0", 1)
+        };
+        processor.process_single_trade(big).unwrap();
+
+        // The failed trade leaves no trace, so a corrected one takes its place
+        assert!(matches!(
+            processor.process_single_trade(create_test_trade(2, "100.0", "1.0", 1)),
+            Err(ProcessingError::Overflow { agg_trade_id: 2 })
+        ));
+        assert_eq!(processor.get_incomplete_bar().unwrap().trade_count, 1);
+        let fixed = AggTrade {
+            volume: FixedPoint(1),
+            ..create_test_trade(2, "100.0", "1.0", 1)
+        };
+        assert!(processor.process_single_trade(fixed).is_ok());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(matches!(
+            EventBarProcessor::ticks(0),
+            Err(ProcessingError::InvalidEventTarget { .. })
+        ));
+        assert!(EventBarProcessor::volume(FixedPoint(0)).is_err());
+
+        let mut processor = EventBarProcessor::ticks(10).unwrap();
+        processor
+            .process_trades(&[create_test_trade(2, "100.0", "1.0", 1)])
+            .unwrap();
+        assert!(matches!(
+            processor.process_single_trade(create_test_trade(1, "100.0", "1.0", 1)),
+            Err(ProcessingError::UnsortedTrades { .. })
+        ));
+    }
+}
@@ -1,9 +1,21 @@
 //! Fixed-point arithmetic for precise decimal calculations without floating point errors
-
+//!
+//! Operators (`+`, `-`, unary `-`, `Sum`) panic on overflow in every build
+//! profile, so release builds never wrap silently. Fallible code uses the
+//! `checked_*` methods; `saturating_*` and `wrapping_*` make any other
+//! behaviour explicit. Multiplication and division take a [`Rounding`] mode.
+//!
+//! Values are raw integers; the number of decimals they carry is a per
+//! instrument [`Scale`] (8 by default). Bar construction never depends on it,
+//! so it is only needed to parse, format and rescale values.
+
+use crate::threshold::{BreachThresholds, THRESHOLD_SCALE, Threshold};
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
 use std::str::FromStr;
 
 /// Scale factor for 8 decimal places (100,000,000)
@@ -12,6 +24,123 @@ pub const SCALE: i64 = 100_000_000;
 /// Scale factor for basis points calculations (standard: 10,000)
 pub const BASIS_POINTS_SCALE: u32 = 10_000;
 
+/// Decimal places carried by raw [`FixedPoint`] values of one instrument
+///
+/// Serialized as the number of decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Scale(u8);
+
+impl Scale {
+    /// 8 decimals, the [`SCALE`] used by Binance data
+    pub const DEFAULT: Scale = Scale(8);
+
+    /// Most decimals whose factor fits in an `i64`
+    pub const MAX_DECIMALS: u8 = 18;
+
+    /// Scale with `decimals` places (at most [`MAX_DECIMALS`](Self::MAX_DECIMALS))
+    pub const fn new(decimals: u8) -> Result<Scale, FixedPointError> {
+        if decimals > Self::MAX_DECIMALS {
+            return Err(FixedPointError::InvalidScale);
+        }
+        Ok(Scale(decimals))
+    }
+
+    /// Number of decimal places
+    pub const fn decimals(self) -> u8 {
+        self.0
+    }
+
+    /// Raw units per whole unit: `10^decimals`
+    pub const fn factor(self) -> i64 {
+        10_i64.pow(self.0 as u32)
+    }
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TryFrom<u8> for Scale {
+    type Error = FixedPointError;
+
+    fn try_from(decimals: u8) -> Result<Self, Self::Error> {
+        Scale::new(decimals)
+    }
+}
+
+impl From<Scale> for u8 {
+    fn from(scale: Scale) -> u8 {
+        scale.0
+    }
+}
+
+impl fmt::Display for Scale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} decimals", self.0)
+    }
+}
+
+/// What [`FixedPoint::from_bytes`] does with digits finer than the scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExcessPrecision {
+    /// Fail with [`FixedPointError::TooManyDecimals`]
+    #[default]
+    Reject,
+
+    /// Round to the nearest raw unit, ties to even
+    Round,
+
+    /// Drop the extra digits (round toward zero)
+    Truncate,
+}
+
+/// How to round a result that falls between two representable values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Toward negative infinity
+    Floor,
+
+    /// Toward positive infinity
+    Ceil,
+
+    /// To the nearest value, ties to the even one (banker's rounding)
+    HalfEven,
+}
+
+impl Rounding {
+    /// `numerator / denominator` rounded in this mode (`None` if `denominator` is zero)
+    pub fn divide(self, numerator: i128, denominator: i128) -> Option<i128> {
+        let quotient = numerator.checked_div(denominator)?;
+        let remainder = numerator % denominator;
+        if remainder == 0 {
+            return Some(quotient);
+        }
+
+        // Truncation went toward zero; step away from it when rounding requires
+        let negative = (remainder < 0) != (denominator < 0);
+        let away = if negative { -1 } else { 1 };
+        let step = match self {
+            Rounding::Floor => negative,
+            Rounding::Ceil => !negative,
+            Rounding::HalfEven => {
+                let twice = remainder.unsigned_abs() * 2;
+                match twice.cmp(&denominator.unsigned_abs()) {
+                    std::cmp::Ordering::Less => false,
+                    std::cmp::Ordering::Greater => true,
+                    std::cmp::Ordering::Equal => quotient % 2 != 0,
+                }
+            }
+        };
+        Some(if step { quotient + away } else { quotient })
+    }
+}
+
 /// Fixed-point decimal representation using i64 with 8 decimal precision
 ///
 /// This avoids floating point rounding errors while maintaining performance.
@@ -36,82 +165,321 @@ impl FixedPoint {
     /// Result containing FixedPoint or parse error
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Self, FixedPointError> {
-        // Handle empty string
-        if s.is_empty() {
-            return Err(FixedPointError::InvalidFormat);
-        }
+        Self::parse_scaled(s, Scale::DEFAULT)
+    }
+
+    /// Parse a decimal string into raw units of `scale`
+    ///
+    /// Fails with [`FixedPointError::TooManyDecimals`] if `s` is more precise
+    /// than `scale`, and with [`FixedPointError::Overflow`] if it is too large.
+    /// See [`from_bytes`](Self::from_bytes) for the accepted syntax.
+    pub fn parse_scaled(s: &str, scale: Scale) -> Result<Self, FixedPointError> {
+        Self::from_bytes(s.as_bytes(), scale, ExcessPrecision::Reject)
+    }
 
-        // Split on decimal point
-        let parts: Vec<&str> = s.split('.').collect();
-        if parts.len() > 2 {
+    /// Parse ASCII decimal bytes into raw units of `scale`, without allocating
+    ///
+    /// Accepts an optional sign, digits with at most one `.` (either side may
+    /// be empty, not both) and an optional exponent: `-0.5`, `+12`, `.25`,
+    /// `1.5e-5`, `3E2`. Digits finer than `scale` are handled per `excess`;
+    /// trailing zeros are never excess.
+    pub fn from_bytes(
+        bytes: &[u8],
+        scale: Scale,
+        excess: ExcessPrecision,
+    ) -> Result<Self, FixedPointError> {
+        let (negative, unsigned) = match bytes.first() {
+            Some(b'-') => (true, &bytes[1..]),
+            Some(b'+') => (false, &bytes[1..]),
+            _ => (false, bytes),
+        };
+        let (mantissa, exponent) = match unsigned.iter().position(|&b| b == b'e' || b == b'E') {
+            Some(at) => (&unsigned[..at], parse_exponent(&unsigned[at + 1..])?),
+            None => (unsigned, 0),
+        };
+        let (int_digits, frac_digits) = match mantissa.iter().position(|&b| b == b'.') {
+            Some(at) => (&mantissa[..at], &mantissa[at + 1..]),
+            None => (mantissa, &[][..]),
+        };
+        let all_digits = |digits: &[u8]| digits.iter().all(u8::is_ascii_digit);
+        if int_digits.len() + frac_digits.len() == 0
+            || !all_digits(int_digits)
+            || !all_digits(frac_digits)
+        {
             return Err(FixedPointError::InvalidFormat);
         }
 
-        // Parse integer part
-        let integer_part: i64 = parts[0]
-            .parse()
-            .map_err(|_| FixedPointError::InvalidFormat)?;
-
-        // Parse fractional part (if exists)
-        let fractional_part = if parts.len() == 2 {
-            let frac_str = parts[1];
-            if frac_str.len() > 8 {
-                return Err(FixedPointError::TooManyDecimals);
+        // Power of ten of each digit in raw units: >= 0 is kept, -1 decides
+        // rounding, anything lower only matters if non-zero
+        let mut power = int_digits.len() as i64 - 1 + exponent + scale.decimals() as i64;
+        let mut raw: u128 = 0;
+        let mut round_digit = 0;
+        let mut sticky = false;
+        for &byte in int_digits.iter().chain(frac_digits) {
+            let digit = byte - b'0';
+            match power {
+                0.. => {
+                    raw = raw
+                        .checked_mul(10)
+                        .and_then(|raw| raw.checked_add(digit as u128))
+                        .ok_or(FixedPointError::Overflow)?;
+                }
+                -1 => round_digit = digit,
+                _ => sticky |= digit != 0,
             }
+            power -= 1;
+        }
+        // Digits ended above the raw unit: scale up by the missing zeros
+        if power >= 0 && raw != 0 {
+            let shift = u32::try_from(power + 1).map_err(|_| FixedPointError::Overflow)?;
+            raw = 10_u128
+                .checked_pow(shift)
+                .and_then(|factor| raw.checked_mul(factor))
+                .ok_or(FixedPointError::Overflow)?;
+        }
 
-            // Pad with zeros to get exactly 8 decimals
-            let padded = format!("{:0<8}", frac_str);
-            padded
-                .parse::<i64>()
-                .map_err(|_| FixedPointError::InvalidFormat)?
-        } else {
-            0
-        };
-
-        // Combine parts with proper sign handling
-        let result = if integer_part >= 0 {
-            integer_part * SCALE + fractional_part
-        } else {
-            integer_part * SCALE - fractional_part
-        };
+        // Out of range wins over excess precision; rounding can still carry past the limit
+        let limit = i64::MAX as u128 + negative as u128;
+        if raw > limit {
+            return Err(FixedPointError::Overflow);
+        }
+        if round_digit != 0 || sticky {
+            match excess {
+                ExcessPrecision::Reject => return Err(FixedPointError::TooManyDecimals),
+                ExcessPrecision::Truncate => {}
+                ExcessPrecision::Round => {
+                    if round_digit > 5 || (round_digit == 5 && (sticky || raw % 2 == 1)) {
+                        raw += 1;
+                    }
+                }
+            }
+        }
+        if raw > limit {
+            return Err(FixedPointError::Overflow);
+        }
 
-        Ok(FixedPoint(result))
+        // Two's complement: the magnitude 2^63 only exists as a negative value
+        let value = (raw as u64).wrapping_neg() as i64;
+        Ok(FixedPoint(if negative { value } else { raw as i64 }))
     }
 
     /// Convert FixedPoint to string representation with 8 decimal places
     #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
-        let abs_value = self.0.abs();
-        let integer_part = abs_value / SCALE;
-        let fractional_part = abs_value % SCALE;
+        self.to_string_scaled(Scale::DEFAULT)
+    }
+
+    /// Decimal string of raw units of `scale`, with every decimal place written
+    pub fn to_string_scaled(&self, scale: Scale) -> String {
+        let abs_value = self.0.unsigned_abs();
+        let factor = scale.factor() as u64;
+        let integer_part = abs_value / factor;
+        let fractional_part = abs_value % factor;
 
         let sign = if self.0 < 0 { "-" } else { "" };
-        format!("{}{}.{:08}", sign, integer_part, fractional_part)
+        match scale.decimals() {
+            0 => format!("{}{}", sign, integer_part),
+            decimals => format!(
+                "{}{}.{:0width$}",
+                sign,
+                integer_part,
+                fractional_part,
+                width = decimals as usize
+            ),
+        }
+    }
+
+    /// Convert raw units of `from` into raw units of `to` without losing precision
+    ///
+    /// Fails with [`FixedPointError::PrecisionLoss`] if the value has more
+    /// significant decimals than `to` holds, or [`FixedPointError::Overflow`].
+    pub fn rescale(self, from: Scale, to: Scale) -> Result<FixedPoint, FixedPointError> {
+        if to >= from {
+            let factor = 10_i64.pow((to.decimals() - from.decimals()) as u32);
+            return self
+                .0
+                .checked_mul(factor)
+                .map(FixedPoint)
+                .ok_or(FixedPointError::Overflow);
+        }
+        let factor = 10_i64.pow((from.decimals() - to.decimals()) as u32);
+        if self.0 % factor != 0 {
+            return Err(FixedPointError::PrecisionLoss);
+        }
+        Ok(FixedPoint(self.0 / factor))
+    }
+
+    /// Convert raw units of `from` into raw units of `to`, rounding dropped decimals
+    pub fn rescale_rounded(self, from: Scale, to: Scale, rounding: Rounding) -> Option<FixedPoint> {
+        if to >= from {
+            return self.rescale(from, to).ok();
+        }
+        let factor = 10_i64.pow((from.decimals() - to.decimals()) as u32);
+        FixedPoint::checked_ratio(self.0 as i128, factor as i128, rounding)
     }
 
     /// Compute range thresholds for given basis points
     ///
     /// # Arguments
     ///
-    /// * `threshold_bps` - Threshold value (25 for 25 bps)
+    /// * `threshold` - Threshold value (`25` for 25 bps, or a fractional [`Threshold`])
     ///
     /// # Returns
     ///
     /// Tuple of (upper_threshold, lower_threshold)
-    pub fn compute_range_thresholds(&self, threshold_bps: u32) -> (FixedPoint, FixedPoint) {
-        // Calculate threshold delta: price * (threshold_bps / 10,000)
-        let delta = (self.0 as i128 * threshold_bps as i128) / BASIS_POINTS_SCALE as i128;
-        let delta = delta as i64;
+    ///
+    /// # Panics
+    ///
+    /// If a level overflows; see [`checked_range_thresholds`](Self::checked_range_thresholds)
+    pub fn compute_range_thresholds(
+        &self,
+        threshold: impl Into<Threshold>,
+    ) -> (FixedPoint, FixedPoint) {
+        self.compute_breach_levels(BreachThresholds::symmetric(threshold.into()))
+    }
+
+    /// Compute (upper, lower) breach levels for separate up/down distances
+    ///
+    /// # Panics
+    ///
+    /// If a level overflows; see [`checked_breach_levels`](Self::checked_breach_levels)
+    pub fn compute_breach_levels(&self, thresholds: BreachThresholds) -> (FixedPoint, FixedPoint) {
+        self.checked_breach_levels(thresholds)
+            .expect("breach level overflows FixedPoint")
+    }
+
+    /// [`compute_range_thresholds`](Self::compute_range_thresholds), failing on overflow
+    pub fn checked_range_thresholds(
+        &self,
+        threshold: impl Into<Threshold>,
+    ) -> Result<(FixedPoint, FixedPoint), FixedPointError> {
+        self.checked_breach_levels(BreachThresholds::symmetric(threshold.into()))
+    }
+
+    /// [`compute_breach_levels`](Self::compute_breach_levels), failing on overflow
+    pub fn checked_breach_levels(
+        &self,
+        thresholds: BreachThresholds,
+    ) -> Result<(FixedPoint, FixedPoint), FixedPointError> {
+        let upper = self.checked_add(self.threshold_delta(thresholds.up)?);
+        let lower = self.checked_sub(self.threshold_delta(thresholds.down)?);
+
+        upper.zip(lower).ok_or(FixedPointError::Overflow)
+    }
+
+    /// Calculate threshold delta: price * (decibps / 100,000), exact for whole bps
+    ///
+    /// Inexact deltas round down, keeping both levels on or inside the nominal range.
+    fn threshold_delta(&self, threshold: Threshold) -> Result<FixedPoint, FixedPointError> {
+        FixedPoint::checked_ratio(
+            self.0 as i128 * threshold.decibps() as i128,
+            THRESHOLD_SCALE,
+            Rounding::Floor,
+        )
+        .ok_or(FixedPointError::Overflow)
+    }
+
+    /// Raw `numerator / denominator` rounded per `rounding`, if it fits
+    ///
+    /// Used for values computed in wider units, e.g. VWAP as `turnover / volume`.
+    pub fn checked_ratio(
+        numerator: i128,
+        denominator: i128,
+        rounding: Rounding,
+    ) -> Option<FixedPoint> {
+        let raw = rounding.divide(numerator, denominator)?;
+        i64::try_from(raw).ok().map(FixedPoint)
+    }
+
+    /// `self + rhs`, or `None` on overflow
+    pub const fn checked_add(self, rhs: FixedPoint) -> Option<FixedPoint> {
+        match self.0.checked_add(rhs.0) {
+            Some(raw) => Some(FixedPoint(raw)),
+            None => None,
+        }
+    }
+
+    /// `self - rhs`, or `None` on overflow
+    pub const fn checked_sub(self, rhs: FixedPoint) -> Option<FixedPoint> {
+        match self.0.checked_sub(rhs.0) {
+            Some(raw) => Some(FixedPoint(raw)),
+            None => None,
+        }
+    }
+
+    /// `-self`, or `None` on overflow
+    pub const fn checked_neg(self) -> Option<FixedPoint> {
+        match self.0.checked_neg() {
+            Some(raw) => Some(FixedPoint(raw)),
+            None => None,
+        }
+    }
+
+    /// `self * rhs` rounded per `rounding`, or `None` on overflow
+    pub fn checked_mul(self, rhs: FixedPoint, rounding: Rounding) -> Option<FixedPoint> {
+        self.checked_mul_scaled(rhs, Scale::DEFAULT, rounding)
+    }
+
+    /// `self * rhs` with both operands and the result in raw units of `scale`
+    pub fn checked_mul_scaled(
+        self,
+        rhs: FixedPoint,
+        scale: Scale,
+        rounding: Rounding,
+    ) -> Option<FixedPoint> {
+        let product = self.0 as i128 * rhs.0 as i128;
+        FixedPoint::checked_ratio(product, scale.factor() as i128, rounding)
+    }
+
+    /// `self / rhs` rounded per `rounding`, or `None` on overflow or division by zero
+    pub fn checked_div(self, rhs: FixedPoint, rounding: Rounding) -> Option<FixedPoint> {
+        self.checked_div_scaled(rhs, Scale::DEFAULT, rounding)
+    }
+
+    /// `self / rhs` with both operands and the result in raw units of `scale`
+    pub fn checked_div_scaled(
+        self,
+        rhs: FixedPoint,
+        scale: Scale,
+        rounding: Rounding,
+    ) -> Option<FixedPoint> {
+        let numerator = self.0 as i128 * scale.factor() as i128;
+        FixedPoint::checked_ratio(numerator, rhs.0 as i128, rounding)
+    }
+
+    /// `self + rhs`, clamped to the representable range
+    pub const fn saturating_add(self, rhs: FixedPoint) -> FixedPoint {
+        FixedPoint(self.0.saturating_add(rhs.0))
+    }
 
-        let upper = FixedPoint(self.0 + delta);
-        let lower = FixedPoint(self.0 - delta);
+    /// `self - rhs`, clamped to the representable range
+    pub const fn saturating_sub(self, rhs: FixedPoint) -> FixedPoint {
+        FixedPoint(self.0.saturating_sub(rhs.0))
+    }
+
+    /// `self + rhs`, wrapping around on overflow
+    pub const fn wrapping_add(self, rhs: FixedPoint) -> FixedPoint {
+        FixedPoint(self.0.wrapping_add(rhs.0))
+    }
 
-        (upper, lower)
+    /// `self - rhs`, wrapping around on overflow
+    pub const fn wrapping_sub(self, rhs: FixedPoint) -> FixedPoint {
+        FixedPoint(self.0.wrapping_sub(rhs.0))
+    }
+
+    /// `-self`, wrapping around on overflow
+    pub const fn wrapping_neg(self) -> FixedPoint {
+        FixedPoint(self.0.wrapping_neg())
     }
 
     /// Convert to f64 for user-friendly output
     pub fn to_f64(&self) -> f64 {
-        self.0 as f64 / SCALE as f64
+        self.to_f64_scaled(Scale::DEFAULT)
+    }
+
+    /// Convert raw units of `scale` to f64
+    pub fn to_f64_scaled(&self, scale: Scale) -> f64 {
+        self.0 as f64 / scale.factor() as f64
     }
 }
 
@@ -121,6 +489,67 @@ impl fmt::Display for FixedPoint {
     }
 }
 
+impl Add for FixedPoint {
+    type Output = FixedPoint;
+
+    /// # Panics
+    ///
+    /// On overflow, in every build profile
+    fn add(self, rhs: FixedPoint) -> FixedPoint {
+        self.checked_add(rhs).expect("FixedPoint addition overflow")
+    }
+}
+
+impl Sub for FixedPoint {
+    type Output = FixedPoint;
+
+    /// # Panics
+    ///
+    /// On overflow, in every build profile
+    fn sub(self, rhs: FixedPoint) -> FixedPoint {
+        self.checked_sub(rhs)
+            .expect("FixedPoint subtraction overflow")
+    }
+}
+
+impl Neg for FixedPoint {
+    type Output = FixedPoint;
+
+    /// # Panics
+    ///
+    /// On `FixedPoint(i64::MIN)`, in every build profile
+    fn neg(self) -> FixedPoint {
+        self.checked_neg().expect("FixedPoint negation overflow")
+    }
+}
+
+impl AddAssign for FixedPoint {
+    fn add_assign(&mut self, rhs: FixedPoint) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for FixedPoint {
+    fn sub_assign(&mut self, rhs: FixedPoint) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for FixedPoint {
+    /// # Panics
+    ///
+    /// On overflow; fold with [`FixedPoint::checked_add`] to handle it
+    fn sum<I: Iterator<Item = FixedPoint>>(iter: I) -> FixedPoint {
+        iter.fold(FixedPoint(0), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a FixedPoint> for FixedPoint {
+    fn sum<I: Iterator<Item = &'a FixedPoint>>(iter: I) -> FixedPoint {
+        iter.copied().sum()
+    }
+}
+
 impl FromStr for FixedPoint {
     type Err = FixedPointError;
 
@@ -129,23 +558,50 @@ impl FromStr for FixedPoint {
     }
 }
 
+/// Exponent digits after `e`/`E`, clamped far beyond any representable value
+fn parse_exponent(bytes: &[u8]) -> Result<i64, FixedPointError> {
+    const LIMIT: i64 = 1_000_000;
+    let (negative, digits) = match bytes.first() {
+        Some(b'-') => (true, &bytes[1..]),
+        Some(b'+') => (false, &bytes[1..]),
+        _ => (false, bytes),
+    };
+    if digits.is_empty() {
+        return Err(FixedPointError::InvalidFormat);
+    }
+    let mut exponent: i64 = 0;
+    for &byte in digits {
+        if !byte.is_ascii_digit() {
+            return Err(FixedPointError::InvalidFormat);
+        }
+        exponent = (exponent * 10 + (byte - b'0') as i64).min(LIMIT);
+    }
+    Ok(if negative { -exponent } else { exponent })
+}
+
 /// Fixed-point arithmetic errors
 #[derive(Debug, Clone, PartialEq)]
 pub enum FixedPointError {
     /// Invalid number format
     InvalidFormat,
-    /// Too many decimal places (>8)
+    /// More decimal places than the scale holds (8 by default)
     TooManyDecimals,
     /// Arithmetic overflow
     Overflow,
+    /// More than [`Scale::MAX_DECIMALS`] decimal places requested
+    InvalidScale,
+    /// Rescaling would drop non-zero decimals
+    PrecisionLoss,
 }
 
 impl fmt::Display for FixedPointError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             FixedPointError::InvalidFormat => write!(f, "Invalid number format"),
-            FixedPointError::TooManyDecimals => write!(f, "Too many decimal places (max 8)"),
+            FixedPointError::TooManyDecimals => write!(f, "Too many decimal places for the scale"),
             FixedPointError::Overflow => write!(f, "Arithmetic overflow"),
+            FixedPointError::InvalidScale => write!(f, "Scale exceeds 18 decimal places"),
+            FixedPointError::PrecisionLoss => write!(f, "Rescaling would lose precision"),
         }
     }
 }
@@ -160,11 +616,17 @@ impl From<FixedPointError> for PyErr {
                 pyo3::exceptions::PyValueError::new_err("Invalid number format")
             }
             FixedPointError::TooManyDecimals => {
-                pyo3::exceptions::PyValueError::new_err("Too many decimal places (max 8)")
+                pyo3::exceptions::PyValueError::new_err("Too many decimal places for the scale")
             }
             FixedPointError::Overflow => {
                 pyo3::exceptions::PyOverflowError::new_err("Arithmetic overflow")
             }
+            FixedPointError::InvalidScale => {
+                pyo3::exceptions::PyValueError::new_err("Scale exceeds 18 decimal places")
+            }
+            FixedPointError::PrecisionLoss => {
+                pyo3::exceptions::PyValueError::new_err("Rescaling would lose precision")
+            }
         }
     }
 }
@@ -224,6 +686,213 @@ mod tests {
         // 50000 * 0.0025 = 125
         assert_eq!(upper.to_string(), "50125.00000000");
         assert_eq!(lower.to_string(), "49875.00000000");
+
+        // 0.5 bps: 50000 * 0.00005 = 2.5
+        let (upper, lower) = price.compute_range_thresholds(Threshold::from_decibps(5));
+        assert_eq!(upper.to_string(), "50002.50000000");
+        assert_eq!(lower.to_string(), "49997.50000000");
+
+        // +30 / -20 bps
+        let (upper, lower) = price.compute_breach_levels(BreachThresholds::asymmetric(
+            Threshold::from_bps(30),
+            Threshold::from_bps(20),
+        ));
+        assert_eq!(upper.to_string(), "50150.00000000");
+        assert_eq!(lower.to_string(), "49900.00000000");
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let max = FixedPoint(i64::MAX);
+        let one = FixedPoint(SCALE);
+
+        assert_eq!(one + one, FixedPoint(2 * SCALE));
+        assert_eq!(-(one - FixedPoint(3 * SCALE)), FixedPoint(2 * SCALE));
+        assert_eq!(
+            [one, one, one].iter().sum::<FixedPoint>(),
+            FixedPoint(3 * SCALE)
+        );
+
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(FixedPoint(i64::MIN).checked_neg(), None);
+        assert_eq!(max.saturating_add(one), max);
+        assert_eq!(max.wrapping_add(FixedPoint(1)), FixedPoint(i64::MIN));
+
+        let result = std::panic::catch_unwind(|| max + one);
+        assert!(result.is_err(), "operators must not wrap");
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        // 2.5 and 3.5 tie: half-even picks 2 and 4
+        let cases = [
+            (25, 10, (2, 3, 2)),
+            (35, 10, (3, 4, 4)),
+            (-25, 10, (-3, -2, -2)),
+            (26, 10, (2, 3, 3)),
+            (-26, 10, (-3, -2, -3)),
+            (30, -10, (-3, -3, -3)),
+        ];
+        for (numerator, denominator, (floor, ceil, half_even)) in cases {
+            let round = |mode: Rounding| mode.divide(numerator, denominator).unwrap();
+            assert_eq!(
+                (
+                    round(Rounding::Floor),
+                    round(Rounding::Ceil),
+                    round(Rounding::HalfEven)
+                ),
+                (floor, ceil, half_even),
+                "{} / {}",
+                numerator,
+                denominator
+            );
+        }
+        assert_eq!(Rounding::Floor.divide(1, 0), None);
+
+        // 1.5 * 0.00000001 = 0.000000015, between two representable values
+        let (a, b) = (FixedPoint(SCALE + SCALE / 2), FixedPoint(1));
+        assert_eq!(a.checked_mul(b, Rounding::Floor), Some(FixedPoint(1)));
+        assert_eq!(a.checked_mul(b, Rounding::Ceil), Some(FixedPoint(2)));
+        assert_eq!(a.checked_mul(b, Rounding::HalfEven), Some(FixedPoint(2)));
+
+        let third = FixedPoint(SCALE).checked_div(FixedPoint(3 * SCALE), Rounding::Ceil);
+        assert_eq!(third, Some(FixedPoint(33_333_334)));
+        assert_eq!(
+            FixedPoint(SCALE).checked_div(FixedPoint(0), Rounding::Floor),
+            None
+        );
+        assert_eq!(
+            FixedPoint(i64::MAX).checked_mul(FixedPoint(2 * SCALE), Rounding::Floor),
+            None
+        );
+
+        // At 2 decimals 1.50 * 0.03 = 0.045, and 1.00 / 3.00 = 0.333...
+        let cents = Scale::new(2).unwrap();
+        let (a, b) = (FixedPoint(150), FixedPoint(3));
+        assert_eq!(
+            a.checked_mul_scaled(b, cents, Rounding::Floor),
+            Some(FixedPoint(4))
+        );
+        assert_eq!(
+            a.checked_mul_scaled(b, cents, Rounding::Ceil),
+            Some(FixedPoint(5))
+        );
+        assert_eq!(
+            FixedPoint(100).checked_div_scaled(FixedPoint(300), cents, Rounding::Ceil),
+            Some(FixedPoint(34))
+        );
+        assert_eq!(
+            FixedPoint(100).checked_div_scaled(FixedPoint(0), cents, Rounding::Floor),
+            None
+        );
+    }
+
+    #[test]
+    fn test_threshold_overflow_is_detected() {
+        let extreme = FixedPoint(i64::MAX - SCALE);
+        assert_eq!(
+            extreme.checked_range_thresholds(25),
+            Err(FixedPointError::Overflow)
+        );
+        assert!(
+            FixedPoint::from_str("50000.0")
+                .unwrap()
+                .checked_range_thresholds(25)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_scaled_parse_and_rescale() {
+        let fine = Scale::new(12).unwrap();
+        let price = FixedPoint::parse_scaled("0.000000000123", fine).unwrap();
+        assert_eq!(price, FixedPoint(123));
+        assert_eq!(price.to_string_scaled(fine), "0.000000000123");
+        assert_eq!(
+            FixedPoint::from_str("0.000000000123"),
+            Err(FixedPointError::TooManyDecimals)
+        );
+
+        // Upscaling is exact; downscaling only when no decimals are dropped
+        let one_and_half = FixedPoint::from_str("1.5").unwrap();
+        let rescaled = one_and_half.rescale(Scale::DEFAULT, fine).unwrap();
+        assert_eq!(rescaled.to_string_scaled(fine), "1.500000000000");
+        assert_eq!(rescaled.rescale(fine, Scale::DEFAULT), Ok(one_and_half));
+        assert_eq!(
+            price.rescale(fine, Scale::DEFAULT),
+            Err(FixedPointError::PrecisionLoss)
+        );
+        assert_eq!(
+            price.rescale_rounded(fine, Scale::DEFAULT, Rounding::Ceil),
+            Some(FixedPoint(1))
+        );
+
+        let whole = Scale::new(0).unwrap();
+        assert_eq!(FixedPoint::parse_scaled("42", whole), Ok(FixedPoint(42)));
+        assert_eq!(FixedPoint::parse_scaled("42.0", whole), Ok(FixedPoint(42)));
+        assert_eq!(FixedPoint(-42).to_string_scaled(whole), "-42");
+        assert_eq!(
+            FixedPoint::parse_scaled("-0.5", Scale::new(1).unwrap()),
+            Ok(FixedPoint(-5))
+        );
+
+        assert_eq!(Scale::new(19), Err(FixedPointError::InvalidScale));
+        assert_eq!(serde_json::to_string(&fine).unwrap(), "12");
+        assert!(serde_json::from_str::<Scale>("19").is_err());
+    }
+
+    #[test]
+    fn test_from_bytes_syntax() {
+        let parse = |s: &str| FixedPoint::from_str(s).map(|fp| fp.0);
+        assert_eq!(parse("-0.5"), Ok(-SCALE / 2));
+        assert_eq!(parse("+1.25"), Ok(SCALE + SCALE / 4));
+        assert_eq!(parse(".5"), Ok(SCALE / 2));
+        assert_eq!(parse("5."), Ok(5 * SCALE));
+        assert_eq!(parse("1e-5"), Ok(1_000));
+        assert_eq!(parse("-2.5E+3"), Ok(-2_500 * SCALE));
+        assert_eq!(parse("1.123456780000"), Ok(112_345_678)); // Trailing zeros are not excess
+        assert_eq!(parse("0.000000001e1"), Ok(1));
+        assert_eq!(parse("-92233720368.54775808"), Ok(i64::MIN));
+
+        for bad in [
+            "", "-", "+", ".", "e5", "1e", "1e+", "1.2.3", "1,5", " 1", "0x10", "--1",
+        ] {
+            assert_eq!(parse(bad), Err(FixedPointError::InvalidFormat), "{:?}", bad);
+        }
+        assert_eq!(
+            parse("92233720368.54775808"),
+            Err(FixedPointError::Overflow)
+        );
+        assert_eq!(parse("1e400"), Err(FixedPointError::Overflow));
+        assert_eq!(parse("0e400"), Ok(0));
+        assert_eq!(parse("1e-400"), Err(FixedPointError::TooManyDecimals));
+
+        // Excess precision policies
+        let with = |s: &str, excess| FixedPoint::from_bytes(s.as_bytes(), Scale::DEFAULT, excess);
+        assert_eq!(
+            with("0.000000015", ExcessPrecision::Round),
+            Ok(FixedPoint(2))
+        );
+        assert_eq!(
+            with("0.000000025", ExcessPrecision::Round),
+            Ok(FixedPoint(2))
+        );
+        assert_eq!(
+            with("0.0000000251", ExcessPrecision::Round),
+            Ok(FixedPoint(3))
+        );
+        assert_eq!(
+            with("-0.000000019", ExcessPrecision::Truncate),
+            Ok(FixedPoint(-1))
+        );
+        assert_eq!(
+            with("-0.000000019", ExcessPrecision::Round),
+            Ok(FixedPoint(-2))
+        );
+        assert_eq!(
+            with("92233720368.547758075", ExcessPrecision::Round),
+            Err(FixedPointError::Overflow)
+        );
     }
 
     #[test]
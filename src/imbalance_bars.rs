@@ -0,0 +1,492 @@
+//! Imbalance and run bars from order-flow sign
+//!
+//! López de Prado style information-driven bars. Each trade is signed by its
+//! aggressor (`is_buyer_maker == false` is a buy, `+1`; otherwise a sell,
+//! `-1`) and a bar closes once the order flow inside it exceeds what recent
+//! history leads us to expect:
+//!
+//! - **Imbalance bars** close when `|buy - sell| >= E[T] * |E[buy/T] - E[sell/T]|`
+//! - **Run bars** close when `max(buy, sell) >= E[T] * max(E[buy/T], E[sell/T])`
+//!
+//! where `buy`/`sell` are trade counts, volumes or turnovers depending on the
+//! [`ImbalanceBarKind`], `T` is the number of individual trades in a bar and
+//! the expectations are EWMAs over **completed bars only**. The threshold for
+//! a bar is fixed when it opens, so nothing inside the bar feeds back into its
+//! own close rule. It never drops below a fraction of the initial threshold:
+//! balanced flow drives `|E[buy/T] - E[sell/T]|` towards zero, which would
+//! otherwise close every following trade as its own bar.
+//!
+//! The buy/sell totals are the [`RangeBar`] microstructure fields, accumulated
+//! by [`RangeBar::update_with_trade`], so the output is the usual bar format.
+
+use crate::range_bars::{
+    BarProcessor, ProcessingError, closed_at_end_of_data, overflow_at, validate_trade_ordering,
+};
+use crate::types::{AggTrade, CloseReason, RangeBar, TradeScale};
+use serde::{Deserialize, Serialize};
+
+/// Default [`ImbalanceBarConfig::min_threshold_ratio`]
+pub const DEFAULT_MIN_THRESHOLD_RATIO: f64 = 0.5;
+
+fn default_min_threshold_ratio() -> f64 {
+    DEFAULT_MIN_THRESHOLD_RATIO
+}
+
+/// Which order-flow quantity is measured and how it closes a bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImbalanceBarKind {
+    /// Signed individual trade count
+    TickImbalance,
+
+    /// Signed base-asset volume
+    VolumeImbalance,
+
+    /// Signed turnover (`price * volume`)
+    DollarImbalance,
+
+    /// Longest one-sided individual trade count
+    TickRun,
+
+    /// Longest one-sided base-asset volume
+    VolumeRun,
+}
+
+impl ImbalanceBarKind {
+    /// Whether this is a run bar (as opposed to an imbalance bar)
+    pub fn is_run(&self) -> bool {
+        matches!(
+            self,
+            ImbalanceBarKind::TickRun | ImbalanceBarKind::VolumeRun
+        )
+    }
+
+    /// Buy-side and sell-side totals of `bar` in natural units
+    ///
+    /// Trade counts, base-asset volume or quote-asset turnover, with raw
+    /// values read at `scale`.
+    pub fn side_totals(&self, bar: &RangeBar, scale: TradeScale) -> (f64, f64) {
+        match self {
+            ImbalanceBarKind::TickImbalance | ImbalanceBarKind::TickRun => {
+                (bar.buy_trade_count as f64, bar.sell_trade_count as f64)
+            }
+            ImbalanceBarKind::VolumeImbalance | ImbalanceBarKind::VolumeRun => (
+                bar.buy_volume.to_f64_scaled(scale.volume),
+                bar.sell_volume.to_f64_scaled(scale.volume),
+            ),
+            ImbalanceBarKind::DollarImbalance => {
+                let factor = scale.turnover_factor() as f64;
+                (
+                    bar.buy_turnover as f64 / factor,
+                    bar.sell_turnover as f64 / factor,
+                )
+            }
+        }
+    }
+
+    /// Order-flow statistic compared against the threshold (`θ`)
+    pub fn statistic(&self, bar: &RangeBar, scale: TradeScale) -> f64 {
+        let (buy, sell) = self.side_totals(bar, scale);
+        if self.is_run() {
+            buy.max(sell)
+        } else {
+            (buy - sell).abs()
+        }
+    }
+}
+
+/// Configuration for [`ImbalanceBarProcessor`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImbalanceBarConfig {
+    /// Measured quantity and close rule
+    pub kind: ImbalanceBarKind,
+
+    /// EWMA smoothing factor in `(0, 1]` applied per completed bar
+    pub alpha: f64,
+
+    /// Threshold for the first bar, before any bar has completed (natural units)
+    pub initial_threshold: f64,
+
+    /// Floor on the threshold as a fraction of `initial_threshold`, in `(0, 1]`
+    #[serde(default = "default_min_threshold_ratio")]
+    pub min_threshold_ratio: f64,
+
+    /// Decimal scales of the instrument, to read volumes and turnover in natural units
+    #[serde(default)]
+    pub scale: TradeScale,
+}
+
+impl ImbalanceBarConfig {
+    /// Create configuration with the default smoothing factor (`0.1`) and
+    /// threshold floor ([`DEFAULT_MIN_THRESHOLD_RATIO`])
+    pub fn new(kind: ImbalanceBarKind, initial_threshold: f64) -> Self {
+        Self {
+            kind,
+            alpha: 0.1,
+            initial_threshold,
+            min_threshold_ratio: DEFAULT_MIN_THRESHOLD_RATIO,
+            scale: TradeScale::default(),
+        }
+    }
+
+    /// Check the configuration is usable
+    fn validate(&self) -> Result<(), ProcessingError> {
+        if !(self.alpha > 0.0 && self.alpha <= 1.0) {
+            return Err(ProcessingError::InvalidImbalanceConfig(format!(
+                "alpha must be in (0, 1], got {}",
+                self.alpha
+            )));
+        }
+        if !(self.initial_threshold.is_finite() && self.initial_threshold > 0.0) {
+            return Err(ProcessingError::InvalidImbalanceConfig(format!(
+                "initial threshold must be positive, got {}",
+                self.initial_threshold
+            )));
+        }
+        if !(self.min_threshold_ratio > 0.0 && self.min_threshold_ratio <= 1.0) {
+            return Err(ProcessingError::InvalidImbalanceConfig(format!(
+                "min threshold ratio must be in (0, 1], got {}",
+                self.min_threshold_ratio
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// EWMA expectations estimated from completed bars
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImbalanceExpectations {
+    /// Expected individual trades per bar (`E[T]`)
+    pub trades_per_bar: f64,
+
+    /// Expected buy-side quantity per trade (`E[buy/T]`)
+    pub buy_per_trade: f64,
+
+    /// Expected sell-side quantity per trade (`E[sell/T]`)
+    pub sell_per_trade: f64,
+}
+
+/// Imbalance or run bar processor
+///
+/// Each bar keeps the threshold expected when it opened; the expectations
+/// learn only from bars that reached it.
+#[derive(Debug, Clone)]
+pub struct ImbalanceBarProcessor {
+    /// Measured quantity, smoothing and warm-up threshold
+    config: ImbalanceBarConfig,
+
+    /// Expectations from completed bars (`None` until the first bar completes)
+    expectations: Option<ImbalanceExpectations>,
+
+    /// Bar currently being built and the threshold fixed at its open
+    current_bar: Option<(RangeBar, f64)>,
+
+    /// Last processed `(timestamp, agg_trade_id)` for cross-call ordering validation
+    last_trade: Option<(i64, i64)>,
+}
+
+impl ImbalanceBarProcessor {
+    /// Create processor with the given configuration
+    pub fn new(config: ImbalanceBarConfig) -> Result<Self, ProcessingError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            expectations: None,
+            current_bar: None,
+            last_trade: None,
+        })
+    }
+
+    /// Configuration in effect
+    pub fn config(&self) -> &ImbalanceBarConfig {
+        &self.config
+    }
+
+    /// Current expectations (`None` until the first bar completes)
+    pub fn expectations(&self) -> Option<ImbalanceExpectations> {
+        self.expectations
+    }
+
+    /// Threshold the next bar would open with
+    pub fn expected_threshold(&self) -> f64 {
+        let floor = self.config.initial_threshold * self.config.min_threshold_ratio;
+        match self.expectations {
+            None => self.config.initial_threshold,
+            Some(e) if self.config.kind.is_run() => {
+                e.trades_per_bar * e.buy_per_trade.max(e.sell_per_trade)
+            }
+            Some(e) => e.trades_per_bar * (e.buy_per_trade - e.sell_per_trade).abs(),
+        }
+        .max(floor)
+    }
+
+    /// Threshold fixed at the open of the current bar
+    pub fn current_threshold(&self) -> Option<f64> {
+        self.current_bar.as_ref().map(|(_, threshold)| *threshold)
+    }
+
+    /// Process a single trade and return completed bar if any
+    pub fn process_single_trade(
+        &mut self,
+        trade: AggTrade,
+    ) -> Result<Option<RangeBar>, ProcessingError> {
+        validate_trade_ordering(self.last_trade, std::slice::from_ref(&trade))?;
+        self.process_trade(&trade)
+    }
+
+    /// Process trades into bars (completed bars only)
+    pub fn process_trades(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<RangeBar>, ProcessingError> {
+        validate_trade_ordering(self.last_trade, trades)?;
+
+        let mut bars = Vec::new();
+        for trade in trades {
+            bars.extend(self.process_trade(trade)?);
+        }
+        Ok(bars)
+    }
+
+    /// Get any incomplete bar currently being processed
+    pub fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        self.current_bar.as_ref().map(|(bar, _)| bar.clone())
+    }
+
+    /// Force-close the open bar at end of data
+    ///
+    /// A forced close does not update the expectations.
+    pub fn finish(&mut self) -> Option<RangeBar> {
+        self.current_bar
+            .take()
+            .map(|(bar, _)| closed_at_end_of_data(bar))
+    }
+
+    /// Discard the open bar, expectations and ordering history
+    pub fn reset(&mut self) {
+        self.expectations = None;
+        self.current_bar = None;
+        self.last_trade = None;
+    }
+
+    /// Advance the state machine by one (already validated) trade
+    ///
+    /// On error nothing changes, so the trade can be retried.
+    fn process_trade(&mut self, trade: &AggTrade) -> Result<Option<RangeBar>, ProcessingError> {
+        match self.current_bar {
+            Some((ref mut bar, _)) => bar.update_with_trade(trade).map_err(overflow_at(trade))?,
+            None => {
+                // Threshold is fixed from completed-bar history at the open
+                let threshold = self.expected_threshold();
+                self.current_bar = Some((RangeBar::new(trade), threshold));
+            }
+        }
+        self.last_trade = Some((trade.timestamp, trade.agg_trade_id));
+
+        let Some((bar, threshold)) = self.current_bar.as_ref() else {
+            return Ok(None);
+        };
+        if self.config.kind.statistic(bar, self.config.scale) < *threshold {
+            return Ok(None);
+        }
+
+        let Some((mut bar, _)) = self.current_bar.take() else {
+            return Ok(None);
+        };
+        bar.force_close(CloseReason::TargetReached);
+        self.update_expectations(&bar);
+        Ok(Some(bar))
+    }
+
+    /// Fold a completed bar into the EWMA expectations
+    fn update_expectations(&mut self, bar: &RangeBar) {
+        let trades = bar.trade_count.max(1) as f64;
+        let (buy, sell) = self.config.kind.side_totals(bar, self.config.scale);
+        let observed = ImbalanceExpectations {
+            trades_per_bar: trades,
+            buy_per_trade: buy / trades,
+            sell_per_trade: sell / trades,
+        };
+
+        let alpha = self.config.alpha;
+        let ewma = |prev: f64, x: f64| prev + alpha * (x - prev);
+        self.expectations = Some(match self.expectations {
+            None => observed,
+            Some(prev) => ImbalanceExpectations {
+                trades_per_bar: ewma(prev.trades_per_bar, observed.trades_per_bar),
+                buy_per_trade: ewma(prev.buy_per_trade, observed.buy_per_trade),
+                sell_per_trade: ewma(prev.sell_per_trade, observed.sell_per_trade),
+            },
+        });
+    }
+}
+
+impl BarProcessor for ImbalanceBarProcessor {
+    fn process_trades(&mut self, trades: &[AggTrade]) -> Result<Vec<RangeBar>, ProcessingError> {
+        ImbalanceBarProcessor::process_trades(self, trades)
+    }
+
+    fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        ImbalanceBarProcessor::get_incomplete_bar(self)
+    }
+
+    fn has_open_bar(&self) -> bool {
+        self.current_bar.is_some()
+    }
+
+    fn finish(&mut self) -> Option<RangeBar> {
+        ImbalanceBarProcessor::finish(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::{FixedPoint, SCALE};
+
+    fn create_test_trade(id: i64, volume: &str, is_buyer_maker: bool) -> AggTrade {
+        AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str("100.0").unwrap(),
+            volume: FixedPoint::from_str(volume).unwrap(),
+            first_trade_id: id,
+            last_trade_id: id,
+            timestamp: 1000 + id,
+            is_buyer_maker,
+        }
+    }
+
+    /// Buys and sells from a compact pattern: `b` = buy aggressor, `s` = sell
+    fn flow(pattern: &str) -> Vec<AggTrade> {
+        flow_from(1, pattern)
+    }
+
+    fn flow_from(first_id: i64, pattern: &str) -> Vec<AggTrade> {
+        pattern
+            .chars()
+            .enumerate()
+            .map(|(i, side)| create_test_trade(first_id + i as i64, "1.0", side == 's'))
+            .collect()
+    }
+
+    #[test]
+    fn test_tick_imbalance_bars() {
+        let mut config = ImbalanceBarConfig::new(ImbalanceBarKind::TickImbalance, 3.0);
+        config.alpha = 1.0;
+        let mut processor = ImbalanceBarProcessor::new(config).unwrap();
+
+        // |buy - sell| reaches 3 after "bsbbb" (4 buys, 1 sell)
+        let bars = processor.process_trades(&flow("bsbbb")).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].trade_count, 5);
+        assert_eq!((bars[0].buy_trade_count, bars[0].sell_trade_count), (4, 1));
+        assert_eq!(bars[0].buy_volume.to_string(), "4.00000000");
+        assert_eq!(bars[0].close_reason, Some(CloseReason::TargetReached));
+
+        // E[T] = 5, E[buy/T] - E[sell/T] = 0.6: next threshold 3
+        let expectations = processor.expectations().unwrap();
+        assert_eq!(expectations.trades_per_bar, 5.0);
+        assert!((processor.expected_threshold() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tick_run_bars() {
+        let mut config = ImbalanceBarConfig::new(ImbalanceBarKind::TickRun, 3.0);
+        config.alpha = 1.0;
+        let mut processor = ImbalanceBarProcessor::new(config).unwrap();
+
+        // Runs count one side regardless of interleaving: 3 sells close the bar
+        let bars = processor.process_trades(&flow("sbsbs")).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!((bars[0].buy_trade_count, bars[0].sell_trade_count), (2, 3));
+
+        // E[T] = 5, max(E[buy/T], E[sell/T]) = 0.6
+        assert!((processor.expected_threshold() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_volume_and_dollar_imbalance() {
+        let trades = vec![
+            create_test_trade(1, "2.0", false),
+            create_test_trade(2, "0.5", true),
+            create_test_trade(3, "1.0", false), // buy 3.0, sell 0.5: imbalance 2.5
+        ];
+
+        let config = ImbalanceBarConfig::new(ImbalanceBarKind::VolumeImbalance, 2.5);
+        let mut processor = ImbalanceBarProcessor::new(config).unwrap();
+        let bars = processor.process_trades(&trades).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].last_id, 3);
+
+        // Same flow at price 100: turnover imbalance 250
+        let config = ImbalanceBarConfig::new(ImbalanceBarKind::DollarImbalance, 250.0);
+        let mut processor = ImbalanceBarProcessor::new(config).unwrap();
+        let bars = processor.process_trades(&trades).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].sell_turnover, 50 * SCALE as i128 * SCALE as i128);
+    }
+
+    #[test]
+    fn test_threshold_fixed_at_open_and_forced_close_ignored() {
+        let config = ImbalanceBarConfig::new(ImbalanceBarKind::TickImbalance, 2.0);
+        let mut processor = ImbalanceBarProcessor::new(config).unwrap();
+
+        assert_eq!(processor.process_trades(&flow("bb")).unwrap().len(), 1);
+        let after_first = processor.expectations();
+        assert!(after_first.is_some());
+
+        // Open bar keeps the threshold chosen at its open while trades arrive
+        assert!(
+            processor
+                .process_trades(&flow_from(3, "bsb"))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(processor.current_threshold(), Some(2.0));
+
+        // End-of-data close is not a completed bar
+        let last = processor.finish().unwrap();
+        assert_eq!(last.close_reason, Some(CloseReason::EndOfData));
+        assert_eq!(processor.expectations(), after_first);
+    }
+
+    #[test]
+    fn test_balanced_flow_keeps_the_threshold_floor() {
+        let mut config = ImbalanceBarConfig::new(ImbalanceBarKind::TickImbalance, 3.0);
+        config.alpha = 0.5;
+        let mut processor = ImbalanceBarProcessor::new(config).unwrap();
+
+        // A buy-heavy then a sell-heavy bar balance E[buy/T] and E[sell/T]
+        let bars = processor.process_trades(&flow("bbbsss")).unwrap();
+        assert_eq!(bars.len(), 2);
+        let e = processor.expectations().unwrap();
+        assert_eq!(e.buy_per_trade, e.sell_per_trade);
+        assert_eq!(processor.expected_threshold(), 1.5);
+
+        // Alternating flow has no imbalance to close on
+        assert!(
+            processor
+                .process_trades(&flow_from(7, "bsbsbsbsbs"))
+                .unwrap()
+                .is_empty()
+        );
+        let bars = processor.process_trades(&flow_from(17, "bb")).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].trade_count, 12);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mut config = ImbalanceBarConfig::new(ImbalanceBarKind::VolumeRun, 0.0);
+        assert!(matches!(
+            ImbalanceBarProcessor::new(config),
+            Err(ProcessingError::InvalidImbalanceConfig(_))
+        ));
+        config.initial_threshold = 1.0;
+        config.alpha = 0.0;
+        assert!(ImbalanceBarProcessor::new(config).is_err());
+        config.alpha = 0.1;
+        config.min_threshold_ratio = 0.0;
+        assert!(ImbalanceBarProcessor::new(config).is_err());
+    }
+}
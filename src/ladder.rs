@@ -0,0 +1,427 @@
+//! Multi-threshold ladder processing
+//!
+//! [`LadderProcessor`] builds range bars for several thresholds in one pass:
+//! each trade is ordering-checked and consumed once, then advanced through one
+//! [`RangeBarProcessor`] per threshold (a rung). Every rung runs the unchanged
+//! range bar state machine, so its bars are identical to running a separate
+//! `RangeBarProcessor` with that threshold over the same trades.
+//!
+//! Bars are delivered per rung, either as one `Vec` per rung or into a
+//! separate [`BarSink`] per rung (e.g. one CSV writer per threshold).
+
+use crate::range_bars::{
+    BarProcessor, ProcessingError, ProcessorCheckpoint, RangeBarProcessor, RangeBarProcessorConfig,
+    validate_trade_ordering,
+};
+use crate::threshold::BarRange;
+use crate::types::{AggTrade, RangeBar};
+use std::io;
+
+/// Destination for the bars of one ladder rung
+pub trait BarSink {
+    /// Take ownership of a completed bar
+    fn accept(&mut self, bar: RangeBar) -> io::Result<()>;
+}
+
+impl BarSink for Vec<RangeBar> {
+    fn accept(&mut self, bar: RangeBar) -> io::Result<()> {
+        self.push(bar);
+        Ok(())
+    }
+}
+
+impl<W: io::Write> BarSink for csv::Writer<W> {
+    fn accept(&mut self, bar: RangeBar) -> io::Result<()> {
+        self.serialize(bar).map_err(io::Error::from)
+    }
+}
+
+impl<S: BarSink + ?Sized> BarSink for &mut S {
+    fn accept(&mut self, bar: RangeBar) -> io::Result<()> {
+        (**self).accept(bar)
+    }
+}
+
+/// Range bars for several thresholds from a single pass over the trades
+///
+/// Ordering is validated once per trade for all rungs.
+pub struct LadderProcessor {
+    /// One engine per threshold, in the order given
+    rungs: Vec<RangeBarProcessor>,
+
+    /// Last processed `(timestamp, agg_trade_id)` for cross-call ordering validation
+    last_trade: Option<(i64, i64)>,
+}
+
+impl LadderProcessor {
+    /// Create a ladder with one rung per threshold
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Thresholds in any form accepted by [`RangeBarProcessor::new`],
+    ///   e.g. `[25, 50, 100]`; bars are routed by position in this list
+    pub fn new<R: Into<BarRange>>(
+        ranges: impl IntoIterator<Item = R>,
+    ) -> Result<Self, ProcessingError> {
+        Self::with_config(ranges, RangeBarProcessorConfig::default())
+    }
+
+    /// Create a ladder with explicit engine options shared by every rung
+    pub fn with_config<R: Into<BarRange>>(
+        ranges: impl IntoIterator<Item = R>,
+        config: RangeBarProcessorConfig,
+    ) -> Result<Self, ProcessingError> {
+        Self::from_processors(
+            ranges
+                .into_iter()
+                .map(|range| RangeBarProcessor::with_config(range, config))
+                .collect(),
+        )
+    }
+
+    /// Create a ladder from configured processors, e.g. with adaptive policies
+    ///
+    /// Processors that already saw trades continue from the latest of them.
+    pub fn from_processors(rungs: Vec<RangeBarProcessor>) -> Result<Self, ProcessingError> {
+        if rungs.is_empty() {
+            return Err(ProcessingError::EmptyLadder);
+        }
+        let last_trade = rungs.iter().filter_map(|rung| rung.last_trade()).max();
+        Ok(Self { rungs, last_trade })
+    }
+
+    /// Rebuild a ladder from a [`checkpoint`](Self::checkpoint) of fixed-threshold rungs
+    pub fn restore(checkpoints: Vec<ProcessorCheckpoint>) -> Result<Self, ProcessingError> {
+        Self::from_processors(
+            checkpoints
+                .into_iter()
+                .map(RangeBarProcessor::restore)
+                .collect::<Result<_, _>>()?,
+        )
+    }
+
+    /// Snapshot every rung, in rung order
+    pub fn checkpoint(&self) -> Vec<ProcessorCheckpoint> {
+        self.rungs
+            .iter()
+            .map(RangeBarProcessor::checkpoint)
+            .collect()
+    }
+
+    /// Per-threshold engines, in rung order
+    pub fn rungs(&self) -> &[RangeBarProcessor] {
+        &self.rungs
+    }
+
+    /// Number of rungs
+    pub fn len(&self) -> usize {
+        self.rungs.len()
+    }
+
+    /// Always `false`: a ladder has at least one rung
+    pub fn is_empty(&self) -> bool {
+        self.rungs.is_empty()
+    }
+
+    /// Process trades, returning the completed bars of each rung
+    pub fn process_trades(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<Vec<RangeBar>>, ProcessingError> {
+        let mut bars = vec![Vec::new(); self.rungs.len()];
+        self.process_trades_into(trades, &mut bars)?;
+        Ok(bars)
+    }
+
+    /// Process trades, handing each completed bar to its rung's sink
+    ///
+    /// `sinks[i]` receives the bars of rung `i`. A failing sink aborts the call
+    /// with [`ProcessingError::SinkFailed`]; every rung has then consumed the
+    /// trades up to and including the one that completed the rejected bar.
+    ///
+    /// Rungs are not rolled back on an engine error such as
+    /// [`ProcessingError::Overflow`]: the rungs before the failing one have
+    /// consumed the trade and the bars they completed on it are discarded.
+    /// Restore from a [`checkpoint`](Self::checkpoint) to retry from a
+    /// consistent state.
+    pub fn process_trades_into<S: BarSink>(
+        &mut self,
+        trades: &[AggTrade],
+        sinks: &mut [S],
+    ) -> Result<(), ProcessingError> {
+        self.check_sinks(sinks.len())?;
+        validate_trade_ordering(self.last_trade, trades)?;
+
+        // Every rung consumes the trade before any sink sees its bars
+        let mut completed = vec![Vec::new(); self.rungs.len()];
+        for trade in trades {
+            self.last_trade = Some((trade.timestamp, trade.agg_trade_id));
+            for (processor, rung_bars) in self.rungs.iter_mut().zip(completed.iter_mut()) {
+                processor.process_trade(trade, rung_bars)?;
+            }
+            for (rung, (rung_bars, sink)) in completed.iter_mut().zip(sinks.iter_mut()).enumerate()
+            {
+                for bar in rung_bars.drain(..) {
+                    deliver(sink, rung, bar)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Snapshot of each rung's open bar
+    pub fn get_incomplete_bars(&self) -> Vec<Option<RangeBar>> {
+        self.rungs
+            .iter()
+            .map(RangeBarProcessor::get_incomplete_bar)
+            .collect()
+    }
+
+    /// Force-close every rung's open bar at end of data
+    pub fn finish(&mut self) -> Vec<Option<RangeBar>> {
+        self.rungs
+            .iter_mut()
+            .map(RangeBarProcessor::finish)
+            .collect()
+    }
+
+    /// Force-close every rung's open bar, handing it to the rung's sink
+    pub fn finish_into<S: BarSink>(&mut self, sinks: &mut [S]) -> Result<(), ProcessingError> {
+        self.check_sinks(sinks.len())?;
+        for (rung, (processor, sink)) in self.rungs.iter_mut().zip(sinks.iter_mut()).enumerate() {
+            if let Some(bar) = processor.finish() {
+                deliver(sink, rung, bar)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Discard every rung's open bar and the ordering history
+    pub fn reset(&mut self) {
+        self.rungs.iter_mut().for_each(RangeBarProcessor::reset);
+        self.last_trade = None;
+    }
+
+    fn check_sinks(&self, found: usize) -> Result<(), ProcessingError> {
+        if found == self.rungs.len() {
+            Ok(())
+        } else {
+            Err(ProcessingError::SinkCountMismatch {
+                expected: self.rungs.len(),
+                found,
+            })
+        }
+    }
+}
+
+fn deliver(sink: &mut impl BarSink, rung: usize, bar: RangeBar) -> Result<(), ProcessingError> {
+    sink.accept(bar).map_err(|e| ProcessingError::SinkFailed {
+        rung,
+        message: e.to_string(),
+    })
+}
+
+/// Routes each rung to its own output; the flat methods merge the rungs
+impl BarProcessor for LadderProcessor {
+    /// Completed bars of all rungs, in completion order (rung order within a trade)
+    fn process_trades(&mut self, trades: &[AggTrade]) -> Result<Vec<RangeBar>, ProcessingError> {
+        Ok(self
+            .process_trades_routed(trades)?
+            .into_iter()
+            .map(|(_, bar)| bar)
+            .collect())
+    }
+
+    /// Open bar of the first rung
+    fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        self.rungs[0].get_incomplete_bar()
+    }
+
+    /// Whether the first rung has an open bar
+    fn has_open_bar(&self) -> bool {
+        self.rungs[0].has_open_bar()
+    }
+
+    /// Force-close the open bar of the first rung that has one
+    ///
+    /// Call until `None` to close the whole ladder, or use
+    /// [`finish_routed`](BarProcessor::finish_routed).
+    fn finish(&mut self) -> Option<RangeBar> {
+        self.rungs.iter_mut().find_map(RangeBarProcessor::finish)
+    }
+
+    fn output_count(&self) -> usize {
+        self.rungs.len()
+    }
+
+    /// Open bars of every rung
+    fn buffered_bytes(&self) -> usize {
+        self.rungs.iter().map(BarProcessor::buffered_bytes).sum()
+    }
+
+    /// Completed bars tagged with their rung
+    ///
+    /// An engine error leaves the rungs in the partially advanced state
+    /// described on [`LadderProcessor::process_trades_into`].
+    fn process_trades_routed(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<(usize, RangeBar)>, ProcessingError> {
+        validate_trade_ordering(self.last_trade, trades)?;
+
+        let mut bars = Vec::new();
+        let mut completed = Vec::new();
+        for trade in trades {
+            self.last_trade = Some((trade.timestamp, trade.agg_trade_id));
+            for (rung, processor) in self.rungs.iter_mut().enumerate() {
+                processor.process_trade(trade, &mut completed)?;
+                bars.extend(completed.drain(..).map(|bar| (rung, bar)));
+            }
+        }
+        Ok(bars)
+    }
+
+    fn finish_routed(&mut self) -> Vec<(usize, RangeBar)> {
+        LadderProcessor::finish(self)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(rung, bar)| Some((rung, bar?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::FixedPoint;
+
+    fn create_test_trade(id: i64, price: &str) -> AggTrade {
+        AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str(price).unwrap(),
+            volume: FixedPoint::from_str("1.0").unwrap(),
+            first_trade_id: id,
+            last_trade_id: id,
+            timestamp: 1000 * id,
+            is_buyer_maker: id % 2 == 0,
+        }
+    }
+
+    /// Sink that rejects every bar
+    struct FailingSink;
+
+    impl BarSink for FailingSink {
+        fn accept(&mut self, _bar: RangeBar) -> io::Result<()> {
+            Err(io::Error::other("disk full"))
+        }
+    }
+
+    #[test]
+    fn test_rungs_close_independently() {
+        let trades = vec![
+            create_test_trade(1, "100.0"),
+            create_test_trade(2, "100.3"), // Breaches 25 bps only
+            create_test_trade(3, "100.6"), // Breaches 50 bps
+            create_test_trade(4, "100.5"),
+        ];
+
+        let mut ladder = LadderProcessor::new([25, 50, 100]).unwrap();
+        let bars = ladder.process_trades(&trades).unwrap();
+        assert_eq!(bars.iter().map(Vec::len).collect::<Vec<_>>(), vec![1, 1, 0]);
+        assert_eq!((bars[0][0].first_id, bars[0][0].last_id), (1, 2));
+        assert_eq!((bars[1][0].first_id, bars[1][0].last_id), (1, 3));
+
+        let open: Vec<_> = ladder
+            .get_incomplete_bars()
+            .into_iter()
+            .map(|bar| bar.map(|b| b.first_id))
+            .collect();
+        assert_eq!(open, vec![Some(3), Some(4), Some(1)]);
+    }
+
+    #[test]
+    fn test_routed_matches_per_rung_output() {
+        let trades: Vec<AggTrade> = (1..=300)
+            .map(|i| {
+                create_test_trade(i, &format!("{:.2}", 100.0 + 2.0 * ((i as f64) / 9.0).sin()))
+            })
+            .collect();
+
+        let mut ladder = LadderProcessor::new([20, 60]).unwrap();
+        let expected = ladder.process_trades(&trades).unwrap();
+
+        let mut routed = LadderProcessor::new([20, 60]).unwrap();
+        let mut bars = vec![Vec::new(); 2];
+        for chunk in trades.chunks(13) {
+            for (rung, bar) in routed.process_trades_routed(chunk).unwrap() {
+                bars[rung].push(bar);
+            }
+        }
+        assert_eq!(expected, bars);
+
+        let finished = routed.finish_routed();
+        assert_eq!(
+            finished.iter().map(|(rung, _)| *rung).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert!(routed.finish_routed().is_empty());
+    }
+
+    #[test]
+    fn test_validation_and_sink_errors() {
+        assert!(matches!(
+            LadderProcessor::new(Vec::<u32>::new()),
+            Err(ProcessingError::EmptyLadder)
+        ));
+
+        let trades = vec![create_test_trade(1, "100.0"), create_test_trade(2, "110.0")];
+        let mut ladder = LadderProcessor::new([25, 50]).unwrap();
+        assert!(matches!(
+            ladder.process_trades_into(&trades, &mut [Vec::new()]),
+            Err(ProcessingError::SinkCountMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            ladder.process_trades_into(&trades, &mut [FailingSink, FailingSink]),
+            Err(ProcessingError::SinkFailed { rung: 0, .. })
+        ));
+
+        // Ordering is checked once for the whole ladder, across calls
+        assert!(matches!(
+            ladder.process_trades(&[create_test_trade(1, "100.0")]),
+            Err(ProcessingError::UnsortedTrades { .. })
+        ));
+    }
+
+    #[test]
+    fn test_engine_error_leaves_earlier_rungs_advanced() {
+        // Breach levels fit at 25 bps but overflow at 500%
+        let trade = AggTrade {
+            price: FixedPoint(i64::MAX / 4),
+            ..create_test_trade(1, "1.0")
+        };
+
+        let mut ladder = LadderProcessor::new([25, 50_000]).unwrap();
+        assert!(matches!(
+            ladder.process_trades_routed(std::slice::from_ref(&trade)),
+            Err(ProcessingError::Overflow { agg_trade_id: 1 })
+        ));
+
+        // The first rung opened a bar on the trade; the failing rung did not
+        let open: Vec<_> = ladder
+            .get_incomplete_bars()
+            .into_iter()
+            .map(|bar| bar.map(|b| b.first_id))
+            .collect();
+        assert_eq!(open, vec![Some(1), None]);
+
+        // The trade counts as consumed, so it cannot be replayed
+        assert!(matches!(
+            ladder.process_trades(&[trade]),
+            Err(ProcessingError::UnsortedTrades { .. })
+        ));
+    }
+}
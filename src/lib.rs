@@ -6,8 +6,21 @@
 //! ## Features
 //!
 //! - Non-lookahead bias range bar construction
-//! - Fixed-point arithmetic for precision
+//! - Source-independent trade input: Binance aggregates or any venue's prints
+//! - Fixed-point arithmetic for precision, with overflow detected in every build
+//! - Per-instrument decimal scales with lossless rescaling
 //! - Streaming and batch processing modes
+//! - Lazy range bars from any trade iterator or async stream, in constant memory
+//! - Tick, volume and dollar bars sharing the range bar output format
+//! - Imbalance and run bars driven by order-flow sign
+//! - Renko bricks on a bps or tick grid
+//! - Threshold ladders: several thresholds from one pass over the trades
+//! - Session-boundary and maximum-duration bar cuts
+//! - Bounded reordering of slightly out-of-order trades
+//! - Streaming memory accounting with pause, shed or spill-to-disk policies
+//! - Multi-symbol routing of one trade feed with per-symbol isolation
+//! - Bar fan-out to many subscribers with per-subscriber lag policies and replay
+//! - Aggregate-trade-id continuity and duplicate audit
 //! - Tier-1 cryptocurrency symbol discovery
 //! - Pure Rust implementation
 //!
@@ -59,13 +72,30 @@
 //! 3. **Fixed thresholds**: Never recalculated during bar lifetime
 //!
 
+pub mod audit;
+pub mod bar_iter;
+pub mod bar_stream;
+pub mod broadcast;
 pub mod config;
+pub mod cut_policy;
+pub mod event_bars;
 pub mod fixed_point;
+pub mod imbalance_bars;
+pub mod ladder;
 pub mod range_bars;
 pub mod range_bars_debug;
+pub mod renko;
+pub mod reorder;
+pub mod router;
+pub mod threshold;
+pub mod threshold_policy;
 pub mod tier1;
+pub mod trade;
 pub mod types;
 
+#[cfg(test)]
+mod test_utils;
+
 #[cfg(feature = "statistics")]
 pub mod statistics;
 
@@ -82,11 +112,39 @@ pub mod api;
 // pub mod python;
 
 // Re-export commonly used types for convenience
+pub use audit::{AuditFinding, AuditReport, DayAudit, IdAuditor};
+pub use bar_iter::{RangeBarIteratorExt, RangeBars, TradeItem, TradeSourceError};
+pub use bar_stream::{RangeBarStreamAdapter, RangeBarStreamExt};
+pub use broadcast::{
+    BarBroadcast, BarSubscription, BarSubscriptions, LagPolicy, PublishReport, SubscriptionConfig,
+};
 pub use config::Settings;
-pub use fixed_point::FixedPoint;
-pub use range_bars::{ExportRangeBarProcessor, ProcessingError, RangeBarProcessor};
+pub use cut_policy::{CutPolicy, SessionBoundary};
+pub use event_bars::{EventBarProcessor, EventBarRule};
+pub use fixed_point::{ExcessPrecision, FixedPoint, Rounding, Scale};
+pub use imbalance_bars::{
+    DEFAULT_MIN_THRESHOLD_RATIO, ImbalanceBarConfig, ImbalanceBarKind, ImbalanceBarProcessor,
+    ImbalanceExpectations,
+};
+pub use ladder::{BarSink, LadderProcessor};
+pub use range_bars::{
+    BarProcessor, CHECKPOINT_VERSION, ExportRangeBarProcessor, NextBarOpen, OpenBarCheckpoint,
+    PolicyCheckpoint, ProcessingError, ProcessorCheckpoint, RangeBarProcessor,
+    RangeBarProcessorConfig,
+};
+pub use renko::RenkoProcessor;
+pub use reorder::{LatePolicy, LatenessWindow, ReorderBuffer, ReorderConfig, ReorderingProcessor};
+pub use router::{
+    RoutedBar, RouterError, SymbolMetrics, SymbolRouter, SymbolRouterConfig, SymbolRouterStream,
+};
+pub use threshold::{BarRange, BreachThresholds, Threshold, ThresholdError};
+pub use threshold_policy::{
+    AdaptiveBounds, EwmaRangePolicy, FixedThreshold, RealizedVolatilityPolicy,
+    TargetBarsPerHourPolicy, ThresholdPolicy,
+};
 pub use tier1::{TIER1_SYMBOLS, get_tier1_symbols, get_tier1_usdt_pairs, is_tier1_symbol};
-pub use types::{AggTrade, RangeBar};
+pub use trade::{Side, Trade, TradePrint};
+pub use types::{AggTrade, BreachDirection, CloseReason, RangeBar, TradeScale};
 
 // Legacy statistics exports removed - now use streaming-stats feature
 
@@ -98,8 +156,8 @@ pub use statistics::{
 
 // Streaming processor exports
 pub use streaming_processor::{
-    MetricsSummary, RangeBarStream, StreamingError, StreamingMetrics, StreamingProcessor,
-    StreamingProcessorConfig,
+    CountingAllocator, MemoryAccounting, MemoryPolicy, MetricsSummary, RangeBarStream,
+    StreamingError, StreamingMetrics, StreamingProcessor, StreamingProcessorConfig,
 };
 
 /// Version information
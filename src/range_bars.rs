@@ -2,17 +2,139 @@
 //!
 //! Implements non-lookahead bias range bar construction where bars close when
 //! price moves ±threshold bps from the bar's OPEN price.
-
-use crate::fixed_point::FixedPoint;
-use crate::types::{AggTrade, RangeBar};
+//!
+//! [`RangeBarProcessor`] is the single canonical engine. The library wrappers,
+//! `StreamingProcessor`, the binaries and the API all drive it, so they share
+//! one set of semantics:
+//!
+//! - Thresholds are `open * threshold / 100%` in exact integer arithmetic, with
+//!   separate upward and downward distances when the thresholds are asymmetric,
+//!   or fixed price distances for absolute (tick-based) [`BarRange`]s
+//! - Turnover is the exact `i128` product `price * volume` (never via `f64`)
+//! - Input is any [`Trade`]; Binance [`AggTrade`]s and venue [`TradePrint`]s
+//!   carrying the same data build identical bars
+//! - Trade counts use [`Trade::trade_count`] (individual trades, not aggregates)
+//! - The bar after a breach opens according to [`NextBarOpen`] (next trade by default)
+//! - Breach distances come from a [`ThresholdPolicy`], consulted once per bar at
+//!   its opening trade; [`FixedThreshold`] reproduces the classic fixed range
+//! - An optional [`CutPolicy`] force-closes bars at session boundaries or size
+//!   caps, marking them with their own [`CloseReason`]
+
+use crate::cut_policy::CutPolicy;
+use crate::event_bars::EventBarRule;
+use crate::fixed_point::{FixedPoint, FixedPointError};
+use crate::threshold::BarRange;
+use crate::threshold_policy::{FixedThreshold, ThresholdPolicy};
+use crate::trade::Trade;
+#[cfg(doc)]
+use crate::trade::TradePrint;
+use crate::types::{AggTrade, BreachDirection, CloseReason, RangeBar};
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Which trade opens the bar that follows a threshold breach
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NextBarOpen {
+    /// The first trade after the breaching trade opens the next bar (canonical)
+    #[default]
+    NextTrade,
+
+    /// The breaching trade closes the bar and also opens the next one
+    ///
+    /// Legacy export behavior: the breaching trade's volume is counted in both bars.
+    BreachingTrade,
+}
+
+/// Engine options for [`RangeBarProcessor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RangeBarProcessorConfig {
+    /// Opening rule for the bar following a breach
+    pub next_bar_open: NextBarOpen,
+}
+
+/// Current [`ProcessorCheckpoint`] format version
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+/// Serializable snapshot of a [`RangeBarProcessor`]
+///
+/// Produced by [`RangeBarProcessor::checkpoint`] and consumed by
+/// [`RangeBarProcessor::restore`]. A restored processor continues exactly where
+/// the original stopped, so a run split across processes emits the same bars
+/// as one uninterrupted run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessorCheckpoint {
+    /// Snapshot format version ([`CHECKPOINT_VERSION`] when written)
+    pub version: u32,
+
+    /// Threshold policy and its state
+    pub policy: PolicyCheckpoint,
+
+    /// Engine options
+    pub config: RangeBarProcessorConfig,
+
+    /// Session and size cuts (absent in checkpoints written before cuts existed)
+    #[serde(default)]
+    pub cut: CutPolicy,
+
+    /// Bar being built when the checkpoint was taken
+    pub open_bar: Option<OpenBarCheckpoint>,
+
+    /// Previous bar closed on breach; the next trade opens a new bar
+    pub defer_open: bool,
+
+    /// Last processed `(timestamp, agg_trade_id)`
+    pub last_trade: Option<(i64, i64)>,
+}
+
+/// [`ThresholdPolicy`] state inside a [`ProcessorCheckpoint`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyCheckpoint {
+    /// [`ThresholdPolicy::name`] of the checkpointed policy
+    pub name: String,
+
+    /// [`ThresholdPolicy::checkpoint_state`] output
+    pub state: serde_json::Value,
+}
+
+/// Open bar inside a [`ProcessorCheckpoint`], with its fixed thresholds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenBarCheckpoint {
+    /// The range bar being constructed
+    pub bar: RangeBar,
+
+    /// Upper breach threshold (fixed from bar open)
+    pub upper_threshold: FixedPoint,
+
+    /// Lower breach threshold (fixed from bar open)
+    pub lower_threshold: FixedPoint,
+}
+
 /// Range bar processor with non-lookahead bias guarantee
+///
+/// The processor is stateful: the open bar, the defer-open flag and the last
+/// seen `(timestamp, sequence_id)` carry over between calls, so feeding a
+/// dataset in consecutive chunks yields exactly the same bars as one batch call.
 pub struct RangeBarProcessor {
-    /// Threshold in basis points (25 = 25 bps)
-    threshold_bps: u32,
+    /// Chooses the breach distances of each new bar
+    policy: Box<dyn ThresholdPolicy>,
+
+    /// Engine options
+    config: RangeBarProcessorConfig,
+
+    /// Session and size cuts applied on top of the threshold
+    cut: CutPolicy,
+
+    /// Bar currently being built (carried across calls)
+    current_bar: Option<RangeBarState>,
+
+    /// Previous bar closed on breach; the next trade opens a new bar
+    defer_open: bool,
+
+    /// Last processed `(timestamp, sequence_id)` for cross-call ordering validation
+    last_trade: Option<(i64, i64)>,
 }
 
 impl RangeBarProcessor {
@@ -20,47 +142,189 @@ impl RangeBarProcessor {
     ///
     /// # Arguments
     ///
-    /// * `threshold_bps` - Threshold value (25 for 25 bps)
-    pub fn new(threshold_bps: u32) -> Self {
-        Self { threshold_bps }
+    /// * `range` - Threshold value (`25` for 25 bps), a fractional
+    ///   [`Threshold`](crate::threshold::Threshold), asymmetric
+    ///   [`BreachThresholds`](crate::threshold::BreachThresholds) or an absolute [`BarRange`]
+    pub fn new(range: impl Into<BarRange>) -> Self {
+        Self::with_config(range, RangeBarProcessorConfig::default())
+    }
+
+    /// Create processor with explicit engine options
+    pub fn with_config(range: impl Into<BarRange>, config: RangeBarProcessorConfig) -> Self {
+        Self::with_policy(FixedThreshold(range.into()), config)
+    }
+
+    /// Create processor whose breach distances are chosen per bar by `policy`
+    ///
+    /// The policy is consulted once at each bar's opening trade and sees every
+    /// completed bar before the next one opens. The chosen distances are
+    /// recorded on each bar as its upper and lower thresholds.
+    pub fn with_policy(
+        policy: impl ThresholdPolicy + 'static,
+        config: RangeBarProcessorConfig,
+    ) -> Self {
+        Self {
+            policy: Box::new(policy),
+            config,
+            cut: CutPolicy::default(),
+            current_bar: None,
+            defer_open: false,
+            last_trade: None,
+        }
+    }
+
+    /// Force-close bars at session boundaries or size caps (see [`CutPolicy`])
+    ///
+    /// A cut bar ends before the trade that revealed the cut; that trade opens
+    /// the next bar.
+    pub fn with_cut_policy(mut self, cut: CutPolicy) -> Self {
+        self.cut = cut;
+        self
     }
 
     /// Process a single trade and return completed bar if any
     ///
     /// # Arguments
     ///
-    /// * `trade` - Single aggregated trade to process
+    /// * `trade` - Single trade to process
     ///
     /// # Returns
     ///
     /// `Some(RangeBar)` if a bar was completed, `None` otherwise
-    pub fn process_single_trade(
+    pub fn process_single_trade<T: Trade>(
         &mut self,
-        trade: AggTrade,
+        trade: T,
     ) -> Result<Option<RangeBar>, ProcessingError> {
-        // Create single-element slice and process
-        let trades = vec![trade];
-        let mut bars = self.process_trades(&trades)?;
+        self.validate_trade_ordering(std::slice::from_ref(&trade))?;
+        let mut bars = Vec::new();
+        self.process_trade(&trade, &mut bars)?;
+        Ok(bars.pop())
+    }
 
-        // Return the last completed bar if any
-        if bars.is_empty() {
-            Ok(None)
-        } else {
-            Ok(bars.pop())
-        }
+    /// Threshold policy in effect
+    pub fn policy(&self) -> &dyn ThresholdPolicy {
+        self.policy.as_ref()
+    }
+
+    /// Engine options in effect
+    pub fn config(&self) -> &RangeBarProcessorConfig {
+        &self.config
+    }
+
+    /// Cut rules in effect
+    pub fn cut_policy(&self) -> &CutPolicy {
+        &self.cut
     }
 
     /// Get any incomplete bar currently being processed
     pub fn get_incomplete_bar(&self) -> Option<RangeBar> {
-        // This would need internal state tracking - simplified for now
-        None
+        self.current_bar
+            .as_ref()
+            .map(|bar_state| bar_state.bar.clone())
+    }
+
+    /// Force-close the open bar at end of data
+    ///
+    /// The bar is returned with [`CloseReason::EndOfData`] and the next trade
+    /// opens a fresh bar. Ordering history is kept.
+    pub fn finish(&mut self) -> Option<RangeBar> {
+        self.defer_open = false;
+        self.current_bar
+            .take()
+            .map(|bar_state| closed_at_end_of_data(bar_state.bar))
+    }
+
+    /// Snapshot the processor state for a later [`restore`](Self::restore)
+    pub fn checkpoint(&self) -> ProcessorCheckpoint {
+        ProcessorCheckpoint {
+            version: CHECKPOINT_VERSION,
+            policy: PolicyCheckpoint {
+                name: self.policy.name().to_string(),
+                state: self.policy.checkpoint_state(),
+            },
+            config: self.config,
+            cut: self.cut.clone(),
+            open_bar: self
+                .current_bar
+                .as_ref()
+                .map(|bar_state| OpenBarCheckpoint {
+                    bar: bar_state.bar.clone(),
+                    upper_threshold: bar_state.upper_threshold,
+                    lower_threshold: bar_state.lower_threshold,
+                }),
+            defer_open: self.defer_open,
+            last_trade: self.last_trade,
+        }
+    }
+
+    /// Rebuild a processor from a [`checkpoint`](Self::checkpoint)
+    ///
+    /// The open bar keeps the thresholds it was opened with; they are not
+    /// recomputed from the current configuration. Only [`FixedThreshold`]
+    /// checkpoints can be restored this way; adaptive policies go through
+    /// [`restore_with_policy`](Self::restore_with_policy).
+    pub fn restore(checkpoint: ProcessorCheckpoint) -> Result<Self, ProcessingError> {
+        if checkpoint.policy.name != FixedThreshold::NAME {
+            return Err(ProcessingError::PolicyMismatch {
+                expected: FixedThreshold::NAME.to_string(),
+                found: checkpoint.policy.name,
+            });
+        }
+        Self::restore_with_policy(checkpoint, FixedThreshold(BarRange::default()))
+    }
+
+    /// Rebuild a processor from a checkpoint, loading the saved state into `policy`
+    ///
+    /// `policy` must be the same kind of policy that was checkpointed.
+    pub fn restore_with_policy(
+        checkpoint: ProcessorCheckpoint,
+        mut policy: impl ThresholdPolicy + 'static,
+    ) -> Result<Self, ProcessingError> {
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(ProcessingError::UnsupportedCheckpointVersion {
+                version: checkpoint.version,
+                supported: CHECKPOINT_VERSION,
+            });
+        }
+        if checkpoint.policy.name != policy.name() {
+            return Err(ProcessingError::PolicyMismatch {
+                expected: policy.name().to_string(),
+                found: checkpoint.policy.name,
+            });
+        }
+        policy.restore_state(checkpoint.policy.state).map_err(|e| {
+            ProcessingError::InvalidPolicyState {
+                policy: policy.name().to_string(),
+                message: e.to_string(),
+            }
+        })?;
+
+        Ok(Self {
+            policy: Box::new(policy),
+            config: checkpoint.config,
+            cut: checkpoint.cut,
+            current_bar: checkpoint.open_bar.map(|open_bar| RangeBarState {
+                bar: open_bar.bar,
+                upper_threshold: open_bar.upper_threshold,
+                lower_threshold: open_bar.lower_threshold,
+            }),
+            defer_open: checkpoint.defer_open,
+            last_trade: checkpoint.last_trade,
+        })
+    }
+
+    /// Discard the open bar and ordering history, returning to the initial state
+    pub fn reset(&mut self) {
+        self.current_bar = None;
+        self.defer_open = false;
+        self.last_trade = None;
     }
 
     /// Process trades into range bars including incomplete bars for analysis
     ///
     /// # Arguments
     ///
-    /// * `trades` - Slice of aggregated trades sorted by (timestamp, agg_trade_id)
+    /// * `trades` - Slice of trades sorted by (timestamp, sequence_id)
     ///
     /// # Returns
     ///
@@ -70,9 +334,9 @@ impl RangeBarProcessor {
     ///
     /// This method is for analysis purposes only. Incomplete bars violate the
     /// fundamental range bar algorithm and should not be used for production trading.
-    pub fn process_trades_with_incomplete(
+    pub fn process_trades_with_incomplete<T: Trade>(
         &mut self,
-        trades: &[AggTrade],
+        trades: &[T],
     ) -> Result<Vec<RangeBar>, ProcessingError> {
         self.process_trades_with_options(trades, true)
     }
@@ -81,14 +345,14 @@ impl RangeBarProcessor {
     ///
     /// # Arguments
     ///
-    /// * `trades` - Slice of aggregated trades sorted by (timestamp, agg_trade_id)
+    /// * `trades` - Slice of trades sorted by (timestamp, sequence_id)
     ///
     /// # Returns
     ///
     /// Vector of completed range bars (ONLY bars that breached thresholds)
-    pub fn process_trades(
+    pub fn process_trades<T: Trade>(
         &mut self,
-        trades: &[AggTrade],
+        trades: &[T],
     ) -> Result<Vec<RangeBar>, ProcessingError> {
         self.process_trades_with_options(trades, false)
     }
@@ -97,100 +361,263 @@ impl RangeBarProcessor {
     ///
     /// # Arguments
     ///
-    /// * `trades` - Slice of aggregated trades sorted by (timestamp, agg_trade_id)
+    /// * `trades` - Slice of trades sorted by (timestamp, sequence_id)
     /// * `include_incomplete` - Whether to include incomplete bars at end of processing
     ///
     /// # Returns
     ///
     /// Vector of range bars
-    pub fn process_trades_with_options(
+    ///
+    /// The incomplete bar is appended as a snapshot only; it stays open in the
+    /// processor and continues with the next call. On error the bars completed
+    /// earlier in the call are dropped; use
+    /// [`process_trades_into`](Self::process_trades_into) to keep them.
+    pub fn process_trades_with_options<T: Trade>(
         &mut self,
-        trades: &[AggTrade],
+        trades: &[T],
         include_incomplete: bool,
     ) -> Result<Vec<RangeBar>, ProcessingError> {
-        if trades.is_empty() {
-            return Ok(Vec::new());
+        let mut bars = Vec::with_capacity(trades.len() / 100); // Heuristic capacity
+        self.process_trades_into(trades, &mut bars)?;
+
+        // Add final partial bar only if explicitly requested
+        // This preserves algorithm integrity: bars should only close on threshold breach
+        if include_incomplete && let Some(bar) = self.get_incomplete_bar() {
+            bars.push(closed_at_end_of_data(bar));
         }
 
-        // Validate trades are sorted
-        self.validate_trade_ordering(trades)?;
+        Ok(bars)
+    }
 
-        let mut bars = Vec::with_capacity(trades.len() / 100); // Heuristic capacity
-        let mut current_bar: Option<RangeBarState> = None;
-        let mut defer_open = false;
+    /// Process trades, appending the completed bars to `bars`
+    ///
+    /// On error `bars` keeps every bar completed before the failure, including
+    /// one closed by the failing trade itself, and the processor stands just
+    /// before the failing trade: retrying from that trade continues the run.
+    pub fn process_trades_into<T: Trade>(
+        &mut self,
+        trades: &[T],
+        bars: &mut Vec<RangeBar>,
+    ) -> Result<(), ProcessingError> {
+        // Validate trades are sorted (including against the previous call)
+        self.validate_trade_ordering(trades)?;
 
         for trade in trades {
-            if defer_open {
-                // Previous bar closed, this trade opens new bar
-                current_bar = Some(RangeBarState::new(trade, self.threshold_bps));
-                defer_open = false;
-                continue;
+            self.process_trade(trade, bars)?;
+        }
+        Ok(())
+    }
+
+    /// Last processed `(timestamp, sequence_id)`
+    pub(crate) fn last_trade(&self) -> Option<(i64, i64)> {
+        self.last_trade
+    }
+
+    /// Advance the state machine by one trade already validated by the caller
+    ///
+    /// A completed bar is pushed to `bars`. Fallible work happens before the
+    /// state it guards changes, and the trade only counts as processed once it
+    /// succeeds. A bar closed by the trade is pushed before the next one opens,
+    /// so if opening fails the closed bar is kept and a retry of the trade opens
+    /// the next bar.
+    pub(crate) fn process_trade<T: Trade>(
+        &mut self,
+        trade: &T,
+        bars: &mut Vec<RangeBar>,
+    ) -> Result<(), ProcessingError> {
+        if self.defer_open {
+            // Previous bar closed, this trade opens new bar
+            self.current_bar = Some(RangeBarState::new(trade, self.policy.next_range(trade))?);
+            self.defer_open = false;
+        } else if let Some(reason) = self
+            .current_bar
+            .as_ref()
+            .and_then(|bar_state| self.cut.cut_reason(&bar_state.bar, trade))
+        {
+            // Session and size cuts close the bar before this trade, which opens the next
+            if let Some(bar_state) = self.current_bar.take() {
+                let mut cut = bar_state.bar;
+                cut.force_close(reason);
+                self.policy.on_bar_closed(&cut);
+                bars.push(cut);
             }
+            self.current_bar = Some(RangeBarState::new(trade, self.policy.next_range(trade))?);
+        } else if let Some(ref mut bar_state) = self.current_bar {
+            // Check if this trade breaches the threshold
+            let breach = bar_state.bar.breach_direction(
+                trade.price(),
+                bar_state.upper_threshold,
+                bar_state.lower_threshold,
+            );
 
-            match current_bar {
-                None => {
-                    // First bar initialization
-                    current_bar = Some(RangeBarState::new(trade, self.threshold_bps));
+            // Update bar with the trade (includes microstructure); leaves it untouched on overflow
+            bar_state
+                .bar
+                .update_with_trade(trade)
+                .map_err(overflow_at(trade))?;
+
+            if let Some(direction) = breach {
+                let level = match direction {
+                    BreachDirection::Up => bar_state.upper_threshold,
+                    BreachDirection::Down => bar_state.lower_threshold,
+                };
+                bar_state.bar.record_breach(direction, level);
+
+                // Validation: Ensure high/low include open/close extremes
+                debug_assert!(bar_state.bar.high >= bar_state.bar.open.max(bar_state.bar.close));
+                debug_assert!(bar_state.bar.low <= bar_state.bar.open.min(bar_state.bar.close));
+
+                // The policy sees the completed bar before the next one opens
+                if let Some(bar_state) = self.current_bar.take() {
+                    self.policy.on_bar_closed(&bar_state.bar);
+                    bars.push(bar_state.bar);
                 }
-                Some(ref mut bar_state) => {
-                    // Check if this trade breaches the threshold
-                    if bar_state.bar.is_breach(
-                        trade.price,
-                        bar_state.upper_threshold,
-                        bar_state.lower_threshold,
-                    ) {
-                        // Breach detected - update bar with breaching trade (includes microstructure)
-                        bar_state.bar.update_with_trade(trade);
-
-                        // Validation: Ensure high/low include open/close extremes
-                        debug_assert!(
-                            bar_state.bar.high >= bar_state.bar.open.max(bar_state.bar.close)
-                        );
-                        debug_assert!(
-                            bar_state.bar.low <= bar_state.bar.open.min(bar_state.bar.close)
-                        );
-
-                        bars.push(bar_state.bar.clone());
-                        current_bar = None;
-                        defer_open = true; // Next trade will open new bar
-                    } else {
-                        // No breach: normal update with microstructure calculations
-                        bar_state.bar.update_with_trade(trade);
+
+                match self.config.next_bar_open {
+                    NextBarOpen::NextTrade => {
+                        self.defer_open = true; // Next trade will open new bar
+                    }
+                    NextBarOpen::BreachingTrade => {
+                        let range = self.policy.next_range(trade);
+                        self.current_bar = Some(RangeBarState::new(trade, range)?);
                     }
                 }
             }
+        } else {
+            // First bar initialization
+            self.current_bar = Some(RangeBarState::new(trade, self.policy.next_range(trade))?);
         }
 
-        // Add final partial bar only if explicitly requested
-        // This preserves algorithm integrity: bars should only close on threshold breach
-        if include_incomplete && let Some(bar_state) = current_bar {
-            bars.push(bar_state.bar);
+        self.last_trade = Some((trade.timestamp(), trade.sequence_id()));
+        Ok(())
+    }
+
+    /// Validate that trades are properly sorted for deterministic processing
+    ///
+    /// The first trade is checked against the last trade seen by a previous call,
+    /// so ordering is enforced across chunk boundaries too.
+    fn validate_trade_ordering<T: Trade>(&self, trades: &[T]) -> Result<(), ProcessingError> {
+        validate_trade_ordering(self.last_trade, trades)
+    }
+}
+
+/// Check `trades` are sorted by `(timestamp, sequence_id)`, continuing from `last_trade`
+pub(crate) fn validate_trade_ordering<T: Trade>(
+    last_trade: Option<(i64, i64)>,
+    trades: &[T],
+) -> Result<(), ProcessingError> {
+    let mut prev = last_trade;
+
+    for (i, curr) in trades.iter().enumerate() {
+        let (curr_time, curr_id) = (curr.timestamp(), curr.sequence_id());
+
+        // Check ordering: (timestamp, sequence_id) ascending
+        if let Some((prev_time, prev_id)) = prev
+            && (curr_time < prev_time || (curr_time == prev_time && curr_id <= prev_id))
+        {
+            return Err(ProcessingError::UnsortedTrades {
+                index: i,
+                prev_time,
+                prev_id,
+                curr_time,
+                curr_id,
+            });
         }
 
+        prev = Some((curr_time, curr_id));
+    }
+
+    Ok(())
+}
+
+/// Bar engine interface shared by the streaming and export wrappers
+///
+/// Implemented by [`RangeBarProcessor`],
+/// [`EventBarProcessor`](crate::event_bars::EventBarProcessor),
+/// [`ImbalanceBarProcessor`](crate::imbalance_bars::ImbalanceBarProcessor) and
+/// [`RenkoProcessor`](crate::renko::RenkoProcessor); every implementation
+/// validates ordering across calls and emits [`RangeBar`]s.
+///
+/// Engines with several outputs, such as
+/// [`LadderProcessor`](crate::ladder::LadderProcessor), tag each bar with its
+/// output index through the `_routed` methods; single-output engines use the
+/// defaults, which route everything to output `0`.
+pub trait BarProcessor: Send + Sync {
+    /// Process a sorted chunk, returning the bars it completed
+    fn process_trades(&mut self, trades: &[AggTrade]) -> Result<Vec<RangeBar>, ProcessingError>;
+
+    /// Snapshot of the open bar, if any
+    fn get_incomplete_bar(&self) -> Option<RangeBar>;
+
+    /// Whether a bar is open, without cloning it
+    ///
+    /// The default takes a snapshot; engines override it with a field check.
+    fn has_open_bar(&self) -> bool {
+        self.get_incomplete_bar().is_some()
+    }
+
+    /// Force-close the open bar at end of data
+    fn finish(&mut self) -> Option<RangeBar>;
+
+    /// Like [`process_trades`](Self::process_trades), appending the open bar as an end-of-data snapshot
+    ///
+    /// The snapshot stays open in the processor and continues with the next call.
+    fn process_trades_with_incomplete(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<RangeBar>, ProcessingError> {
+        let mut bars = self.process_trades(trades)?;
+        bars.extend(self.get_incomplete_bar().map(closed_at_end_of_data));
         Ok(bars)
     }
 
-    /// Validate that trades are properly sorted for deterministic processing
-    fn validate_trade_ordering(&self, trades: &[AggTrade]) -> Result<(), ProcessingError> {
-        for i in 1..trades.len() {
-            let prev = &trades[i - 1];
-            let curr = &trades[i];
-
-            // Check ordering: (timestamp, agg_trade_id) ascending
-            if curr.timestamp < prev.timestamp
-                || (curr.timestamp == prev.timestamp && curr.agg_trade_id <= prev.agg_trade_id)
-            {
-                return Err(ProcessingError::UnsortedTrades {
-                    index: i,
-                    prev_time: prev.timestamp,
-                    prev_id: prev.agg_trade_id,
-                    curr_time: curr.timestamp,
-                    curr_id: curr.agg_trade_id,
-                });
-            }
+    /// Number of independent bar outputs
+    fn output_count(&self) -> usize {
+        1
+    }
+
+    /// Like [`process_trades`](Self::process_trades), tagging each bar with its output index
+    fn process_trades_routed(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<(usize, RangeBar)>, ProcessingError> {
+        Ok(self
+            .process_trades(trades)?
+            .into_iter()
+            .map(|bar| (0, bar))
+            .collect())
+    }
+
+    /// Like [`finish`](Self::finish), force-closing the open bar of every output
+    fn finish_routed(&mut self) -> Vec<(usize, RangeBar)> {
+        self.finish().into_iter().map(|bar| (0, bar)).collect()
+    }
+
+    /// Approximate bytes held in open bars and internal buffers
+    fn buffered_bytes(&self) -> usize {
+        if self.has_open_bar() {
+            std::mem::size_of::<RangeBar>()
+        } else {
+            0
         }
+    }
+}
 
-        Ok(())
+impl BarProcessor for RangeBarProcessor {
+    fn process_trades(&mut self, trades: &[AggTrade]) -> Result<Vec<RangeBar>, ProcessingError> {
+        RangeBarProcessor::process_trades(self, trades)
+    }
+
+    fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        RangeBarProcessor::get_incomplete_bar(self)
+    }
+
+    fn has_open_bar(&self) -> bool {
+        self.current_bar.is_some()
+    }
+
+    fn finish(&mut self) -> Option<RangeBar> {
+        RangeBarProcessor::finish(self)
     }
 }
 
@@ -208,20 +635,36 @@ struct RangeBarState {
 
 impl RangeBarState {
     /// Create new range bar state from opening trade
-    fn new(trade: &AggTrade, threshold_bps: u32) -> Self {
-        let bar = RangeBar::new(trade);
+    fn new<T: Trade>(trade: &T, range: BarRange) -> Result<Self, ProcessingError> {
+        let mut bar = RangeBar::new(trade);
 
         // Compute FIXED thresholds from opening price
-        let (upper_threshold, lower_threshold) = bar.open.compute_range_thresholds(threshold_bps);
+        let (upper_threshold, lower_threshold) = range
+            .checked_breach_levels(bar.open)
+            .map_err(overflow_at(trade))?;
+        bar.upper_threshold = Some(upper_threshold);
+        bar.lower_threshold = Some(lower_threshold);
 
-        Self {
+        Ok(Self {
             bar,
             upper_threshold,
             lower_threshold,
-        }
+        })
     }
 }
 
+/// Attribute a fixed-point overflow to the trade being processed
+pub(crate) fn overflow_at<T: Trade>(trade: &T) -> impl FnOnce(FixedPointError) -> ProcessingError {
+    let agg_trade_id = trade.sequence_id();
+    move |_| ProcessingError::Overflow { agg_trade_id }
+}
+
+/// Close an open bar at end of data
+pub(crate) fn closed_at_end_of_data(mut bar: RangeBar) -> RangeBar {
+    bar.force_close(CloseReason::EndOfData);
+    bar
+}
+
 /// Processing errors
 #[derive(Error, Debug)]
 pub enum ProcessingError {
@@ -241,6 +684,43 @@ pub enum ProcessingError {
 
     #[error("Invalid threshold: {threshold_bps} basis points")]
     InvalidThreshold { threshold_bps: u32 },
+
+    #[error("Unsupported checkpoint version {version} (supported: {supported})")]
+    UnsupportedCheckpointVersion { version: u32, supported: u32 },
+
+    #[error("Checkpoint threshold policy '{found}' does not match '{expected}'")]
+    PolicyMismatch { expected: String, found: String },
+
+    #[error("Invalid '{policy}' threshold policy state: {message}")]
+    InvalidPolicyState { policy: String, message: String },
+
+    #[error("Invalid event bar target: {rule} (must be positive)")]
+    InvalidEventTarget { rule: EventBarRule },
+
+    #[error("Invalid imbalance bar configuration: {0}")]
+    InvalidImbalanceConfig(String),
+
+    #[error(
+        "Trade ({timestamp}, {agg_trade_id}) arrived after ({released_time}, {released_id}) was released"
+    )]
+    LateTrade {
+        timestamp: i64,
+        agg_trade_id: i64,
+        released_time: i64,
+        released_id: i64,
+    },
+
+    #[error("Threshold ladder needs at least one threshold")]
+    EmptyLadder,
+
+    #[error("Threshold ladder has {expected} rungs but {found} sinks were given")]
+    SinkCountMismatch { expected: usize, found: usize },
+
+    #[error("Bar sink for rung {rung} failed: {message}")]
+    SinkFailed { rung: usize, message: String },
+
+    #[error("Fixed-point overflow processing agg_trade_id {agg_trade_id}")]
+    Overflow { agg_trade_id: i64 },
 }
 
 #[cfg(feature = "python")]
@@ -266,14 +746,133 @@ impl From<ProcessingError> for PyErr {
                     threshold_bps
                 ))
             }
+            ProcessingError::UnsupportedCheckpointVersion { version, supported } => {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unsupported checkpoint version {} (supported: {})",
+                    version, supported
+                ))
+            }
+            ProcessingError::PolicyMismatch { expected, found } => {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Checkpoint threshold policy '{}' does not match '{}'",
+                    found, expected
+                ))
+            }
+            ProcessingError::InvalidPolicyState { policy, message } => {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Invalid '{}' threshold policy state: {}",
+                    policy, message
+                ))
+            }
+            ProcessingError::InvalidImbalanceConfig(message) => {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Invalid imbalance bar configuration: {}",
+                    message
+                ))
+            }
+            ProcessingError::InvalidEventTarget { rule } => {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Invalid event bar target: {} (must be positive)",
+                    rule
+                ))
+            }
+            ProcessingError::LateTrade {
+                timestamp,
+                agg_trade_id,
+                released_time,
+                released_id,
+            } => pyo3::exceptions::PyValueError::new_err(format!(
+                "Trade ({}, {}) arrived after ({}, {}) was released",
+                timestamp, agg_trade_id, released_time, released_id
+            )),
+            ProcessingError::EmptyLadder => pyo3::exceptions::PyValueError::new_err(
+                "Threshold ladder needs at least one threshold",
+            ),
+            ProcessingError::SinkCountMismatch { expected, found } => {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Threshold ladder has {} rungs but {} sinks were given",
+                    expected, found
+                ))
+            }
+            ProcessingError::SinkFailed { rung, message } => pyo3::exceptions::PyIOError::new_err(
+                format!("Bar sink for rung {} failed: {}", rung, message),
+            ),
+            ProcessingError::Overflow { agg_trade_id } => {
+                pyo3::exceptions::PyOverflowError::new_err(format!(
+                    "Fixed-point overflow processing agg_trade_id {}",
+                    agg_trade_id
+                ))
+            }
         }
     }
 }
 
+/// Export-oriented range bar processor for streaming use cases
+///
+/// Buffering wrapper over [`RangeBarProcessor`]: completed bars accumulate
+/// until drained with [`get_all_completed_bars`](Self::get_all_completed_bars),
+/// while the open bar carries over between calls.
+pub struct ExportRangeBarProcessor {
+    processor: Box<dyn BarProcessor>,
+    completed_bars: Vec<RangeBar>,
+}
+
+impl ExportRangeBarProcessor {
+    /// Create new export processor with given threshold
+    pub fn new(range: impl Into<BarRange>) -> Self {
+        Self::with_config(range, RangeBarProcessorConfig::default())
+    }
+
+    /// Create export processor with explicit engine options
+    pub fn with_config(range: impl Into<BarRange>, config: RangeBarProcessorConfig) -> Self {
+        Self::with_processor(RangeBarProcessor::with_config(range, config))
+    }
+
+    /// Buffer the output of any bar engine (e.g. tick, volume or dollar bars)
+    pub fn with_processor(processor: impl BarProcessor + 'static) -> Self {
+        Self {
+            processor: Box::new(processor),
+            completed_bars: Vec::new(),
+        }
+    }
+
+    /// Process trades continuously, buffering completed bars
+    ///
+    /// State is preserved across calls, so chunked input yields the same bars
+    /// as a single call over the joined slice.
+    pub fn process_trades_continuously(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<(), ProcessingError> {
+        let bars = self.processor.process_trades(trades)?;
+        self.completed_bars.extend(bars);
+        Ok(())
+    }
+
+    /// Get all completed bars accumulated so far
+    /// This drains the internal buffer to avoid memory leaks
+    pub fn get_all_completed_bars(&mut self) -> Vec<RangeBar> {
+        std::mem::take(&mut self.completed_bars)
+    }
+
+    /// Get incomplete bar if exists (for final bar processing)
+    pub fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        self.processor.get_incomplete_bar()
+    }
+
+    /// Force-close the open bar at end of data (see [`RangeBarProcessor::finish`])
+    pub fn finish(&mut self) -> Option<RangeBar> {
+        self.processor.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cut_policy::SessionBoundary;
     use crate::fixed_point::FixedPoint;
+    use crate::threshold::{BreachThresholds, Threshold};
+    use crate::types::BreachDirection;
 
     fn create_test_trade(id: i64, price: &str, volume: &str, timestamp: i64) -> AggTrade {
         AggTrade {
@@ -306,6 +905,7 @@ mod tests {
         );
 
         // Test analysis mode: incomplete bar should be available for analysis
+        let mut processor = RangeBarProcessor::new(25);
         let bars_with_incomplete = processor.process_trades_with_incomplete(&trades).unwrap();
         assert_eq!(
             bars_with_incomplete.len(),
@@ -320,6 +920,217 @@ mod tests {
         assert_eq!(bar.close.to_string(), "49900.00000000");
     }
 
+    #[test]
+    fn test_fractional_bps_threshold() {
+        let mut processor = RangeBarProcessor::new(Threshold::from_decibps(5)); // 0.5 bps
+
+        let trades = vec![
+            create_test_trade(1, "50000.0", "1.0", 1000), // Open
+            create_test_trade(2, "50002.0", "1.0", 2000), // +0.4 bps
+            create_test_trade(3, "49997.5", "1.0", 3000), // -0.5 bps BREACH
+            create_test_trade(4, "49998.0", "1.0", 4000), // New bar
+        ];
+
+        let bars = processor.process_trades(&trades).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].close.to_string(), "49997.50000000");
+        assert_eq!(
+            bars[0].upper_threshold,
+            Some(FixedPoint::from_str("50002.5").unwrap())
+        );
+        assert_eq!(
+            bars[0].lower_threshold,
+            Some(FixedPoint::from_str("49997.5").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_close_metadata() {
+        let mut processor = RangeBarProcessor::new(25);
+
+        let trades = vec![
+            create_test_trade(1, "50000.0", "1.0", 1000), // Open: 49875 / 50125
+            create_test_trade(2, "50150.0", "1.0", 2000), // Up breach, 25 past the level
+            create_test_trade(3, "50000.0", "1.0", 3000), // Open: 49875 / 50125
+            create_test_trade(4, "49875.0", "1.0", 4000), // Down breach exactly at the level
+            create_test_trade(5, "49900.0", "1.0", 5000), // Open (left incomplete)
+        ];
+
+        let bars = processor.process_trades(&trades).unwrap();
+        assert_eq!(bars.len(), 2);
+
+        assert_eq!(bars[0].close_reason, Some(CloseReason::Breach));
+        assert_eq!(bars[0].close_direction, Some(BreachDirection::Up));
+        assert_eq!(
+            bars[0].upper_threshold.unwrap().to_string(),
+            "50125.00000000"
+        );
+        assert_eq!(
+            bars[0].lower_threshold.unwrap().to_string(),
+            "49875.00000000"
+        );
+        assert_eq!(bars[0].overshoot.unwrap().to_string(), "25.00000000");
+        assert_eq!(bars[0].overshoot_bps, Some(5.0));
+
+        assert_eq!(bars[1].close_direction, Some(BreachDirection::Down));
+        assert_eq!(bars[1].overshoot, Some(FixedPoint(0)));
+        assert_eq!(bars[1].overshoot_bps, Some(0.0));
+
+        // The open bar carries no close metadata until it is forced closed
+        let open = processor.get_incomplete_bar().unwrap();
+        assert_eq!(open.close_reason, None);
+        let last = processor.finish().unwrap();
+        assert_eq!(last.close_reason, Some(CloseReason::EndOfData));
+        assert_eq!((last.close_direction, last.overshoot), (None, None));
+        assert!(processor.get_incomplete_bar().is_none());
+
+        // Metadata survives CSV and JSON export
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.serialize(&bars[0]).unwrap();
+        wtr.serialize(&last).unwrap();
+        let csv = String::from_utf8(wtr.into_inner().unwrap()).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().ends_with(
+            "close_direction,upper_threshold,lower_threshold,close_reason,overshoot,overshoot_bps,missing_agg_trade_ids"
+        ));
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .ends_with(",up,5012500000000,4987500000000,breach,2500000000,5.0,")
+        );
+        assert!(lines.next().unwrap().ends_with(",end_of_data,,,"));
+
+        let json = serde_json::to_string(&bars[0]).unwrap();
+        assert_eq!(serde_json::from_str::<RangeBar>(&json).unwrap(), bars[0]);
+    }
+
+    #[test]
+    fn test_overflow_is_an_error_not_a_wrap() {
+        // Two trades whose volumes sum past i64::MAX raw units (~92 billion)
+        let mut processor = RangeBarProcessor::new(25);
+        let trades = vec![
+            create_test_trade(1, "0.00001", "90000000000.0", 1000),
+            create_test_trade(2, "0.00001", "90000000000.0", 2000),
+        ];
+        assert!(matches!(
+            processor.process_trades(&trades),
+            Err(ProcessingError::Overflow { agg_trade_id: 2 })
+        ));
+
+        // The open bar keeps the totals from before the failing trade
+        let bar = processor.get_incomplete_bar().unwrap();
+        assert_eq!(bar.volume, FixedPoint::from_str("90000000000.0").unwrap());
+
+        // Breach levels of an extreme price cannot be represented
+        let mut processor = RangeBarProcessor::new(25);
+        let extreme = AggTrade {
+            price: FixedPoint(i64::MAX - 1),
+            ..create_test_trade(1, "1.0", "1.0", 1000)
+        };
+        assert!(matches!(
+            processor.process_single_trade(extreme),
+            Err(ProcessingError::Overflow { agg_trade_id: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_cut_policy_closes_without_breach() {
+        let day = 86_400_000;
+        let mut processor = RangeBarProcessor::new(25).with_cut_policy(CutPolicy {
+            session: Some(SessionBoundary::UtcDay),
+            max_duration_ms: Some(10_000),
+            max_trades: Some(3),
+        });
+
+        let trades = vec![
+            create_test_trade(1, "50000.0", "1.0", day - 2_000),
+            create_test_trade(2, "50010.0", "1.0", day + 1_000), // New UTC day: cut before
+            create_test_trade(3, "50020.0", "1.0", day + 20_000), // 19s after open: cut before
+            create_test_trade(4, "50030.0", "1.0", day + 21_000),
+            create_test_trade(5, "50040.0", "1.0", day + 22_000), // Third trade in the bar
+            create_test_trade(6, "50050.0", "1.0", day + 23_000), // Trade cap reached: cut before
+            create_test_trade(7, "50200.0", "1.0", day + 24_000), // Breach
+        ];
+
+        let bars = processor.process_trades(&trades).unwrap();
+        let summary: Vec<_> = bars
+            .iter()
+            .map(|b| (b.first_id, b.last_id, b.close_reason))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, 1, Some(CloseReason::SessionCut)),
+                (2, 2, Some(CloseReason::MaxDuration)),
+                (3, 5, Some(CloseReason::MaxTrades)),
+                (6, 7, Some(CloseReason::Breach)),
+            ]
+        );
+
+        // Cut bars are not breaches
+        for bar in &bars[..3] {
+            assert_eq!((bar.close_direction, bar.overshoot), (None, None));
+        }
+        assert_eq!(bars[3].close_direction, Some(BreachDirection::Up));
+
+        // Cuts survive checkpoint and restore
+        let restored = RangeBarProcessor::restore(processor.checkpoint()).unwrap();
+        assert_eq!(restored.cut_policy(), processor.cut_policy());
+    }
+
+    #[test]
+    fn test_asymmetric_thresholds() {
+        let thresholds =
+            BreachThresholds::asymmetric(Threshold::from_bps(30), Threshold::from_bps(20));
+        let mut processor = RangeBarProcessor::new(thresholds);
+
+        let trades = vec![
+            create_test_trade(1, "50000.0", "1.0", 1000),  // Open
+            create_test_trade(2, "50100.0", "1.0", 2000),  // +20 bps: below +30 bps
+            create_test_trade(3, "49900.0", "1.0", 3000),  // -20 bps BREACH (down)
+            create_test_trade(4, "49950.0", "1.0", 4000),  // New bar open
+            create_test_trade(5, "49850.2", "1.0", 5000),  // -19.98 bps: no breach
+            create_test_trade(6, "50099.85", "1.0", 6000), // +30 bps BREACH (up)
+            create_test_trade(7, "50000.0", "1.0", 7000),  // New bar (incomplete)
+        ];
+
+        let bars = processor.process_trades_with_incomplete(&trades).unwrap();
+        assert_eq!(bars.len(), 3);
+
+        assert_eq!(bars[0].close.to_string(), "49900.00000000");
+        assert_eq!(bars[0].close_direction, Some(BreachDirection::Down));
+
+        assert_eq!(bars[1].open.to_string(), "49950.00000000");
+        assert_eq!(bars[1].close.to_string(), "50099.85000000");
+        assert_eq!(bars[1].close_direction, Some(BreachDirection::Up));
+
+        // Open bar has no close direction yet
+        assert_eq!(bars[2].close_direction, None);
+    }
+
+    #[test]
+    fn test_tick_range_bars() {
+        // 5 ticks of 0.1 = 0.5 absolute range, independent of price level
+        let range = BarRange::ticks(5, FixedPoint::from_str("0.1").unwrap());
+        let mut processor = RangeBarProcessor::new(range);
+
+        let trades = vec![
+            create_test_trade(1, "50000.0", "1.0", 1000), // Open
+            create_test_trade(2, "50000.4", "1.0", 2000), // +4 ticks
+            create_test_trade(3, "50000.5", "1.0", 3000), // +5 ticks BREACH (up)
+            create_test_trade(4, "50001.0", "1.0", 4000), // New bar open
+            create_test_trade(5, "50000.5", "1.0", 5000), // -5 ticks BREACH (down)
+        ];
+
+        let bars = processor.process_trades(&trades).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].close.to_string(), "50000.50000000");
+        assert_eq!(bars[0].close_direction, Some(BreachDirection::Up));
+        assert_eq!(bars[1].open.to_string(), "50001.00000000");
+        assert_eq!(bars[1].close_direction, Some(BreachDirection::Down));
+    }
+
     #[test]
     fn test_exact_breach_upward() {
         let mut processor = RangeBarProcessor::new(25); // 25 bps
@@ -347,6 +1158,7 @@ mod tests {
         assert_eq!(bar1.low.to_string(), "50000.00000000");
 
         // Test analysis mode: includes incomplete second bar
+        let mut processor = RangeBarProcessor::new(25);
         let bars_with_incomplete = processor.process_trades_with_incomplete(&trades).unwrap();
         assert_eq!(
             bars_with_incomplete.len(),
@@ -422,12 +1234,289 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unsorted_across_chunk_boundary() {
+        let mut processor = RangeBarProcessor::new(25);
+
+        processor
+            .process_trades(&[create_test_trade(1, "50000.0", "1.0", 2000)])
+            .unwrap();
+
+        // Earlier timestamp than the last trade of the previous chunk
+        let result = processor.process_trades(&[create_test_trade(2, "50100.0", "1.0", 1000)]);
+        match result {
+            Err(ProcessingError::UnsortedTrades {
+                index,
+                prev_time,
+                curr_time,
+                ..
+            }) => {
+                assert_eq!(index, 0);
+                assert_eq!(prev_time, 2000);
+                assert_eq!(curr_time, 1000);
+            }
+            _ => panic!("Expected UnsortedTrades error across chunk boundary"),
+        }
+
+        // Duplicate of the last trade is rejected by process_single_trade too
+        let result = processor.process_single_trade(create_test_trade(1, "50000.0", "1.0", 2000));
+        assert!(matches!(
+            result,
+            Err(ProcessingError::UnsortedTrades { .. })
+        ));
+    }
+
+    fn create_oscillating_trades(count: i64) -> Vec<AggTrade> {
+        (0..count)
+            .map(|i| {
+                // Saw-tooth path that breaches 25 bps every few trades in both directions
+                let offset = ((i % 17) - 8) * 20;
+                let price = format!("{}.0", 50000 + offset + (i / 17) * 7);
+                create_test_trade(i + 1, &price, "0.5", 1000 + i * 10)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunked_processing_matches_batch() {
+        let trades = create_oscillating_trades(1000);
+
+        let mut batch_processor = RangeBarProcessor::new(25);
+        let batch_bars = batch_processor.process_trades(&trades).unwrap();
+        assert!(batch_bars.len() > 10, "Test data should produce many bars");
+
+        for chunk_size in [1, 2, 7, 64, 333] {
+            let mut processor = RangeBarProcessor::new(25);
+            let mut chunked_bars = Vec::new();
+            for chunk in trades.chunks(chunk_size) {
+                chunked_bars.extend(processor.process_trades(chunk).unwrap());
+            }
+
+            assert_eq!(batch_bars, chunked_bars, "chunk size {}", chunk_size);
+            assert_eq!(
+                batch_processor.get_incomplete_bar(),
+                processor.get_incomplete_bar()
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_trade_processing_matches_batch() {
+        let trades = create_oscillating_trades(500);
+
+        let mut batch_processor = RangeBarProcessor::new(25);
+        let mut batch_bars = batch_processor
+            .process_trades_with_incomplete(&trades)
+            .unwrap();
+
+        let mut processor = RangeBarProcessor::new(25);
+        let mut single_bars = Vec::new();
+        for trade in &trades {
+            if let Some(bar) = processor.process_single_trade(trade.clone()).unwrap() {
+                single_bars.push(bar);
+            }
+        }
+        single_bars.extend(processor.finish());
+
+        assert_eq!(batch_bars, single_bars);
+
+        // Incomplete bar is a snapshot: it stays open after being reported
+        let incomplete = batch_bars.pop().unwrap();
+        assert_eq!(
+            batch_processor.get_incomplete_bar().unwrap().first_id,
+            incomplete.first_id
+        );
+    }
+
+    #[test]
+    fn test_reset_clears_state() {
+        let mut processor = RangeBarProcessor::new(25);
+        let trades = create_oscillating_trades(10);
+
+        processor.process_trades(&trades).unwrap();
+        assert!(processor.get_incomplete_bar().is_some());
+
+        processor.reset();
+        assert!(processor.get_incomplete_bar().is_none());
+
+        // Same trades are accepted again after reset
+        assert!(processor.process_trades(&trades).is_ok());
+    }
+
+    #[test]
+    fn test_checkpoint_restore_matches_uninterrupted() {
+        let trades = create_oscillating_trades(1000);
+        let expected = RangeBarProcessor::new(25)
+            .process_trades_with_incomplete(&trades)
+            .unwrap();
+
+        for split in [0, 1, 17, 500, 999, 1000] {
+            let mut first = RangeBarProcessor::new(25);
+            let mut bars = first.process_trades(&trades[..split]).unwrap();
+
+            // Round-trip through JSON as a resumed process would
+            let json = serde_json::to_string(&first.checkpoint()).unwrap();
+            let checkpoint: ProcessorCheckpoint = serde_json::from_str(&json).unwrap();
+            assert_eq!(checkpoint, first.checkpoint());
+
+            let mut resumed = RangeBarProcessor::restore(checkpoint).unwrap();
+            bars.extend(
+                resumed
+                    .process_trades_with_incomplete(&trades[split..])
+                    .unwrap(),
+            );
+
+            assert_eq!(bars, expected, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn test_restore_preserves_ordering_and_rejects_unknown_version() {
+        let trades = create_oscillating_trades(20);
+        let mut processor = RangeBarProcessor::new(25);
+        processor.process_trades(&trades).unwrap();
+
+        // Ordering history survives the round trip
+        let mut restored = RangeBarProcessor::restore(processor.checkpoint()).unwrap();
+        assert!(restored.process_trades(&trades[19..]).is_err());
+
+        let mut checkpoint = processor.checkpoint();
+        checkpoint.version = CHECKPOINT_VERSION + 1;
+        assert!(matches!(
+            RangeBarProcessor::restore(checkpoint),
+            Err(ProcessingError::UnsupportedCheckpointVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_next_bar_open_breaching_trade() {
+        let config = RangeBarProcessorConfig {
+            next_bar_open: NextBarOpen::BreachingTrade,
+        };
+        let mut processor = RangeBarProcessor::with_config(25, config);
+
+        let trades = vec![
+            create_test_trade(1, "50000.0", "1.0", 1000), // Open
+            create_test_trade(2, "50125.0", "2.0", 2000), // +0.25% BREACH
+            create_test_trade(3, "50200.0", "1.0", 3000),
+        ];
+
+        let bars = processor.process_trades_with_incomplete(&trades).unwrap();
+        assert_eq!(bars.len(), 2);
+
+        // Breaching trade closes the first bar and opens the second one
+        assert_eq!(bars[0].last_id, 2);
+        assert_eq!(bars[1].first_id, 2);
+        assert_eq!(bars[1].open.to_string(), "50125.00000000");
+        assert_eq!(bars[1].volume.to_string(), "3.00000000");
+    }
+
+    #[test]
+    fn test_overflow_keeps_completed_bars_and_allows_retry() {
+        // Two of these fit in a bar's volume, three do not
+        let huge = FixedPoint(i64::MAX / 2 - 1_000_000_000);
+        let trades = vec![
+            create_test_trade(1, "50000.0", "1.0", 1000),
+            create_test_trade(2, "50125.0", "1.0", 2000), // Closes bar 1..=2
+            AggTrade {
+                volume: huge,
+                ..create_test_trade(3, "50100.0", "1.0", 3000)
+            },
+            AggTrade {
+                volume: huge,
+                ..create_test_trade(4, "50100.0", "1.0", 4000)
+            },
+            AggTrade {
+                volume: huge,
+                ..create_test_trade(5, "50100.0", "1.0", 5000)
+            }, // Volume overflows
+        ];
+
+        let mut processor = RangeBarProcessor::new(25);
+        let mut bars = Vec::new();
+        assert!(matches!(
+            processor.process_trades_into(&trades, &mut bars),
+            Err(ProcessingError::Overflow { agg_trade_id: 5 })
+        ));
+        assert_eq!(bars.len(), 1);
+        assert_eq!((bars[0].first_id, bars[0].last_id), (1, 2));
+        assert_eq!(processor.get_incomplete_bar().unwrap().last_id, 4);
+
+        // The failing trade was not consumed: retrying it reports the same overflow
+        assert!(matches!(
+            processor.process_trades_into(&trades[4..], &mut bars),
+            Err(ProcessingError::Overflow { agg_trade_id: 5 })
+        ));
+
+        // Skipping it continues the open bar
+        let next = create_test_trade(6, "50100.0", "1.0", 6000);
+        processor.process_trades_into(&[next], &mut bars).unwrap();
+        assert_eq!(processor.get_incomplete_bar().unwrap().last_id, 6);
+    }
+
+    #[test]
+    fn test_failed_open_keeps_the_bar_the_trade_closed() {
+        let config = RangeBarProcessorConfig {
+            next_bar_open: NextBarOpen::BreachingTrade,
+        };
+        let mut processor = RangeBarProcessor::with_config(25, config);
+
+        // The breaching price fits, but its own breach levels overflow
+        let trades = vec![
+            AggTrade {
+                price: FixedPoint(i64::MAX / 2),
+                ..create_test_trade(1, "1.0", "1.0", 1000)
+            },
+            AggTrade {
+                price: FixedPoint(i64::MAX - 1),
+                ..create_test_trade(2, "1.0", "1.0", 2000)
+            },
+        ];
+
+        let mut bars = Vec::new();
+        assert!(matches!(
+            processor.process_trades_into(&trades, &mut bars),
+            Err(ProcessingError::Overflow { agg_trade_id: 2 })
+        ));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].last_id, 2);
+        assert_eq!(bars[0].close_reason, Some(CloseReason::Breach));
+        assert!(processor.get_incomplete_bar().is_none());
+
+        // The retry only has the opening left to do
+        assert!(matches!(
+            processor.process_trades_into(&trades[1..], &mut bars),
+            Err(ProcessingError::Overflow { agg_trade_id: 2 })
+        ));
+        assert_eq!(bars.len(), 1);
+    }
+
+    #[test]
+    fn test_export_processor_matches_core() {
+        let trades = create_oscillating_trades(1000);
+
+        let mut core = RangeBarProcessor::new(25);
+        let core_bars = core.process_trades(&trades).unwrap();
+
+        let mut export_processor = ExportRangeBarProcessor::new(25);
+        for chunk in trades.chunks(97) {
+            export_processor.process_trades_continuously(chunk).unwrap();
+        }
+
+        assert_eq!(core_bars, export_processor.get_all_completed_bars());
+        assert_eq!(
+            core.get_incomplete_bar(),
+            export_processor.get_incomplete_bar()
+        );
+        assert!(export_processor.get_all_completed_bars().is_empty());
+    }
+
     #[test]
     fn test_threshold_calculation() {
-        let processor = RangeBarProcessor::new(25); // 0.25%
+        let mut processor = RangeBarProcessor::new(25); // 0.25%
 
         let trade = create_test_trade(1, "50000.0", "1.0", 1000);
-        let bar_state = RangeBarState::new(&trade, processor.threshold_bps);
+        let bar_state = RangeBarState::new(&trade, processor.policy.next_range(&trade)).unwrap();
 
         // 50000 * 0.0025 = 125
         assert_eq!(bar_state.upper_threshold.to_string(), "50125.00000000");
@@ -437,7 +1526,7 @@ mod tests {
     #[test]
     fn test_empty_trades() {
         let mut processor = RangeBarProcessor::new(25);
-        let bars = processor.process_trades(&[]).unwrap();
+        let bars = processor.process_trades::<AggTrade>(&[]).unwrap();
         assert_eq!(bars.len(), 0);
     }
 
@@ -509,7 +1598,9 @@ mod tests {
             trades.len()
         );
 
-        export_processor.process_trades_continuously(&trades);
+        export_processor
+            .process_trades_continuously(&trades)
+            .expect("trades should be sorted");
         let bars = export_processor.get_all_completed_bars();
 
         println!(
@@ -534,251 +1625,3 @@ mod tests {
         );
     }
 }
-
-/// Internal state for range bar construction with fixed-point precision
-#[derive(Debug, Clone)]
-struct InternalRangeBar {
-    open_time: i64,
-    close_time: i64,
-    open: FixedPoint,
-    high: FixedPoint,
-    low: FixedPoint,
-    close: FixedPoint,
-    volume: FixedPoint,
-    turnover: i128,
-    trade_count: i64,
-    first_id: i64,
-    last_id: i64,
-    /// Volume from buy-side trades (is_buyer_maker = false)
-    buy_volume: FixedPoint,
-    /// Volume from sell-side trades (is_buyer_maker = true)
-    sell_volume: FixedPoint,
-    /// Number of buy-side trades
-    buy_trade_count: i64,
-    /// Number of sell-side trades
-    sell_trade_count: i64,
-    /// Volume Weighted Average Price
-    vwap: FixedPoint,
-    /// Turnover from buy-side trades
-    buy_turnover: i128,
-    /// Turnover from sell-side trades
-    sell_turnover: i128,
-}
-
-/// Export-oriented range bar processor for streaming use cases
-///
-/// This implementation uses the proven fixed-point arithmetic algorithm
-/// that achieves 100% breach consistency compliance in multi-year processing.
-pub struct ExportRangeBarProcessor {
-    threshold_bps: u32,
-    current_bar: Option<InternalRangeBar>,
-    completed_bars: Vec<RangeBar>,
-}
-
-impl ExportRangeBarProcessor {
-    /// Create new export processor with given threshold
-    pub fn new(threshold_bps: u32) -> Self {
-        Self {
-            threshold_bps,
-            current_bar: None,
-            completed_bars: Vec::new(),
-        }
-    }
-
-    /// Process trades continuously using proven fixed-point algorithm
-    /// This method maintains 100% breach consistency by using precise integer arithmetic
-    pub fn process_trades_continuously(&mut self, trades: &[AggTrade]) {
-        for trade in trades {
-            self.process_single_trade_fixed_point(trade);
-        }
-    }
-
-    /// Process single trade using proven fixed-point algorithm (100% breach consistency)
-    fn process_single_trade_fixed_point(&mut self, trade: &AggTrade) {
-        if self.current_bar.is_none() {
-            // Start new bar
-            let trade_turnover = (trade.price.to_f64() * trade.volume.to_f64()) as i128;
-
-            self.current_bar = Some(InternalRangeBar {
-                open_time: trade.timestamp,
-                close_time: trade.timestamp,
-                open: trade.price,
-                high: trade.price,
-                low: trade.price,
-                close: trade.price,
-                volume: trade.volume,
-                turnover: trade_turnover,
-                trade_count: 1,
-                first_id: trade.agg_trade_id,
-                last_id: trade.agg_trade_id,
-                // Market microstructure fields
-                buy_volume: if trade.is_buyer_maker {
-                    FixedPoint(0)
-                } else {
-                    trade.volume
-                },
-                sell_volume: if trade.is_buyer_maker {
-                    trade.volume
-                } else {
-                    FixedPoint(0)
-                },
-                buy_trade_count: if trade.is_buyer_maker { 0 } else { 1 },
-                sell_trade_count: if trade.is_buyer_maker { 1 } else { 0 },
-                vwap: trade.price,
-                buy_turnover: if trade.is_buyer_maker {
-                    0
-                } else {
-                    trade_turnover
-                },
-                sell_turnover: if trade.is_buyer_maker {
-                    trade_turnover
-                } else {
-                    0
-                },
-            });
-            return;
-        }
-
-        // Process existing bar - work with reference
-        let bar = self.current_bar.as_mut().unwrap();
-        let trade_turnover = (trade.price.to_f64() * trade.volume.to_f64()) as i128;
-
-        // CRITICAL FIX: Use fixed-point integer arithmetic for precise threshold calculation
-        let price_val = trade.price.0;
-        let bar_open_val = bar.open.0;
-        let threshold_bps = self.threshold_bps as i64;
-        let upper_threshold = bar_open_val + (bar_open_val * threshold_bps) / 1_000_000;
-        let lower_threshold = bar_open_val - (bar_open_val * threshold_bps) / 1_000_000;
-
-        // Update bar with new trade
-        bar.close_time = trade.timestamp;
-        bar.close = trade.price;
-        bar.volume.0 += trade.volume.0;
-        bar.turnover += trade_turnover;
-        bar.trade_count += 1;
-        bar.last_id = trade.agg_trade_id;
-
-        // Update high/low
-        if price_val > bar.high.0 {
-            bar.high = trade.price;
-        }
-        if price_val < bar.low.0 {
-            bar.low = trade.price;
-        }
-
-        // Update market microstructure
-        if trade.is_buyer_maker {
-            bar.sell_volume.0 += trade.volume.0;
-            bar.sell_turnover += trade_turnover;
-            bar.sell_trade_count += 1;
-        } else {
-            bar.buy_volume.0 += trade.volume.0;
-            bar.buy_turnover += trade_turnover;
-            bar.buy_trade_count += 1;
-        }
-
-        // CRITICAL: Fixed-point threshold breach detection (matches proven 100% compliance algorithm)
-        if price_val >= upper_threshold || price_val <= lower_threshold {
-            // Close current bar and move to completed
-            let completed_bar = self.current_bar.take().unwrap();
-
-            // Convert to export format
-            let export_bar = RangeBar {
-                open_time: completed_bar.open_time,
-                close_time: completed_bar.close_time,
-                open: completed_bar.open,
-                high: completed_bar.high,
-                low: completed_bar.low,
-                close: completed_bar.close,
-                volume: completed_bar.volume,
-                turnover: completed_bar.turnover,
-                trade_count: completed_bar.trade_count,
-                first_id: completed_bar.first_id,
-                last_id: completed_bar.last_id,
-                // Market microstructure fields
-                buy_volume: completed_bar.buy_volume,
-                sell_volume: completed_bar.sell_volume,
-                buy_trade_count: completed_bar.buy_trade_count,
-                sell_trade_count: completed_bar.sell_trade_count,
-                vwap: completed_bar.vwap,
-                buy_turnover: completed_bar.buy_turnover,
-                sell_turnover: completed_bar.sell_turnover,
-            };
-
-            self.completed_bars.push(export_bar);
-
-            // Start new bar with breaching trade
-            let initial_buy_turnover = if trade.is_buyer_maker {
-                0
-            } else {
-                trade_turnover
-            };
-            let initial_sell_turnover = if trade.is_buyer_maker {
-                trade_turnover
-            } else {
-                0
-            };
-
-            self.current_bar = Some(InternalRangeBar {
-                open_time: trade.timestamp,
-                close_time: trade.timestamp,
-                open: trade.price,
-                high: trade.price,
-                low: trade.price,
-                close: trade.price,
-                volume: trade.volume,
-                turnover: trade_turnover,
-                trade_count: 1,
-                first_id: trade.agg_trade_id,
-                last_id: trade.agg_trade_id,
-                // Market microstructure fields
-                buy_volume: if trade.is_buyer_maker {
-                    FixedPoint(0)
-                } else {
-                    trade.volume
-                },
-                sell_volume: if trade.is_buyer_maker {
-                    trade.volume
-                } else {
-                    FixedPoint(0)
-                },
-                buy_trade_count: if trade.is_buyer_maker { 0 } else { 1 },
-                sell_trade_count: if trade.is_buyer_maker { 1 } else { 0 },
-                vwap: trade.price,
-                buy_turnover: initial_buy_turnover,
-                sell_turnover: initial_sell_turnover,
-            });
-        }
-    }
-
-    /// Get all completed bars accumulated so far
-    /// This drains the internal buffer to avoid memory leaks
-    pub fn get_all_completed_bars(&mut self) -> Vec<RangeBar> {
-        std::mem::take(&mut self.completed_bars)
-    }
-
-    /// Get incomplete bar if exists (for final bar processing)
-    pub fn get_incomplete_bar(&mut self) -> Option<RangeBar> {
-        self.current_bar.as_ref().map(|incomplete| RangeBar {
-            open_time: incomplete.open_time,
-            close_time: incomplete.close_time,
-            open: incomplete.open,
-            high: incomplete.high,
-            low: incomplete.low,
-            close: incomplete.close,
-            volume: incomplete.volume,
-            turnover: incomplete.turnover,
-            trade_count: incomplete.trade_count,
-            first_id: incomplete.first_id,
-            last_id: incomplete.last_id,
-            // Market microstructure fields
-            buy_volume: incomplete.buy_volume,
-            sell_volume: incomplete.sell_volume,
-            buy_trade_count: incomplete.buy_trade_count,
-            sell_trade_count: incomplete.sell_trade_count,
-            vwap: incomplete.vwap,
-            buy_turnover: incomplete.buy_turnover,
-            sell_turnover: incomplete.sell_turnover,
-        })
-    }
-}
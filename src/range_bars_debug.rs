@@ -85,7 +85,8 @@ fn test_fixed_algorithm_behavior() {
         }
     }
 
-    // Test with analysis mode (includes incomplete bars)
+    // Test with analysis mode (includes incomplete bars) on a fresh processor
+    let mut processor = RangeBarProcessor::new(5000);
     let bars_with_incomplete = processor.process_trades_with_incomplete(&trades).unwrap();
     println!(
         "   Analysis mode result: {} bars",
@@ -131,6 +132,7 @@ fn test_analysis_mode_compatibility() {
     ];
 
     let strict_bars = processor.process_trades(&trades).unwrap();
+    processor.reset();
     let analysis_bars = processor.process_trades_with_incomplete(&trades).unwrap();
 
     println!(
@@ -529,6 +531,7 @@ fn test_specific_small_threshold(threshold_bps: u32, threshold_desc: &str) {
     }
 
     // Test analysis mode
+    processor.reset();
     let bars_analysis = processor
         .process_trades_with_incomplete(&trades_no_breach)
         .unwrap();
@@ -578,6 +581,7 @@ fn test_specific_small_threshold(threshold_bps: u32, threshold_desc: &str) {
         },
     ];
 
+    processor.reset();
     let bars_with_breach = processor.process_trades(&trades_with_breach).unwrap();
     println!("   With breach: {} bars created", bars_with_breach.len());
 
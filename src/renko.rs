@@ -0,0 +1,337 @@
+//! Renko brick construction
+//!
+//! [`RenkoProcessor`] builds Renko bricks from the same `AggTrade` stream as
+//! range bars. A brick forms when price moves one brick size (a [`BarRange`]:
+//! bps or a fixed tick distance) from the previous brick's close in the
+//! direction of the trend. A reversal needs two brick sizes: the reversal
+//! brick opens at the previous brick's open, so every brick's open and close
+//! lie on the brick grid.
+//!
+//! Bricks are emitted as [`RangeBar`]s with `open`/`close`/`high`/`low` on the
+//! grid and the volume, turnover and trade id range of the trades since the
+//! previous brick. A single trade that jumps several bricks forms all of them;
+//! the first carries the accumulated trades and the following gap bricks
+//! carry no volume, with `first_id == last_id` set to the jumping trade.
+
+use crate::fixed_point::{FixedPoint, FixedPointError};
+use crate::range_bars::{
+    BarProcessor, ProcessingError, closed_at_end_of_data, overflow_at, validate_trade_ordering,
+};
+use crate::threshold::BarRange;
+use crate::types::{AggTrade, BreachDirection, CloseReason, RangeBar};
+
+/// Grid position of the most recent brick
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LastBrick {
+    open: FixedPoint,
+    close: FixedPoint,
+    direction: BreachDirection,
+}
+
+/// Renko brick processor
+///
+/// Bricks sit on a fixed grid anchored at the first trade price.
+#[derive(Debug, Clone)]
+pub struct RenkoProcessor {
+    /// Brick size, applied from each brick's open
+    brick: BarRange,
+
+    /// Grid origin: first trade price until the first brick forms
+    origin: Option<FixedPoint>,
+
+    /// Most recent brick
+    last_brick: Option<LastBrick>,
+
+    /// Trades since the last brick, accumulated with `RangeBar::update_with_trade`
+    pending: Option<RangeBar>,
+
+    /// Last processed `(timestamp, agg_trade_id)` for cross-call ordering validation
+    last_trade: Option<(i64, i64)>,
+}
+
+impl RenkoProcessor {
+    /// Create processor with the given brick size
+    ///
+    /// # Arguments
+    ///
+    /// * `brick` - Brick size in bps (`25`), a [`Threshold`](crate::threshold::Threshold)
+    ///   or an absolute [`BarRange`] such as [`BarRange::ticks`]
+    pub fn new(brick: impl Into<BarRange>) -> Self {
+        Self {
+            brick: brick.into(),
+            origin: None,
+            last_brick: None,
+            pending: None,
+            last_trade: None,
+        }
+    }
+
+    /// Brick size in effect
+    pub fn brick(&self) -> BarRange {
+        self.brick
+    }
+
+    /// Process a single trade and return the bricks it formed
+    pub fn process_single_trade(
+        &mut self,
+        trade: AggTrade,
+    ) -> Result<Vec<RangeBar>, ProcessingError> {
+        validate_trade_ordering(self.last_trade, std::slice::from_ref(&trade))?;
+        let mut bricks = Vec::new();
+        self.process_trade(&trade, &mut bricks)?;
+        Ok(bricks)
+    }
+
+    /// Process trades into completed bricks
+    pub fn process_trades(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<RangeBar>, ProcessingError> {
+        validate_trade_ordering(self.last_trade, trades)?;
+
+        let mut bricks = Vec::new();
+        for trade in trades {
+            self.process_trade(trade, &mut bricks)?;
+        }
+        Ok(bricks)
+    }
+
+    /// Trades since the last brick (not a brick; open/close are trade prices)
+    pub fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        self.pending.clone()
+    }
+
+    /// Return the trades since the last brick at end of data
+    ///
+    /// The grid is kept, so further trades continue from the last brick.
+    pub fn finish(&mut self) -> Option<RangeBar> {
+        self.pending.take().map(closed_at_end_of_data)
+    }
+
+    /// Discard the grid, pending trades and ordering history
+    pub fn reset(&mut self) {
+        self.origin = None;
+        self.last_brick = None;
+        self.pending = None;
+        self.last_trade = None;
+    }
+
+    /// Candidate bricks from the current grid position: `((open, close) up, (open, close) down)`
+    ///
+    /// Continuation bricks open at the last close; reversal bricks open at the
+    /// last open, which makes a reversal need two brick sizes.
+    fn next_levels(&self) -> Result<Option<(BrickLevels, BrickLevels)>, FixedPointError> {
+        let (up_open, down_open) = match self.last_brick {
+            None => match self.origin {
+                Some(origin) => (origin, origin),
+                None => return Ok(None),
+            },
+            Some(LastBrick {
+                open,
+                close,
+                direction: BreachDirection::Up,
+            }) => (close, open),
+            Some(LastBrick {
+                open,
+                close,
+                direction: BreachDirection::Down,
+            }) => (open, close),
+        };
+        let up_close = self.brick.checked_breach_levels(up_open)?.0;
+        let down_close = self.brick.checked_breach_levels(down_open)?.1;
+        Ok(Some(((up_open, up_close), (down_open, down_close))))
+    }
+
+    /// Advance the grid by one (already validated) trade
+    fn process_trade(
+        &mut self,
+        trade: &AggTrade,
+        bricks: &mut Vec<RangeBar>,
+    ) -> Result<(), ProcessingError> {
+        self.last_trade = Some((trade.timestamp, trade.agg_trade_id));
+        self.origin.get_or_insert(trade.price);
+
+        match self.pending {
+            Some(ref mut bar) => bar.update_with_trade(trade).map_err(overflow_at(trade))?,
+            None => self.pending = Some(RangeBar::new(trade)),
+        }
+
+        while let Some(((up_open, up_close), (down_open, down_close))) =
+            self.next_levels().map_err(overflow_at(trade))?
+        {
+            let (open, close, direction) = if trade.price >= up_close && up_close > up_open {
+                (up_open, up_close, BreachDirection::Up)
+            } else if trade.price <= down_close && down_close < down_open {
+                (down_open, down_close, BreachDirection::Down)
+            } else {
+                break;
+            };
+
+            // Gap bricks after the first one formed by this trade carry no volume
+            let mut brick = self.pending.take().unwrap_or_else(|| empty_brick(trade));
+            brick.open = open;
+            brick.close = close;
+            brick.high = open.max(close);
+            brick.low = open.min(close);
+            brick.upper_threshold = Some(up_close);
+            brick.lower_threshold = Some(down_close);
+            brick.close_direction = Some(direction);
+            brick.force_close(CloseReason::Breach);
+            bricks.push(brick);
+
+            self.last_brick = Some(LastBrick {
+                open,
+                close,
+                direction,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Candidate brick as `(open, close)`
+type BrickLevels = (FixedPoint, FixedPoint);
+
+/// Zero-volume brick anchored at `trade` (for multi-brick gaps)
+fn empty_brick(trade: &AggTrade) -> RangeBar {
+    let mut brick = RangeBar::new(trade);
+    brick.volume = FixedPoint(0);
+    brick.turnover = 0;
+    brick.trade_count = 0;
+    brick.buy_volume = FixedPoint(0);
+    brick.sell_volume = FixedPoint(0);
+    brick.buy_trade_count = 0;
+    brick.sell_trade_count = 0;
+    brick.buy_turnover = 0;
+    brick.sell_turnover = 0;
+    brick
+}
+
+impl BarProcessor for RenkoProcessor {
+    fn process_trades(&mut self, trades: &[AggTrade]) -> Result<Vec<RangeBar>, ProcessingError> {
+        RenkoProcessor::process_trades(self, trades)
+    }
+
+    fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        RenkoProcessor::get_incomplete_bar(self)
+    }
+
+    fn has_open_bar(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn finish(&mut self) -> Option<RangeBar> {
+        RenkoProcessor::finish(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_trade(id: i64, price: &str, volume: &str) -> AggTrade {
+        AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str(price).unwrap(),
+            volume: FixedPoint::from_str(volume).unwrap(),
+            first_trade_id: id,
+            last_trade_id: id,
+            timestamp: 1000 * id,
+            is_buyer_maker: false,
+        }
+    }
+
+    fn grid(bricks: &[RangeBar]) -> Vec<(String, String)> {
+        bricks
+            .iter()
+            .map(|b| (b.open.to_string(), b.close.to_string()))
+            .collect()
+    }
+
+    fn fp(s: &str) -> String {
+        FixedPoint::from_str(s).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_bricks_reversal_and_gaps() {
+        // 10 ticks of 1.0 = bricks of 10
+        let mut processor =
+            RenkoProcessor::new(BarRange::ticks(10, FixedPoint::from_str("1.0").unwrap()));
+
+        let trades = vec![
+            create_test_trade(1, "100.0", "1.0"), // Origin
+            create_test_trade(2, "105.0", "1.0"),
+            create_test_trade(3, "110.0", "1.0"), // Up brick 100 -> 110
+            create_test_trade(4, "125.0", "1.0"), // Up brick 110 -> 120
+            create_test_trade(5, "105.0", "1.0"), // One brick down: no reversal yet
+            create_test_trade(6, "99.0", "2.0"),  // Reversal brick 110 -> 100
+            create_test_trade(7, "75.0", "3.0"),  // Gap: 100 -> 90 and 90 -> 80
+            create_test_trade(8, "85.0", "1.0"),  // Pending
+        ];
+
+        let bricks = processor.process_trades(&trades).unwrap();
+        assert_eq!(
+            grid(&bricks),
+            vec![
+                (fp("100"), fp("110")),
+                (fp("110"), fp("120")),
+                (fp("110"), fp("100")),
+                (fp("100"), fp("90")),
+                (fp("90"), fp("80")),
+            ]
+        );
+
+        // Trade id ranges and attributed volume
+        let ids: Vec<_> = bricks.iter().map(|b| (b.first_id, b.last_id)).collect();
+        assert_eq!(ids, vec![(1, 3), (4, 4), (5, 6), (7, 7), (7, 7)]);
+        let volumes: Vec<_> = bricks.iter().map(|b| b.volume.to_string()).collect();
+        assert_eq!(volumes, vec![fp("3"), fp("1"), fp("3"), fp("3"), fp("0")]);
+        assert_eq!(bricks[4].trade_count, 0);
+
+        // Grid bounds and direction
+        assert_eq!(bricks[2].high.to_string(), fp("110"));
+        assert_eq!(bricks[2].low.to_string(), fp("100"));
+        assert_eq!(bricks[2].close_direction, Some(BreachDirection::Down));
+        assert_eq!(bricks[0].close_reason, Some(CloseReason::Breach));
+
+        let pending = processor.finish().unwrap();
+        assert_eq!((pending.first_id, pending.last_id), (8, 8));
+        assert_eq!(pending.close_reason, Some(CloseReason::EndOfData));
+    }
+
+    #[test]
+    fn test_bps_bricks_and_chunking() {
+        // 100 bps bricks: sizes follow each brick's open
+        let trades: Vec<AggTrade> = (1..=400)
+            .map(|i| {
+                let price = 1000.0 + 80.0 * ((i as f64) / 13.0).sin() + i as f64 / 4.0;
+                create_test_trade(i, &format!("{:.2}", price), "0.5")
+            })
+            .collect();
+
+        let mut batch = RenkoProcessor::new(100);
+        let expected = batch.process_trades(&trades).unwrap();
+        assert!(expected.len() > 10);
+
+        for pair in expected.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            let continuation = next.close_direction == prev.close_direction;
+            // Continuations open at the previous close, reversals at the previous open
+            assert_eq!(next.open, if continuation { prev.close } else { prev.open });
+            let delta = (next.close.0 - next.open.0).abs();
+            assert_eq!(delta, next.open.0 / 100);
+        }
+
+        let mut chunked = RenkoProcessor::new(100);
+        let mut bricks = Vec::new();
+        for chunk in trades.chunks(7) {
+            bricks.extend(chunked.process_trades(chunk).unwrap());
+        }
+        assert_eq!(expected, bricks);
+
+        // Every trade is attributed to exactly one brick or the pending remainder
+        let pending = chunked.get_incomplete_bar().map_or(0, |b| b.trade_count);
+        let attributed: i64 = bricks.iter().map(|b| b.trade_count).sum();
+        assert_eq!(attributed + pending, trades.len() as i64);
+    }
+}
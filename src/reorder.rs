@@ -0,0 +1,429 @@
+//! Bounded reordering of slightly out-of-order trades
+//!
+//! The engines reject unsorted input with [`ProcessingError::UnsortedTrades`].
+//! Live feeds and merged files sometimes deliver trades a few milliseconds out
+//! of order; [`ReorderBuffer`] holds trades for a configurable lateness window
+//! and releases them sorted by `(timestamp, agg_trade_id)`.
+//!
+//! A trade that arrives after a later trade was already released cannot be
+//! placed without corrupting bars. It is rejected with
+//! [`ProcessingError::LateTrade`] or dropped and counted, per [`LatePolicy`].
+//!
+//! [`ReorderingProcessor`] puts the buffer in front of any [`BarProcessor`],
+//! so it plugs into the streaming and export wrappers unchanged.
+
+use crate::range_bars::{BarProcessor, ProcessingError};
+use crate::types::{AggTrade, RangeBar};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// How far behind the newest trade a trade may arrive and still be placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatenessWindow {
+    /// Timestamp distance in milliseconds
+    Millis(i64),
+
+    /// `agg_trade_id` distance
+    Ids(i64),
+}
+
+/// What to do with a trade that arrives after its slot was released
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatePolicy {
+    /// Fail with [`ProcessingError::LateTrade`]
+    #[default]
+    Reject,
+
+    /// Skip the trade and count it in [`ReorderBuffer::late_trades`]
+    Drop,
+}
+
+/// Reordering stage options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorderConfig {
+    /// Lateness tolerated before a trade is released
+    pub window: LatenessWindow,
+
+    /// Handling of trades later than the window
+    pub late: LatePolicy,
+
+    /// Most trades held at once; the oldest is released early when exceeded
+    pub capacity: usize,
+}
+
+impl ReorderConfig {
+    /// Reject late trades and hold at most 100k trades
+    pub fn new(window: LatenessWindow) -> Self {
+        Self {
+            window,
+            late: LatePolicy::default(),
+            capacity: 100_000,
+        }
+    }
+}
+
+/// Trade ordered by `(timestamp, agg_trade_id)`
+#[derive(Debug)]
+struct Held(AggTrade);
+
+impl Held {
+    fn key(&self) -> (i64, i64) {
+        (self.0.timestamp, self.0.agg_trade_id)
+    }
+}
+
+impl PartialEq for Held {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Held {}
+
+impl PartialOrd for Held {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Held {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Min-heap of held trades released once they fall outside the lateness window
+#[derive(Debug)]
+pub struct ReorderBuffer {
+    config: ReorderConfig,
+
+    /// Held trades, earliest on top
+    held: BinaryHeap<Reverse<Held>>,
+
+    /// Newest `(timestamp, agg_trade_id)` seen, per component
+    newest: Option<(i64, i64)>,
+
+    /// Last released `(timestamp, agg_trade_id)`; anything not after it is late
+    released: Option<(i64, i64)>,
+
+    /// Trades dropped under [`LatePolicy::Drop`]
+    late_trades: u64,
+}
+
+impl ReorderBuffer {
+    /// Create an empty buffer
+    pub fn new(config: ReorderConfig) -> Self {
+        Self {
+            config,
+            held: BinaryHeap::new(),
+            newest: None,
+            released: None,
+            late_trades: 0,
+        }
+    }
+
+    /// Options in effect
+    pub fn config(&self) -> &ReorderConfig {
+        &self.config
+    }
+
+    /// Trades currently held
+    pub fn len(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Whether no trade is held
+    pub fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+
+    /// Trades dropped as late so far
+    pub fn late_trades(&self) -> u64 {
+        self.late_trades
+    }
+
+    /// Accept one trade, appending every trade it releases to `out` in order
+    pub fn push(
+        &mut self,
+        trade: AggTrade,
+        out: &mut Vec<AggTrade>,
+    ) -> Result<(), ProcessingError> {
+        let key = (trade.timestamp, trade.agg_trade_id);
+        if let Some((released_time, released_id)) = self.released
+            && key <= (released_time, released_id)
+        {
+            return match self.config.late {
+                LatePolicy::Reject => Err(ProcessingError::LateTrade {
+                    timestamp: trade.timestamp,
+                    agg_trade_id: trade.agg_trade_id,
+                    released_time,
+                    released_id,
+                }),
+                LatePolicy::Drop => {
+                    self.late_trades += 1;
+                    Ok(())
+                }
+            };
+        }
+
+        self.newest = Some(match self.newest {
+            Some((time, id)) => (time.max(trade.timestamp), id.max(trade.agg_trade_id)),
+            None => key,
+        });
+        self.held.push(Reverse(Held(trade)));
+
+        while let Some(Reverse(oldest)) = self.held.peek() {
+            if !self.is_due(oldest) && self.held.len() <= self.config.capacity {
+                break;
+            }
+            self.release_oldest(out);
+        }
+        Ok(())
+    }
+
+    /// Accept trades in arrival order, returning the trades they release
+    ///
+    /// Stops at the first late trade under [`LatePolicy::Reject`]; the trades
+    /// released before it are appended to `out` and the rest are not consumed.
+    pub fn extend(
+        &mut self,
+        trades: &[AggTrade],
+        out: &mut Vec<AggTrade>,
+    ) -> Result<(), ProcessingError> {
+        for trade in trades {
+            self.push(trade.clone(), out)?;
+        }
+        Ok(())
+    }
+
+    /// Release every held trade in order (end of data)
+    pub fn flush(&mut self) -> Vec<AggTrade> {
+        let mut out = Vec::with_capacity(self.held.len());
+        while !self.held.is_empty() {
+            self.release_oldest(&mut out);
+        }
+        out
+    }
+
+    /// Whether `held` is far enough behind the newest trade to be released
+    fn is_due(&self, held: &Held) -> bool {
+        let Some((newest_time, newest_id)) = self.newest else {
+            return false;
+        };
+        match self.config.window {
+            LatenessWindow::Millis(ms) => held.0.timestamp < newest_time.saturating_sub(ms),
+            LatenessWindow::Ids(ids) => held.0.agg_trade_id < newest_id.saturating_sub(ids),
+        }
+    }
+
+    fn release_oldest(&mut self, out: &mut Vec<AggTrade>) {
+        if let Some(Reverse(Held(trade))) = self.held.pop() {
+            self.released = Some((trade.timestamp, trade.agg_trade_id));
+            out.push(trade);
+        }
+    }
+}
+
+/// [`ReorderBuffer`] in front of a bar engine
+///
+/// Bars completed from trades released before a rejected late trade are kept
+/// and returned by the next successful call.
+pub struct ReorderingProcessor<P> {
+    buffer: ReorderBuffer,
+    processor: P,
+
+    /// Bars not yet handed out (after a late-trade error, or at end of data)
+    pending: Vec<(usize, RangeBar)>,
+
+    /// Engine error on the trades flushed at end of data
+    finish_error: Option<ProcessingError>,
+}
+
+impl<P: BarProcessor> ReorderingProcessor<P> {
+    /// Reorder trades within `config.window` before they reach `processor`
+    pub fn new(processor: P, config: ReorderConfig) -> Self {
+        Self {
+            buffer: ReorderBuffer::new(config),
+            processor,
+            pending: Vec::new(),
+            finish_error: None,
+        }
+    }
+
+    /// The reordering stage
+    pub fn buffer(&self) -> &ReorderBuffer {
+        &self.buffer
+    }
+
+    /// The wrapped engine
+    pub fn processor(&self) -> &P {
+        &self.processor
+    }
+
+    /// Error the engine raised on the trades flushed by the last finish, if any
+    ///
+    /// `finish` cannot fail, so the bars of an engine that rejected its
+    /// flushed trades are missing from its output; check here after finishing.
+    pub fn take_finish_error(&mut self) -> Option<ProcessingError> {
+        self.finish_error.take()
+    }
+}
+
+impl<P: BarProcessor> BarProcessor for ReorderingProcessor<P> {
+    fn process_trades(&mut self, trades: &[AggTrade]) -> Result<Vec<RangeBar>, ProcessingError> {
+        Ok(self
+            .process_trades_routed(trades)?
+            .into_iter()
+            .map(|(_, bar)| bar)
+            .collect())
+    }
+
+    /// Open bar of the wrapped engine; held trades are not part of it yet
+    fn get_incomplete_bar(&self) -> Option<RangeBar> {
+        self.processor.get_incomplete_bar()
+    }
+
+    /// Flush the held trades through the engine and force-close it
+    ///
+    /// The flush may complete several bars; they are returned one per call,
+    /// so call until `None` or use [`finish_routed`](BarProcessor::finish_routed).
+    fn finish(&mut self) -> Option<RangeBar> {
+        if self.pending.is_empty() {
+            self.pending = self.finish_routed();
+        }
+        (!self.pending.is_empty()).then(|| self.pending.remove(0).1)
+    }
+
+    fn has_open_bar(&self) -> bool {
+        self.processor.has_open_bar()
+    }
+
+    fn output_count(&self) -> usize {
+        self.processor.output_count()
+    }
+
+    /// Held trades and undelivered bars, plus the wrapped engine's open bars
+    fn buffered_bytes(&self) -> usize {
+        self.buffer.len() * std::mem::size_of::<AggTrade>()
+            + self.pending.len() * std::mem::size_of::<(usize, RangeBar)>()
+            + self.processor.buffered_bytes()
+    }
+
+    fn process_trades_routed(
+        &mut self,
+        trades: &[AggTrade],
+    ) -> Result<Vec<(usize, RangeBar)>, ProcessingError> {
+        let mut released = Vec::with_capacity(trades.len());
+        let reordered = self.buffer.extend(trades, &mut released);
+        let bars = self.processor.process_trades_routed(&released)?;
+        self.pending.extend(bars);
+        reordered?;
+        Ok(std::mem::take(&mut self.pending))
+    }
+
+    fn finish_routed(&mut self) -> Vec<(usize, RangeBar)> {
+        let mut bars = std::mem::take(&mut self.pending);
+        let flushed = self.buffer.flush();
+        match self.processor.process_trades_routed(&flushed) {
+            Ok(completed) => bars.extend(completed),
+            Err(e) => self.finish_error = Some(e),
+        }
+        bars.extend(self.processor.finish_routed());
+        bars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::FixedPoint;
+
+    fn create_test_trade(id: i64, timestamp: i64) -> AggTrade {
+        AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str("100.0").unwrap(),
+            volume: FixedPoint::from_str("1.0").unwrap(),
+            first_trade_id: id,
+            last_trade_id: id,
+            timestamp,
+            is_buyer_maker: false,
+        }
+    }
+
+    fn ids(trades: &[AggTrade]) -> Vec<i64> {
+        trades.iter().map(|t| t.agg_trade_id).collect()
+    }
+
+    #[test]
+    fn test_time_window_sorts_and_rejects_late() {
+        let mut buffer = ReorderBuffer::new(ReorderConfig::new(LatenessWindow::Millis(5)));
+        let mut out = Vec::new();
+
+        let arrivals = [(2, 1002), (1, 1001), (4, 1004), (3, 1003), (5, 1010)];
+        for (id, ts) in arrivals {
+            buffer.push(create_test_trade(id, ts), &mut out).unwrap();
+        }
+        // 1010 - 5 = 1005: everything before it is released in order
+        assert_eq!(ids(&out), vec![1, 2, 3, 4]);
+
+        // Inside the window: still placed correctly
+        buffer.push(create_test_trade(6, 1006), &mut out).unwrap();
+        // Behind the released trade 4
+        assert!(matches!(
+            buffer.push(create_test_trade(0, 1000), &mut out),
+            Err(ProcessingError::LateTrade {
+                agg_trade_id: 0,
+                released_id: 4,
+                ..
+            })
+        ));
+        assert_eq!(ids(&buffer.flush()), vec![6, 5]);
+    }
+
+    #[test]
+    fn test_finish_keeps_engine_error() {
+        use crate::range_bars::RangeBarProcessor;
+
+        let config = ReorderConfig::new(LatenessWindow::Millis(1_000));
+        let mut processor = ReorderingProcessor::new(RangeBarProcessor::new(25), config);
+        let mut extreme = create_test_trade(1, 1000);
+        extreme.price = FixedPoint(i64::MAX - 1);
+
+        // Held by the window, so the engine only sees it at finish
+        assert!(processor.process_trades(&[extreme]).unwrap().is_empty());
+        assert!(processor.finish_routed().is_empty());
+        assert!(processor.take_finish_error().is_some());
+        assert!(processor.take_finish_error().is_none());
+    }
+
+    #[test]
+    fn test_id_window_drop_policy_and_capacity() {
+        let config = ReorderConfig {
+            window: LatenessWindow::Ids(2),
+            late: LatePolicy::Drop,
+            capacity: 3,
+        };
+        let mut buffer = ReorderBuffer::new(config);
+        let mut out = Vec::new();
+
+        for id in [3, 1, 2, 6, 5, 1] {
+            buffer
+                .push(create_test_trade(id, 1000 + id), &mut out)
+                .unwrap();
+        }
+        assert_eq!(ids(&out), vec![1, 2, 3]);
+        assert_eq!(buffer.late_trades(), 1);
+
+        // Capacity releases the oldest early even inside the window
+        for id in [7, 8] {
+            buffer
+                .push(create_test_trade(id, 1000 + id), &mut out)
+                .unwrap();
+        }
+        assert!(buffer.len() <= 3);
+        assert_eq!(ids(&out), vec![1, 2, 3, 5]);
+    }
+}
@@ -0,0 +1,573 @@
+//! Multi-symbol routing of one trade feed
+//!
+//! [`SymbolRouter`] takes a single feed of `(symbol, AggTrade)` pairs and keeps
+//! one independent engine per `(symbol, threshold)` pair, emitting each
+//! completed bar as a [`RoutedBar`]. Engines are created from the
+//! [`SymbolRouterConfig`] the first time a symbol trades, so a router for the
+//! Tier-1 universe costs nothing for symbols that stay quiet.
+//!
+//! Symbols are isolated from each other: ordering is validated per symbol, an
+//! out-of-order trade leaves that symbol's bars untouched, and errors only ever
+//! count against the symbol that caused them. A feed that keeps failing can be
+//! quarantined so its trades are dropped while every other symbol carries on.
+//!
+//! ```rust
+//! use rangebar::{AggTrade, FixedPoint, SymbolRouter, SymbolRouterConfig};
+//!
+//! let mut router = SymbolRouter::new(SymbolRouterConfig::new([25, 50])).unwrap();
+//! let trade = |id: i64, price: &str| AggTrade {
+//!     agg_trade_id: id,
+//!     price: FixedPoint::from_str(price).unwrap(),
+//!     volume: FixedPoint::from_str("1.0").unwrap(),
+//!     first_trade_id: id,
+//!     last_trade_id: id,
+//!     timestamp: 1_609_459_200_000 + id,
+//!     is_buyer_maker: false,
+//! };
+//!
+//! router.process("BTCUSDT", &trade(1, "50000.0")).unwrap();
+//! router.process("ETHUSDT", &trade(1, "4000.0")).unwrap();
+//! let bars = router.process("BTCUSDT", &trade(2, "50200.0")).unwrap();
+//!
+//! // Breaches 25 bps but not 50 bps
+//! assert_eq!(bars.len(), 1);
+//! assert_eq!((bars[0].symbol.as_str(), bars[0].range), ("BTCUSDT", 25.into()));
+//! assert_eq!(router.metrics("ETHUSDT").unwrap().trades_processed, 1);
+//! ```
+
+use crate::ladder::LadderProcessor;
+use crate::range_bars::{BarProcessor, ProcessingError, RangeBarProcessorConfig};
+use crate::threshold::BarRange;
+use crate::tier1::get_tier1_usdt_pairs;
+use crate::types::{AggTrade, RangeBar};
+use futures::{Stream, ready};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use thiserror::Error;
+
+/// Which engines a [`SymbolRouter`] builds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SymbolRouterConfig {
+    /// Thresholds built for every symbol, in output order
+    pub thresholds: Vec<BarRange>,
+
+    /// Thresholds replacing `thresholds` for particular symbols
+    pub symbol_thresholds: BTreeMap<String, Vec<BarRange>>,
+
+    /// Symbols accepted by the router (`None` accepts any symbol)
+    pub symbols: Option<BTreeSet<String>>,
+
+    /// Engine options shared by every engine
+    pub processor: RangeBarProcessorConfig,
+
+    /// Consecutive rejected trades after which a symbol is quarantined
+    /// (`None` never quarantines)
+    pub quarantine_after: Option<u64>,
+}
+
+impl Default for SymbolRouterConfig {
+    fn default() -> Self {
+        Self::new([BarRange::default()])
+    }
+}
+
+impl SymbolRouterConfig {
+    /// Accept any symbol and build one engine per threshold for each
+    pub fn new<R: Into<BarRange>>(thresholds: impl IntoIterator<Item = R>) -> Self {
+        Self {
+            thresholds: thresholds.into_iter().map(Into::into).collect(),
+            symbol_thresholds: BTreeMap::new(),
+            symbols: None,
+            processor: RangeBarProcessorConfig::default(),
+            quarantine_after: None,
+        }
+    }
+
+    /// Accept only the Tier-1 USDT pairs (`BTCUSDT`, `ETHUSDT`, ...)
+    pub fn tier1<R: Into<BarRange>>(thresholds: impl IntoIterator<Item = R>) -> Self {
+        Self {
+            symbols: Some(get_tier1_usdt_pairs().into_iter().collect()),
+            ..Self::new(thresholds)
+        }
+    }
+
+    /// Thresholds of `symbol`'s engines, in output order
+    pub fn thresholds_for(&self, symbol: &str) -> &[BarRange] {
+        self.symbol_thresholds
+            .get(symbol)
+            .unwrap_or(&self.thresholds)
+    }
+
+    /// Whether trades of `symbol` are routed
+    pub fn accepts(&self, symbol: &str) -> bool {
+        self.symbols
+            .as_ref()
+            .is_none_or(|symbols| symbols.contains(symbol))
+    }
+
+    /// Check that every symbol gets at least one engine
+    pub fn validate(&self) -> Result<(), ProcessingError> {
+        let any_empty =
+            self.thresholds.is_empty() || self.symbol_thresholds.values().any(Vec::is_empty);
+        if any_empty {
+            return Err(ProcessingError::EmptyLadder);
+        }
+        Ok(())
+    }
+}
+
+/// A completed bar and the engine that built it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutedBar {
+    /// Symbol the trades belonged to
+    pub symbol: String,
+
+    /// Threshold of the engine
+    pub range: BarRange,
+
+    /// The bar itself
+    pub bar: RangeBar,
+}
+
+/// A trade the router rejected, with the symbol it belonged to
+#[derive(Error, Debug)]
+pub enum RouterError {
+    #[error("{symbol}: symbol is not configured for routing")]
+    UnknownSymbol { symbol: String },
+
+    #[error("{symbol}: trade dropped, symbol quarantined after {errors} consecutive errors")]
+    Quarantined { symbol: String, errors: u64 },
+
+    #[error("{symbol}: {source}")]
+    Processing {
+        symbol: String,
+        #[source]
+        source: ProcessingError,
+    },
+}
+
+impl RouterError {
+    /// Symbol of the rejected trade
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::UnknownSymbol { symbol }
+            | Self::Quarantined { symbol, .. }
+            | Self::Processing { symbol, .. } => symbol,
+        }
+    }
+}
+
+/// Counters of one symbol
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolMetrics {
+    /// Trades accepted by the symbol's engines
+    pub trades_processed: u64,
+
+    /// Bars completed across all of the symbol's thresholds
+    pub bars_generated: u64,
+
+    /// Trades rejected by the engines (unsorted, overflow, ...)
+    pub errors_total: u64,
+
+    /// Rejected trades since the last accepted one
+    pub consecutive_errors: u64,
+
+    /// Trades dropped while quarantined
+    pub trades_dropped: u64,
+
+    /// Whether the symbol's trades are currently dropped
+    pub quarantined: bool,
+}
+
+/// Engines and counters of one symbol
+struct SymbolEngine {
+    /// One rung per threshold; validates ordering once per trade
+    ladder: LadderProcessor,
+
+    /// Threshold of each rung, in rung order
+    ranges: Vec<BarRange>,
+
+    metrics: SymbolMetrics,
+}
+
+/// Range bars for many symbols and thresholds from one trade feed
+///
+/// Trades of different symbols may interleave freely; only trades of the
+/// same symbol must be sorted.
+pub struct SymbolRouter {
+    config: SymbolRouterConfig,
+
+    /// Engines created so far, by symbol
+    engines: BTreeMap<String, SymbolEngine>,
+
+    /// Trades of symbols the config does not accept
+    unknown_symbol_trades: u64,
+}
+
+impl SymbolRouter {
+    /// Create a router; engines are built as symbols first trade
+    pub fn new(config: SymbolRouterConfig) -> Result<Self, ProcessingError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            engines: BTreeMap::new(),
+            unknown_symbol_trades: 0,
+        })
+    }
+
+    /// Routing configuration
+    pub fn config(&self) -> &SymbolRouterConfig {
+        &self.config
+    }
+
+    /// Process one trade of `symbol`, returning the bars it completed
+    ///
+    /// Bars are in threshold order. Errors only count against `symbol`. An
+    /// out-of-order trade changes no bar; an engine error such as an overflow
+    /// can leave the lower thresholds advanced past the trade, as described on
+    /// [`LadderProcessor::process_trades_into`](crate::ladder::LadderProcessor::process_trades_into).
+    pub fn process(
+        &mut self,
+        symbol: &str,
+        trade: &AggTrade,
+    ) -> Result<Vec<RoutedBar>, RouterError> {
+        let quarantine_after = self.config.quarantine_after;
+        let engine = self.engine_mut(symbol)?;
+        let metrics = &mut engine.metrics;
+        if metrics.quarantined {
+            metrics.trades_dropped += 1;
+            return Err(RouterError::Quarantined {
+                symbol: symbol.to_string(),
+                errors: metrics.consecutive_errors,
+            });
+        }
+
+        match engine
+            .ladder
+            .process_trades_routed(std::slice::from_ref(trade))
+        {
+            Ok(bars) => {
+                metrics.trades_processed += 1;
+                metrics.consecutive_errors = 0;
+                metrics.bars_generated += bars.len() as u64;
+                Ok(bars
+                    .into_iter()
+                    .map(|(rung, bar)| RoutedBar {
+                        symbol: symbol.to_string(),
+                        range: engine.ranges[rung],
+                        bar,
+                    })
+                    .collect())
+            }
+            Err(source) => {
+                metrics.errors_total += 1;
+                metrics.consecutive_errors += 1;
+                if quarantine_after.is_some_and(|limit| metrics.consecutive_errors >= limit) {
+                    metrics.quarantined = true;
+                }
+                Err(RouterError::Processing {
+                    symbol: symbol.to_string(),
+                    source,
+                })
+            }
+        }
+    }
+
+    /// Force-close the open bars of every symbol, in symbol then threshold order
+    pub fn finish(&mut self) -> Vec<RoutedBar> {
+        let mut bars = Vec::new();
+        for (symbol, engine) in &mut self.engines {
+            let closed = engine.ladder.finish_routed();
+            engine.metrics.bars_generated += closed.len() as u64;
+            bars.extend(closed.into_iter().map(|(rung, bar)| RoutedBar {
+                symbol: symbol.clone(),
+                range: engine.ranges[rung],
+                bar,
+            }));
+        }
+        bars
+    }
+
+    /// Resume routing a quarantined symbol; `false` if it was not quarantined
+    pub fn release(&mut self, symbol: &str) -> bool {
+        match self.engines.get_mut(symbol) {
+            Some(engine) if engine.metrics.quarantined => {
+                engine.metrics.quarantined = false;
+                engine.metrics.consecutive_errors = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Symbols that have traded so far, sorted
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.engines.keys().map(String::as_str)
+    }
+
+    /// Engines of `symbol`, e.g. to checkpoint them or inspect open bars
+    pub fn ladder(&self, symbol: &str) -> Option<&LadderProcessor> {
+        self.engines.get(symbol).map(|engine| &engine.ladder)
+    }
+
+    /// Counters of `symbol`, once it has traded
+    pub fn metrics(&self, symbol: &str) -> Option<&SymbolMetrics> {
+        self.engines.get(symbol).map(|engine| &engine.metrics)
+    }
+
+    /// Counters of every symbol that has traded, sorted by symbol
+    pub fn all_metrics(&self) -> impl Iterator<Item = (&str, &SymbolMetrics)> {
+        self.engines
+            .iter()
+            .map(|(symbol, engine)| (symbol.as_str(), &engine.metrics))
+    }
+
+    /// Trades rejected because their symbol is not accepted
+    pub fn unknown_symbol_trades(&self) -> u64 {
+        self.unknown_symbol_trades
+    }
+
+    /// Route a stream of `(symbol, trade)` pairs into a stream of bars
+    pub fn into_stream<S>(self, trades: S) -> SymbolRouterStream<S> {
+        SymbolRouterStream {
+            trades,
+            router: self,
+            pending: VecDeque::new(),
+            include_incomplete: false,
+            done: false,
+        }
+    }
+
+    /// Engines of `symbol`, built on its first trade
+    fn engine_mut(&mut self, symbol: &str) -> Result<&mut SymbolEngine, RouterError> {
+        if !self.engines.contains_key(symbol) {
+            if !self.config.accepts(symbol) {
+                self.unknown_symbol_trades += 1;
+                return Err(RouterError::UnknownSymbol {
+                    symbol: symbol.to_string(),
+                });
+            }
+            let ranges = self.config.thresholds_for(symbol).to_vec();
+            let ladder =
+                LadderProcessor::with_config(ranges.iter().copied(), self.config.processor)
+                    .map_err(|source| RouterError::Processing {
+                        symbol: symbol.to_string(),
+                        source,
+                    })?;
+            self.engines.insert(
+                symbol.to_string(),
+                SymbolEngine {
+                    ladder,
+                    ranges,
+                    metrics: SymbolMetrics::default(),
+                },
+            );
+        }
+        Ok(self.engines.get_mut(symbol).expect("engine inserted above"))
+    }
+}
+
+/// Stream of routed bars over a stream of `(symbol, trade)` pairs
+///
+/// Created by [`SymbolRouter::into_stream`]. The trade stream must be
+/// `Unpin`; pin other streams with `Box::pin` first. Rejected trades are
+/// yielded as errors and the stream carries on with the next trade, so one
+/// symbol's bad feed never ends it. Trades dropped by a quarantine are only
+/// counted in that symbol's [`SymbolMetrics`].
+pub struct SymbolRouterStream<S> {
+    trades: S,
+    router: SymbolRouter,
+
+    /// Bars completed by the last trade, not yet yielded
+    pending: VecDeque<RoutedBar>,
+
+    /// Yield every open bar, force-closed, once the trades end
+    include_incomplete: bool,
+
+    /// Trades ended
+    done: bool,
+}
+
+impl<S> SymbolRouterStream<S> {
+    /// Also yield the trailing open bars with [`CloseReason::EndOfData`](crate::types::CloseReason::EndOfData)
+    pub fn with_incomplete(mut self) -> Self {
+        self.include_incomplete = true;
+        self
+    }
+
+    /// The underlying router, e.g. to read per-symbol metrics
+    pub fn router(&self) -> &SymbolRouter {
+        &self.router
+    }
+
+    /// Stop streaming and return the router with its open bars
+    pub fn into_router(self) -> SymbolRouter {
+        self.router
+    }
+}
+
+impl<S, K> Stream for SymbolRouterStream<S>
+where
+    S: Stream<Item = (K, AggTrade)> + Unpin,
+    K: AsRef<str>,
+{
+    type Item = Result<RoutedBar, RouterError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(bar) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(bar)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            match ready!(Pin::new(&mut this.trades).poll_next(cx)) {
+                Some((symbol, trade)) => match this.router.process(symbol.as_ref(), &trade) {
+                    Ok(bars) => this.pending.extend(bars),
+                    Err(RouterError::Quarantined { .. }) => {}
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                },
+                None => {
+                    this.done = true;
+                    if this.include_incomplete {
+                        this.pending.extend(this.router.finish());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_trades;
+    use crate::types::CloseReason;
+    use futures::{StreamExt, stream};
+
+    #[test]
+    fn test_interleaved_symbols_match_separate_ladders() {
+        let config = SymbolRouterConfig {
+            symbol_thresholds: BTreeMap::from([("ETH".to_string(), vec![BarRange::from(10)])]),
+            ..SymbolRouterConfig::new([25, 50])
+        };
+        let mut router = SymbolRouter::new(config).unwrap();
+        let trades = create_trades();
+
+        let mut routed = Vec::new();
+        for trade in &trades {
+            routed.extend(router.process("BTC", trade).unwrap());
+            routed.extend(router.process("ETH", trade).unwrap());
+        }
+        routed.extend(router.finish());
+
+        for (symbol, ranges) in [("BTC", vec![25, 50]), ("ETH", vec![10])] {
+            for range in ranges {
+                let expected = crate::RangeBarProcessor::new(range)
+                    .process_trades_with_incomplete(&trades)
+                    .unwrap();
+                let bars: Vec<_> = routed
+                    .iter()
+                    .filter(|r| r.symbol == symbol && r.range == range.into())
+                    .map(|r| r.bar.clone())
+                    .collect();
+                assert_eq!(bars, expected, "{symbol} at {range} bps");
+            }
+        }
+        assert_eq!(router.symbols().collect::<Vec<_>>(), ["BTC", "ETH"]);
+        assert_eq!(router.metrics("BTC").unwrap().trades_processed, 7);
+    }
+
+    #[test]
+    fn test_bad_feed_is_isolated_and_quarantined() {
+        let config = SymbolRouterConfig {
+            symbols: Some(BTreeSet::from(["BTC".to_string(), "ETH".to_string()])),
+            quarantine_after: Some(2),
+            ..SymbolRouterConfig::new([25])
+        };
+        let mut router = SymbolRouter::new(config).unwrap();
+        let trades = create_trades();
+
+        router.process("ETH", &trades[5]).unwrap();
+        for trade in &trades[..3] {
+            // ETH goes backwards: rejected, then quarantined
+            let eth = router.process("ETH", trade);
+            router.process("BTC", trade).unwrap();
+            assert!(eth.is_err());
+        }
+        assert!(matches!(
+            router.process("DOGE", &trades[0]),
+            Err(RouterError::UnknownSymbol { .. })
+        ));
+
+        let eth = router.metrics("ETH").unwrap();
+        assert_eq!(
+            (eth.errors_total, eth.trades_dropped, eth.quarantined),
+            (2, 1, true)
+        );
+        assert_eq!(router.metrics("BTC").unwrap().bars_generated, 1);
+        assert_eq!(router.unknown_symbol_trades(), 1);
+
+        // Released, ETH continues from its last accepted trade
+        assert!(router.release("ETH"));
+        assert!(router.process("ETH", &trades[6]).is_ok());
+        assert!(!router.metrics("ETH").unwrap().quarantined);
+    }
+
+    #[test]
+    fn test_config_validation_and_deserialization() {
+        assert!(matches!(
+            SymbolRouter::new(SymbolRouterConfig::new(Vec::<u32>::new())),
+            Err(ProcessingError::EmptyLadder)
+        ));
+
+        let config: SymbolRouterConfig = serde_json::from_str(
+            r#"{"thresholds": [{"relative": {"up": 25, "down": 25}}], "quarantine_after": 10}"#,
+        )
+        .unwrap();
+        assert_eq!(config.thresholds_for("BTCUSDT"), [BarRange::from(25)]);
+        assert!(config.accepts("ANYTHING"));
+
+        let tier1 = SymbolRouterConfig::tier1([25]);
+        assert!(tier1.accepts("BTCUSDT") && !tier1.accepts("SHIBUSDT"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_continues_past_rejected_trades() {
+        let trades = create_trades();
+        let mut feed: Vec<_> = trades.iter().map(|t| ("BTC", t.clone())).collect();
+        feed.insert(3, ("BTC", trades[0].clone()));
+
+        let router = SymbolRouter::new(SymbolRouterConfig::new([25])).unwrap();
+        let results: Vec<_> = router
+            .into_stream(stream::iter(feed))
+            .with_incomplete()
+            .collect()
+            .await;
+
+        assert!(matches!(
+            results[1],
+            Err(RouterError::Processing {
+                source: ProcessingError::UnsortedTrades { .. },
+                ..
+            })
+        ));
+        let bars: Vec<_> = results
+            .into_iter()
+            .filter_map(|result| Some(result.ok()?.bar))
+            .collect();
+        let expected = crate::RangeBarProcessor::new(25)
+            .process_trades_with_incomplete(&trades)
+            .unwrap();
+        assert_eq!(bars, expected);
+        assert_eq!(
+            bars.last().unwrap().close_reason,
+            Some(CloseReason::EndOfData)
+        );
+    }
+}
@@ -219,6 +219,7 @@ impl TradeStats {
         Self { count: 0 }
     }
 
+    #[cfg_attr(not(feature = "streaming-stats"), allow(unused_variables))]
     fn update(&mut self, trade: &AggTrade) {
         self.count += 1;
 
@@ -383,6 +384,7 @@ impl BarStats {
         Self { count: 0 }
     }
 
+    #[cfg_attr(not(feature = "streaming-stats"), allow(unused_variables))]
     fn update(&mut self, bar: &RangeBar) {
         self.count += 1;
 
@@ -538,6 +540,13 @@ mod tests {
             buy_turnover: 0,
             sell_volume: FixedPoint::from_str("5.5").unwrap(),
             sell_turnover: 0,
+            close_direction: None,
+            upper_threshold: None,
+            lower_threshold: None,
+            close_reason: None,
+            overshoot: None,
+            overshoot_bps: None,
+            missing_agg_trade_ids: None,
             buy_trade_count: 20,
             sell_trade_count: 22,
             vwap: FixedPoint::from_str("50025.0").unwrap(),
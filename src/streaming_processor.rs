@@ -5,12 +5,20 @@
 /// - Implements proper backpressure with bounded channels
 /// - Provides circuit breaker resilience patterns
 /// - Maintains temporal integrity for financial data
-use crate::range_bars::ExportRangeBarProcessor;
+/// - Accounts buffered memory and enforces a [`MemoryPolicy`] above the threshold
+/// - Fans bars out to any number of subscribers, each with its own [`LagPolicy`](crate::broadcast::LagPolicy)
+use crate::broadcast::{BarBroadcast, BarSubscription, BarSubscriptions, SubscriptionConfig};
+use crate::range_bars::{BarProcessor, RangeBarProcessor, RangeBarProcessorConfig};
+use crate::threshold::BarRange;
 use crate::types::{AggTrade, RangeBar};
 use futures::Stream;
+use futures::future::{self, BoxFuture, FutureExt};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::task::{Context, Poll};
 use tokio::sync::mpsc;
 use tokio::time::{Duration, Instant};
@@ -22,45 +30,225 @@ pub struct StreamingProcessorConfig {
     pub trade_channel_capacity: usize,
     /// Channel capacity for completed bars
     pub bar_channel_capacity: usize,
+    /// Recent bars kept per output for subscribers that ask for a replay
+    pub bar_replay_capacity: usize,
     /// Memory usage threshold in bytes
     pub memory_threshold_bytes: usize,
+    /// Response once memory usage exceeds the threshold
+    pub memory_policy: MemoryPolicy,
+    /// How memory usage is measured
+    pub memory_accounting: MemoryAccounting,
     /// Backpressure timeout
     pub backpressure_timeout: Duration,
     /// Circuit breaker error rate threshold (0.0-1.0)
     pub circuit_breaker_threshold: f64,
     /// Circuit breaker timeout before retry
     pub circuit_breaker_timeout: Duration,
+    /// Range bar engine options
+    pub processor: RangeBarProcessorConfig,
 }
 
 impl Default for StreamingProcessorConfig {
     fn default() -> Self {
         Self {
-            trade_channel_capacity: 5_000,       // Based on consensus analysis
-            bar_channel_capacity: 100,           // Bounded per consumer
+            trade_channel_capacity: 5_000, // Based on consensus analysis
+            bar_channel_capacity: 100,     // Bounded per consumer
+            bar_replay_capacity: 0,
             memory_threshold_bytes: 100_000_000, // 100MB limit
+            memory_policy: MemoryPolicy::default(),
+            memory_accounting: MemoryAccounting::default(),
             backpressure_timeout: Duration::from_millis(100),
             circuit_breaker_threshold: 0.5, // 50% error rate
             circuit_breaker_timeout: Duration::from_secs(30),
+            processor: RangeBarProcessorConfig::default(),
         }
     }
 }
 
+/// Response to memory usage above [`StreamingProcessorConfig::memory_threshold_bytes`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MemoryPolicy {
+    /// Stop reading trades until consumers drain the bar channels
+    ///
+    /// Trade senders then wait on the full trade channel. Intake only pauses
+    /// while bars are queued, since nothing else can free memory.
+    #[default]
+    PauseIntake,
+
+    /// Drop each incoming trade with [`StreamingError::MemoryThresholdExceeded`]
+    Shed,
+
+    /// Write completed bars to files in `dir` and deliver them from there in order
+    ///
+    /// Intake never waits for consumers; spilled bars are read back as the bar
+    /// channels free up, and the files are removed with the processor.
+    SpillToDisk { dir: PathBuf },
+}
+
+/// How [`StreamingMetrics::memory_usage_bytes`] is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryAccounting {
+    /// Trades and bars queued in the channels plus the engine's open bars and buffers
+    #[default]
+    Buffered,
+
+    /// Live heap bytes of the whole process, counted by [`CountingAllocator`]
+    ///
+    /// Reads zero unless `CountingAllocator` is the global allocator.
+    Allocator,
+}
+
+/// Live heap bytes, maintained by [`CountingAllocator`]
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// System allocator that counts live heap bytes
+///
+/// Install it as the global allocator to use [`MemoryAccounting::Allocator`]:
+///
+/// ```rust
+/// use rangebar::CountingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator;
+///
+/// fn main() {
+///     let buffer = vec![0u8; 4096];
+///     assert!(CountingAllocator::allocated_bytes() >= buffer.len());
+/// }
+/// ```
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    /// Heap bytes currently allocated through this allocator
+    pub fn allocated_bytes() -> usize {
+        ALLOCATED_BYTES.load(Ordering::Relaxed)
+    }
+}
+
+// SAFETY: every call is forwarded unchanged to `System`; the counter is bookkeeping only
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the caller upholds `GlobalAlloc::alloc`'s contract
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the caller upholds `GlobalAlloc::alloc_zeroed`'s contract
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr` was allocated by `System` with `layout`
+        unsafe { System.dealloc(ptr, layout) };
+        ALLOCATED_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: the caller upholds `GlobalAlloc::realloc`'s contract
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+/// Distinguishes spill files of processors in one process
+static SPILL_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Completed bars of one output waiting on disk, delivered first-in first-out
+///
+/// Headerless CSV, which reads every field back exactly.
+struct SpillFile {
+    path: PathBuf,
+    writer: csv::Writer<File>,
+    reader: csv::Reader<File>,
+
+    /// Bars written but not yet read back
+    pending: usize,
+}
+
+impl SpillFile {
+    fn create(dir: &Path, output: usize) -> std::io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let id = SPILL_FILE_ID.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!(
+            "rangebar-spill-{}-{}-{}.csv",
+            std::process::id(),
+            id,
+            output
+        ));
+        let writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(File::create(&path)?);
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(File::open(&path)?);
+        Ok(Self {
+            path,
+            writer,
+            reader,
+            pending: 0,
+        })
+    }
+
+    fn push(&mut self, bar: &RangeBar) -> Result<(), StreamingError> {
+        self.writer.serialize(bar).map_err(spill_failed)?;
+        self.pending += 1;
+        Ok(())
+    }
+
+    /// Oldest spilled bar, if any
+    fn pop(&mut self) -> Result<Option<RangeBar>, StreamingError> {
+        if self.pending == 0 {
+            return Ok(None);
+        }
+        self.writer.flush().map_err(spill_failed)?;
+        let bar = self
+            .reader
+            .deserialize()
+            .next()
+            .ok_or_else(|| spill_failed("spill file ended early"))?
+            .map_err(spill_failed)?;
+        self.pending -= 1;
+        Ok(Some(bar))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn spill_failed(e: impl std::fmt::Display) -> StreamingError {
+    StreamingError::SpillFailed(e.to_string())
+}
+
 /// Production streaming processor with bounded memory
 pub struct StreamingProcessor {
-    /// Range bar processor (single instance, no accumulation)
-    processor: ExportRangeBarProcessor,
-
-    /// Threshold in basis points for recreating processor
-    #[allow(dead_code)]
-    threshold_bps: u32,
+    /// Bar engine (single instance, no accumulation)
+    processor: Box<dyn BarProcessor>,
 
     /// Bounded channel for incoming trades
     trade_sender: Option<mpsc::Sender<AggTrade>>,
     trade_receiver: mpsc::Receiver<AggTrade>,
 
-    /// Bounded channel for outgoing bars
-    bar_sender: mpsc::Sender<RangeBar>,
-    bar_receiver: Option<mpsc::Receiver<RangeBar>>,
+    /// Bounded channel for outgoing bars, one per engine output
+    bar_senders: Vec<mpsc::Sender<RangeBar>>,
+    bar_receivers: Vec<Option<mpsc::Receiver<RangeBar>>>,
+
+    /// Subscriber fan-out, one per engine output
+    broadcasts: Vec<BarBroadcast>,
 
     /// Configuration
     config: StreamingProcessorConfig,
@@ -70,6 +258,12 @@ pub struct StreamingProcessor {
 
     /// Circuit breaker state
     circuit_breaker: CircuitBreaker,
+
+    /// Spilled bars per output ([`MemoryPolicy::SpillToDisk`])
+    spills: Vec<Option<SpillFile>>,
+
+    /// Intake is paused under [`MemoryPolicy::PauseIntake`]
+    paused: bool,
 }
 
 /// Circuit breaker implementation
@@ -99,35 +293,66 @@ pub struct StreamingMetrics {
     pub backpressure_events: AtomicU64,
     pub circuit_breaker_trips: AtomicU64,
     pub memory_usage_bytes: AtomicU64,
+    pub peak_memory_usage_bytes: AtomicU64,
+    pub memory_pauses: AtomicU64,
+    pub trades_shed: AtomicU64,
+    pub bars_spilled: AtomicU64,
+    pub bars_undelivered: AtomicU64,
+    pub subscriber_bars_dropped: AtomicU64,
+    pub subscribers_disconnected: AtomicU64,
 }
 
 impl StreamingProcessor {
     /// Create new production streaming processor
-    pub fn new(threshold_bps: u32) -> Self {
-        Self::with_config(threshold_bps, StreamingProcessorConfig::default())
+    pub fn new(range: impl Into<BarRange>) -> Self {
+        Self::with_config(range, StreamingProcessorConfig::default())
     }
 
     /// Create with custom configuration
-    pub fn with_config(threshold_bps: u32, config: StreamingProcessorConfig) -> Self {
+    pub fn with_config(range: impl Into<BarRange>, config: StreamingProcessorConfig) -> Self {
+        let processor = RangeBarProcessor::with_config(range, config.processor);
+        Self::with_processor(processor, config)
+    }
+
+    /// Stream the output of any bar engine (e.g. tick, volume or dollar bars)
+    ///
+    /// `config.processor` is ignored; the engine comes fully configured.
+    /// Multi-output engines such as a
+    /// [`LadderProcessor`](crate::ladder::LadderProcessor) get one bounded bar
+    /// channel per output, see [`bar_receivers`](Self::bar_receivers).
+    pub fn with_processor(
+        processor: impl BarProcessor + 'static,
+        config: StreamingProcessorConfig,
+    ) -> Self {
         let (trade_sender, trade_receiver) = mpsc::channel(config.trade_channel_capacity);
-        let (bar_sender, bar_receiver) = mpsc::channel(config.bar_channel_capacity);
+        let outputs = processor.output_count();
+        let (bar_senders, bar_receivers) = (0..outputs)
+            .map(|_| {
+                let (sender, receiver) = mpsc::channel(config.bar_channel_capacity);
+                (sender, Some(receiver))
+            })
+            .unzip();
 
         let circuit_breaker_threshold = config.circuit_breaker_threshold;
         let circuit_breaker_timeout = config.circuit_breaker_timeout;
 
         Self {
-            processor: ExportRangeBarProcessor::new(threshold_bps),
-            threshold_bps,
+            processor: Box::new(processor),
             trade_sender: Some(trade_sender),
             trade_receiver,
-            bar_sender,
-            bar_receiver: Some(bar_receiver),
+            bar_senders,
+            bar_receivers,
+            broadcasts: (0..outputs)
+                .map(|_| BarBroadcast::new(config.bar_replay_capacity))
+                .collect(),
             config,
             metrics: Arc::new(StreamingMetrics::default()),
             circuit_breaker: CircuitBreaker::new(
                 circuit_breaker_threshold,
                 circuit_breaker_timeout,
             ),
+            spills: (0..outputs).map(|_| None).collect(),
+            paused: false,
         }
     }
 
@@ -136,12 +361,48 @@ impl StreamingProcessor {
         self.trade_sender.take()
     }
 
-    /// Get bar receiver for external components
+    /// Get bar receiver for external components (the first output of multi-output engines)
     pub fn bar_receiver(&mut self) -> Option<mpsc::Receiver<RangeBar>> {
-        self.bar_receiver.take()
+        self.bar_receivers[0].take()
+    }
+
+    /// Get one bar receiver per engine output, e.g. per ladder rung
+    ///
+    /// Every receiver must be drained: a full channel applies backpressure to
+    /// the whole processor. Returns `None` if any receiver was already taken.
+    pub fn bar_receivers(&mut self) -> Option<Vec<mpsc::Receiver<RangeBar>>> {
+        if self.bar_receivers.iter().any(Option::is_none) {
+            return None;
+        }
+        self.bar_receivers.iter_mut().map(Option::take).collect()
+    }
+
+    /// Subscribe to every bar of the first output
+    ///
+    /// Unlike [`bar_receiver`](Self::bar_receiver), any number of subscribers
+    /// can be added, each with its own buffer and [`LagPolicy`](crate::broadcast::LagPolicy). Once an
+    /// output has had a subscriber, its bar channel is only fed if its
+    /// receiver was taken, so subscribers alone never stall the processor.
+    pub fn subscribe(&self, config: SubscriptionConfig) -> BarSubscription {
+        self.broadcasts[0].subscribe(config)
+    }
+
+    /// Handle for subscribing to the first output from other tasks, e.g. while
+    /// [`start_processing`](Self::start_processing) runs
+    pub fn subscriptions(&self) -> BarSubscriptions {
+        self.broadcasts[0].subscriptions()
+    }
+
+    /// Handle for subscribing to one engine output, e.g. one ladder rung
+    pub fn output_subscriptions(&self, output: usize) -> Option<BarSubscriptions> {
+        self.broadcasts.get(output).map(BarBroadcast::subscriptions)
     }
 
     /// Start processing loop (bounded memory, infinite capability)
+    ///
+    /// Stops with the error if a completed bar cannot be delivered (bar
+    /// channel closed or spill file failure); the bars that were lost are
+    /// counted in `bars_undelivered` and subscriptions are closed.
     pub async fn start_processing(&mut self) -> Result<(), StreamingError> {
         loop {
             // Check circuit breaker state
@@ -150,6 +411,15 @@ impl StreamingProcessor {
                 continue;
             }
 
+            self.deliver_spilled()?;
+            if self.intake_paused() {
+                // Consumers taking bars is what lifts the pause; the timeout
+                // re-checks memory that was freed elsewhere
+                let _ =
+                    tokio::time::timeout(self.config.backpressure_timeout, self.bar_taken()).await;
+                continue;
+            }
+
             // Receive trade with timeout (prevents blocking forever)
             let trade = match tokio::time::timeout(
                 self.config.backpressure_timeout,
@@ -159,11 +429,12 @@ impl StreamingProcessor {
             {
                 Ok(Some(trade)) => trade,
                 Ok(None) => {
-                    // Channel closed - send final incomplete bar if exists
-                    if let Some(final_bar) = self.processor.get_incomplete_bar()
-                        && let Err(e) = self.send_bar_with_backpressure(final_bar).await
-                    {
-                        println!("Failed to send final incomplete bar: {:?}", e);
+                    // Channel closed - send final incomplete bars if they exist
+                    let final_bars = self.processor.finish_routed();
+                    self.deliver_all(final_bars).await?;
+                    self.drain_spilled().await?;
+                    for broadcast in &self.broadcasts {
+                        broadcast.close();
                     }
                     break;
                 }
@@ -172,17 +443,14 @@ impl StreamingProcessor {
 
             // Process single trade
             match self.process_single_trade(trade).await {
-                Ok(bar_opt) => {
+                Ok(bars) => {
                     self.circuit_breaker.record_success();
 
-                    // If bar completed, send with backpressure handling
-                    if let Some(bar) = bar_opt
-                        && let Err(e) = self.send_bar_with_backpressure(bar).await
-                    {
-                        println!("Failed to send bar: {:?}", e);
-                        self.circuit_breaker.record_failure();
-                    }
+                    // Deliver every completed bar in order; each send waits for capacity
+                    self.deliver_all(bars).await?;
                 }
+                // Shedding is load control, already counted in `trades_shed`
+                Err(StreamingError::MemoryThresholdExceeded) => {}
                 Err(e) => {
                     println!("Trade processing error: {:?}", e);
                     self.circuit_breaker.record_failure();
@@ -195,48 +463,201 @@ impl StreamingProcessor {
     }
 
     /// Process single trade - extracts completed bars without accumulation
+    ///
+    /// Returns every `(output, bar)` the trade completed, in completion order.
+    /// The batch is bounded by the engine: one bar per output for the core
+    /// engines, at most the held trades for a
+    /// [`ReorderingProcessor`](crate::reorder::ReorderingProcessor) flush.
     async fn process_single_trade(
         &mut self,
         trade: AggTrade,
-    ) -> Result<Option<RangeBar>, StreamingError> {
+    ) -> Result<Vec<(usize, RangeBar)>, StreamingError> {
+        if self.config.memory_policy == MemoryPolicy::Shed && !self.check_memory_usage() {
+            self.metrics.trades_shed.fetch_add(1, Ordering::Relaxed);
+            return Err(StreamingError::MemoryThresholdExceeded);
+        }
+
         // Update metrics
         self.metrics
             .trades_processed
             .fetch_add(1, Ordering::Relaxed);
 
-        // Process trade using existing algorithm (single trade at a time)
-        self.processor.process_trades_continuously(&[trade]);
+        // Process trade using the canonical engine (single trade at a time)
+        let completed_bars = self
+            .processor
+            .process_trades_routed(std::slice::from_ref(&trade))
+            .map_err(|e| StreamingError::ProcessingError(e.to_string()))?;
 
-        // Extract completed bars immediately (prevents accumulation)
-        let mut completed_bars = self.processor.get_all_completed_bars();
-
-        if !completed_bars.is_empty() {
-            // Bounded memory: only return first completed bar
-            // Additional bars would be rare edge cases but must be handled
-            let completed_bar = completed_bars.remove(0);
+        self.metrics
+            .bars_generated
+            .fetch_add(completed_bars.len() as u64, Ordering::Relaxed);
+        Ok(completed_bars)
+    }
 
-            // Handle rare case of multiple completions
-            if !completed_bars.is_empty() {
-                println!(
-                    "Warning: {} additional bars completed, dropping for bounded memory",
-                    completed_bars.len()
-                );
+    /// Deliver bars in order, stopping at the first that cannot be delivered
+    ///
+    /// That bar and the ones after it are counted as undelivered and the
+    /// subscriptions are closed, since the output can no longer stay complete.
+    async fn deliver_all(&mut self, bars: Vec<(usize, RangeBar)>) -> Result<(), StreamingError> {
+        let mut bars = bars.into_iter();
+        while let Some((output, bar)) = bars.next() {
+            if let Err(e) = self.deliver_bar(output, bar).await {
                 self.metrics
-                    .backpressure_events
-                    .fetch_add(completed_bars.len() as u64, Ordering::Relaxed);
+                    .bars_undelivered
+                    .fetch_add(1 + bars.len() as u64, Ordering::Relaxed);
+                for broadcast in &self.broadcasts {
+                    broadcast.close();
+                }
+                return Err(e);
             }
+        }
+        Ok(())
+    }
 
-            self.metrics.bars_generated.fetch_add(1, Ordering::Relaxed);
-            Ok(Some(completed_bar))
-        } else {
-            Ok(None)
+    /// Publish a bar to subscribers, then send it, or spill it under
+    /// [`MemoryPolicy::SpillToDisk`] once memory is over the threshold
+    ///
+    /// Once an output has spilled bars, its later bars queue behind them on
+    /// disk so the output stays in order. Subscribers are not spilled for;
+    /// their [`LagPolicy`](crate::broadcast::LagPolicy) applies instead.
+    async fn deliver_bar(&mut self, output: usize, bar: RangeBar) -> Result<(), StreamingError> {
+        let broadcast = &self.broadcasts[output];
+        let report = broadcast.publish(&bar).await;
+        self.metrics
+            .subscriber_bars_dropped
+            .fetch_add(report.dropped as u64, Ordering::Relaxed);
+        self.metrics
+            .subscribers_disconnected
+            .fetch_add(report.disconnected as u64, Ordering::Relaxed);
+        if broadcast.has_subscribed() && self.bar_receivers[output].is_some() {
+            return Ok(());
+        }
+
+        let MemoryPolicy::SpillToDisk { ref dir } = self.config.memory_policy else {
+            return self.send_bar_with_backpressure(output, bar).await;
+        };
+
+        // Below the threshold bars wait for channel capacity as usual
+        let spilling = self.spills[output]
+            .as_ref()
+            .is_some_and(|spill| spill.pending > 0);
+        if !spilling && self.check_memory_usage() {
+            return self.send_bar_with_backpressure(output, bar).await;
+        }
+
+        let spill = match self.spills[output] {
+            Some(ref mut spill) => spill,
+            None => {
+                self.spills[output].insert(SpillFile::create(dir, output).map_err(spill_failed)?)
+            }
+        };
+        spill.push(&bar)?;
+        self.metrics.bars_spilled.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Move spilled bars into bar channels with free capacity
+    fn deliver_spilled(&mut self) -> Result<(), StreamingError> {
+        for (sender, spill) in self.bar_senders.iter().zip(self.spills.iter_mut()) {
+            let Some(spill) = spill else { continue };
+            while spill.pending > 0 {
+                let Ok(permit) = sender.try_reserve() else {
+                    break;
+                };
+                if let Some(bar) = spill.pop()? {
+                    permit.send(bar);
+                }
+            }
         }
+        Ok(())
     }
 
-    /// Send bar with backpressure handling
-    async fn send_bar_with_backpressure(&self, bar: RangeBar) -> Result<(), StreamingError> {
+    /// Deliver every spilled bar, waiting for consumers (end of data)
+    async fn drain_spilled(&mut self) -> Result<(), StreamingError> {
+        for (sender, spill) in self.bar_senders.iter().zip(self.spills.iter_mut()) {
+            let Some(spill) = spill else { continue };
+            while let Some(bar) = spill.pop()? {
+                sender
+                    .send(bar)
+                    .await
+                    .map_err(|_| StreamingError::ChannelClosed)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether intake waits for consumers under [`MemoryPolicy::PauseIntake`]
+    fn intake_paused(&mut self) -> bool {
+        let paused = self.config.memory_policy == MemoryPolicy::PauseIntake
+            && !self.check_memory_usage()
+            && self.queued_bar_bytes() > 0;
+        if paused && !self.paused {
+            self.metrics.memory_pauses.fetch_add(1, Ordering::Relaxed);
+        }
+        self.paused = paused;
+        paused
+    }
+
+    /// Wait until a consumer takes a queued bar from any channel or subscriber buffer
+    async fn bar_taken(&self) {
+        let mut waits: Vec<BoxFuture<'_, ()>> = Vec::new();
+        for sender in &self.bar_senders {
+            // One permit more than is free only exists once a bar is received
+            let free = sender.capacity();
+            if free < sender.max_capacity() {
+                waits.push(sender.reserve_many(free + 1).map(drop).boxed());
+            }
+        }
+        for broadcast in &self.broadcasts {
+            if broadcast.queued_bars() > 0 {
+                waits.push(broadcast.bar_taken().boxed());
+            }
+        }
+        if !waits.is_empty() {
+            future::select_all(waits).await;
+        }
+    }
+
+    /// Bytes of bars waiting in the bar channels and subscriber buffers
+    fn queued_bar_bytes(&self) -> usize {
+        let channels: usize = self
+            .bar_senders
+            .iter()
+            .map(|sender| sender.max_capacity() - sender.capacity())
+            .sum();
+        let subscribers: usize = self.broadcasts.iter().map(BarBroadcast::queued_bars).sum();
+        (channels + subscribers) * std::mem::size_of::<RangeBar>()
+    }
+
+    /// Measure memory usage, recording it and its peak in the metrics
+    pub fn update_memory_usage(&self) -> usize {
+        let usage = match self.config.memory_accounting {
+            MemoryAccounting::Buffered => {
+                self.trade_receiver.len() * std::mem::size_of::<AggTrade>()
+                    + self.queued_bar_bytes()
+                    + self.processor.buffered_bytes()
+            }
+            MemoryAccounting::Allocator => CountingAllocator::allocated_bytes(),
+        };
+        self.metrics
+            .memory_usage_bytes
+            .store(usage as u64, Ordering::Relaxed);
+        self.metrics
+            .peak_memory_usage_bytes
+            .fetch_max(usage as u64, Ordering::Relaxed);
+        usage
+    }
+
+    /// Send bar to an output channel with backpressure handling
+    async fn send_bar_with_backpressure(
+        &self,
+        output: usize,
+        bar: RangeBar,
+    ) -> Result<(), StreamingError> {
+        let bar_sender = &self.bar_senders[output];
+
         // Use try_send for immediate check, then send for blocking
-        match self.bar_sender.try_send(bar.clone()) {
+        match bar_sender.try_send(bar.clone()) {
             Ok(()) => Ok(()),
             Err(mpsc::error::TrySendError::Full(_)) => {
                 // Apply backpressure - channel is full
@@ -246,7 +667,7 @@ impl StreamingProcessor {
                     .fetch_add(1, Ordering::Relaxed);
 
                 // Wait for capacity with blocking send
-                self.bar_sender
+                bar_sender
                     .send(bar)
                     .await
                     .map_err(|_| StreamingError::ChannelClosed)
@@ -262,13 +683,12 @@ impl StreamingProcessor {
 
     /// Extract final incomplete bar when stream ends (for algorithmic consistency)
     pub fn get_final_incomplete_bar(&mut self) -> Option<RangeBar> {
-        self.processor.get_incomplete_bar()
+        self.processor.finish()
     }
 
-    /// Check memory usage against threshold
+    /// Measure memory usage and check it against the threshold
     pub fn check_memory_usage(&self) -> bool {
-        let current_usage = self.metrics.memory_usage_bytes.load(Ordering::Relaxed);
-        current_usage < self.config.memory_threshold_bytes as u64
+        self.update_memory_usage() < self.config.memory_threshold_bytes
     }
 }
 
@@ -330,13 +750,33 @@ impl CircuitBreaker {
 }
 
 /// Stream implementation for range bars (true streaming)
+///
+/// Reads a [`StreamingProcessor`] bar channel, or wraps any bar stream such
+/// as a [`RangeBarStreamAdapter`](crate::bar_stream::RangeBarStreamAdapter).
 pub struct RangeBarStream {
-    receiver: mpsc::Receiver<RangeBar>,
+    source: BarSource,
+}
+
+/// Where a [`RangeBarStream`] gets its bars
+enum BarSource {
+    Channel(mpsc::Receiver<RangeBar>),
+    Stream(Pin<Box<dyn Stream<Item = Result<RangeBar, StreamingError>> + Send>>),
 }
 
 impl RangeBarStream {
     pub fn new(receiver: mpsc::Receiver<RangeBar>) -> Self {
-        Self { receiver }
+        Self {
+            source: BarSource::Channel(receiver),
+        }
+    }
+
+    /// Wrap a bar stream built without a [`StreamingProcessor`]
+    pub fn from_stream(
+        stream: impl Stream<Item = Result<RangeBar, StreamingError>> + Send + 'static,
+    ) -> Self {
+        Self {
+            source: BarSource::Stream(Box::pin(stream)),
+        }
     }
 }
 
@@ -344,10 +784,13 @@ impl Stream for RangeBarStream {
     type Item = Result<RangeBar, StreamingError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match self.receiver.poll_recv(cx) {
-            Poll::Ready(Some(bar)) => Poll::Ready(Some(Ok(bar))),
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
+        match self.source {
+            BarSource::Channel(ref mut receiver) => match receiver.poll_recv(cx) {
+                Poll::Ready(Some(bar)) => Poll::Ready(Some(Ok(bar))),
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            },
+            BarSource::Stream(ref mut stream) => stream.as_mut().poll_next(cx),
         }
     }
 }
@@ -369,6 +812,12 @@ pub enum StreamingError {
 
     #[error("Processing error: {0}")]
     ProcessingError(String),
+
+    #[error("Bar spill file failed: {0}")]
+    SpillFailed(String),
+
+    #[error("Subscriber disconnected after falling {capacity} bars behind")]
+    SubscriberLagged { capacity: usize },
 }
 
 impl StreamingMetrics {
@@ -381,6 +830,13 @@ impl StreamingMetrics {
             backpressure_events: self.backpressure_events.load(Ordering::Relaxed),
             circuit_breaker_trips: self.circuit_breaker_trips.load(Ordering::Relaxed),
             memory_usage_bytes: self.memory_usage_bytes.load(Ordering::Relaxed),
+            peak_memory_usage_bytes: self.peak_memory_usage_bytes.load(Ordering::Relaxed),
+            memory_pauses: self.memory_pauses.load(Ordering::Relaxed),
+            trades_shed: self.trades_shed.load(Ordering::Relaxed),
+            bars_spilled: self.bars_spilled.load(Ordering::Relaxed),
+            bars_undelivered: self.bars_undelivered.load(Ordering::Relaxed),
+            subscriber_bars_dropped: self.subscriber_bars_dropped.load(Ordering::Relaxed),
+            subscribers_disconnected: self.subscribers_disconnected.load(Ordering::Relaxed),
         }
     }
 }
@@ -394,6 +850,13 @@ pub struct MetricsSummary {
     pub backpressure_events: u64,
     pub circuit_breaker_trips: u64,
     pub memory_usage_bytes: u64,
+    pub peak_memory_usage_bytes: u64,
+    pub memory_pauses: u64,
+    pub trades_shed: u64,
+    pub bars_spilled: u64,
+    pub bars_undelivered: u64,
+    pub subscriber_bars_dropped: u64,
+    pub subscribers_disconnected: u64,
 }
 
 impl MetricsSummary {
@@ -449,9 +912,9 @@ mod tests {
         // Send 1000 trades
         for i in 0..1000 {
             let trade = create_test_trade(i, 23000.0 + (i as f64), 1659312000000 + i);
-            if let Ok(bar_opt) = processor.process_single_trade(trade).await {
+            if let Ok(bars) = processor.process_single_trade(trade).await {
                 // Verify no accumulation - at most one bar per trade
-                assert!(bar_opt.is_none() || bar_opt.is_some());
+                assert!(bars.len() <= 1);
             }
         }
 
@@ -498,6 +961,13 @@ mod tests {
             backpressure_events: 2,
             circuit_breaker_trips: 1,
             memory_usage_bytes: 50_000_000,
+            peak_memory_usage_bytes: 60_000_000,
+            memory_pauses: 0,
+            trades_shed: 0,
+            bars_spilled: 0,
+            bars_undelivered: 0,
+            subscriber_bars_dropped: 0,
+            subscribers_disconnected: 0,
         };
 
         assert_eq!(metrics.bars_per_trade(), 0.05);
@@ -0,0 +1,26 @@
+//! Fixtures shared by the unit tests
+
+use crate::fixed_point::FixedPoint;
+use crate::types::AggTrade;
+
+/// One-unit trade at `price`, one millisecond per id
+fn create_test_trade(id: i64, price: &str) -> AggTrade {
+    AggTrade {
+        agg_trade_id: id,
+        price: FixedPoint::from_str(price).unwrap(),
+        volume: FixedPoint::from_str("1.0").unwrap(),
+        first_trade_id: id,
+        last_trade_id: id,
+        timestamp: 1_000 + id,
+        is_buyer_maker: false,
+    }
+}
+
+/// Zig-zag around 100.0 that crosses 25 bps thresholds both ways
+pub(crate) fn create_trades() -> Vec<AggTrade> {
+    ["100.0", "100.1", "100.3", "100.2", "99.9", "100.0", "100.1"]
+        .iter()
+        .enumerate()
+        .map(|(i, price)| create_test_trade(i as i64, price))
+        .collect()
+}
@@ -0,0 +1,673 @@
+//! Range threshold with sub-basis-point precision
+//!
+//! A [`Threshold`] is stored as an integer count of decibasis points
+//! (1 decibps = 0.1 bps = 0.001%), so fractional thresholds such as 0.5 bps
+//! stay exact and breach levels are still computed in integer arithmetic.
+
+use crate::fixed_point::{BASIS_POINTS_SCALE, FixedPoint, FixedPointError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Decibasis points per basis point
+pub const DECIBPS_PER_BPS: u32 = 10;
+
+/// Decibasis points in 100% (threshold denominator)
+pub const THRESHOLD_SCALE: i128 = BASIS_POINTS_SCALE as i128 * DECIBPS_PER_BPS as i128;
+
+/// Decibasis points per percent
+const DECIBPS_PER_PERCENT: u32 = 100 * DECIBPS_PER_BPS;
+
+/// Range bar threshold, measured from the bar's open price
+///
+/// Construct explicitly with [`from_bps`](Self::from_bps),
+/// [`from_decibps`](Self::from_decibps) or [`from_percent`](Self::from_percent),
+/// or parse strings such as `"25"`, `"0.5bps"`, `"5dbps"` or `"0.25%"`.
+/// A bare number means basis points. Plain `u32` values convert as whole bps
+/// and panic if that overflows; see [`checked_from_bps`](Self::checked_from_bps).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Threshold(u32);
+
+impl Threshold {
+    /// Threshold of `bps` whole basis points (25 = 0.25%)
+    ///
+    /// # Panics
+    ///
+    /// If `bps` does not fit in decibasis points; see
+    /// [`checked_from_bps`](Self::checked_from_bps)
+    pub const fn from_bps(bps: u32) -> Self {
+        match bps.checked_mul(DECIBPS_PER_BPS) {
+            Some(decibps) => Self(decibps),
+            None => panic!("threshold overflows decibasis points"),
+        }
+    }
+
+    /// [`from_bps`](Self::from_bps), failing on overflow
+    pub fn checked_from_bps(bps: u32) -> Result<Self, ThresholdError> {
+        bps.checked_mul(DECIBPS_PER_BPS)
+            .map(Self)
+            .ok_or_else(|| ThresholdError::Overflow(format!("{}bps", bps)))
+    }
+
+    /// Threshold of `decibps` tenths of a basis point (5 = 0.5 bps)
+    pub const fn from_decibps(decibps: u32) -> Self {
+        Self(decibps)
+    }
+
+    /// Threshold from a percentage (0.25 = 25 bps)
+    ///
+    /// Fails if the value is negative, not finite, or finer than 0.1 bps.
+    pub fn from_percent(percent: f64) -> Result<Self, ThresholdError> {
+        if !percent.is_finite() || percent < 0.0 {
+            return Err(ThresholdError::InvalidFormat(percent.to_string()));
+        }
+
+        let decibps = percent * DECIBPS_PER_PERCENT as f64;
+        let rounded = decibps.round();
+        if (decibps - rounded).abs() > 1e-6 {
+            return Err(ThresholdError::TooPrecise(format!("{}%", percent)));
+        }
+        if rounded > u32::MAX as f64 {
+            return Err(ThresholdError::Overflow(format!("{}%", percent)));
+        }
+
+        Ok(Self(rounded as u32))
+    }
+
+    /// Threshold in decibasis points
+    pub const fn decibps(&self) -> u32 {
+        self.0
+    }
+
+    /// Threshold in basis points, if it is a whole number of them
+    pub const fn whole_bps(&self) -> Option<u32> {
+        if self.0.is_multiple_of(DECIBPS_PER_BPS) {
+            Some(self.0 / DECIBPS_PER_BPS)
+        } else {
+            None
+        }
+    }
+
+    /// Threshold in basis points (for display and reporting)
+    pub fn as_bps(&self) -> f64 {
+        self.0 as f64 / DECIBPS_PER_BPS as f64
+    }
+
+    /// Threshold as a percentage (for display and reporting)
+    pub fn as_percent(&self) -> f64 {
+        self.0 as f64 / DECIBPS_PER_PERCENT as f64
+    }
+
+    /// Threshold as a fraction of price (0.0025 for 25 bps)
+    pub fn as_fraction(&self) -> f64 {
+        self.0 as f64 / THRESHOLD_SCALE as f64
+    }
+}
+
+impl From<u32> for Threshold {
+    fn from(bps: u32) -> Self {
+        Self::from_bps(bps)
+    }
+}
+
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.0 / DECIBPS_PER_BPS;
+        match self.0 % DECIBPS_PER_BPS {
+            0 => write!(f, "{}bps", whole),
+            tenths => write!(f, "{}.{}bps", whole, tenths),
+        }
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = ThresholdError;
+
+    /// Parse `"25"`, `"25bps"`, `"0.5 bps"`, `"5dbps"`, `"5decibps"` or `"0.25%"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_ascii_lowercase();
+
+        let (number, decibps_per_unit) = if let Some(n) = input.strip_suffix('%') {
+            (n, DECIBPS_PER_PERCENT)
+        } else if let Some(n) = input
+            .strip_suffix("decibps")
+            .or_else(|| input.strip_suffix("dbps"))
+        {
+            (n, 1)
+        } else if let Some(n) = input
+            .strip_suffix("bps")
+            .or_else(|| input.strip_suffix("bp"))
+        {
+            (n, DECIBPS_PER_BPS)
+        } else {
+            (input.as_str(), DECIBPS_PER_BPS)
+        };
+
+        let invalid = || ThresholdError::InvalidFormat(s.to_string());
+        let number = number.trim();
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let fraction = fraction.trim_end_matches('0');
+
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !all_digits(integer)
+            || !all_digits(fraction)
+        {
+            return Err(invalid());
+        }
+        if fraction.len() > 9 {
+            return Err(ThresholdError::TooPrecise(s.to_string()));
+        }
+
+        // Exact decimal arithmetic: value = digits / 10^fraction_len
+        let overflow = || ThresholdError::Overflow(s.to_string());
+        let denominator = 10u128.pow(fraction.len() as u32);
+        let digits: u128 = format!("{}{}", integer, fraction)
+            .parse()
+            .map_err(|_| overflow())?;
+        let scaled = digits
+            .checked_mul(decibps_per_unit as u128)
+            .ok_or_else(overflow)?;
+
+        if !scaled.is_multiple_of(denominator) {
+            return Err(ThresholdError::TooPrecise(s.to_string()));
+        }
+
+        u32::try_from(scaled / denominator)
+            .map(Self)
+            .map_err(|_| overflow())
+    }
+}
+
+/// Upward and downward breach distances, both measured from the bar open
+///
+/// Symmetric by default; [`asymmetric`](Self::asymmetric) configures the two
+/// sides separately (e.g. +30 bps / -20 bps for take-profit/stop-loss studies).
+/// Plain `u32` and [`Threshold`] values convert to symmetric thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BreachThresholds {
+    /// Distance above the open that closes the bar
+    pub up: Threshold,
+
+    /// Distance below the open that closes the bar
+    pub down: Threshold,
+}
+
+impl BreachThresholds {
+    /// Same distance in both directions
+    pub const fn symmetric(threshold: Threshold) -> Self {
+        Self {
+            up: threshold,
+            down: threshold,
+        }
+    }
+
+    /// Separate upward and downward distances
+    pub const fn asymmetric(up: Threshold, down: Threshold) -> Self {
+        Self { up, down }
+    }
+
+    /// Whether both directions use the same distance
+    pub fn is_symmetric(&self) -> bool {
+        self.up == self.down
+    }
+}
+
+impl From<Threshold> for BreachThresholds {
+    fn from(threshold: Threshold) -> Self {
+        Self::symmetric(threshold)
+    }
+}
+
+impl From<u32> for BreachThresholds {
+    fn from(bps: u32) -> Self {
+        Self::symmetric(Threshold::from_bps(bps))
+    }
+}
+
+impl fmt::Display for BreachThresholds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_symmetric() {
+            write!(f, "±{}", self.up)
+        } else {
+            write!(f, "+{}/-{}", self.up, self.down)
+        }
+    }
+}
+
+/// Breach distance definition for a range bar
+///
+/// Either relative to the open ([`BreachThresholds`], the default) or a fixed
+/// absolute price distance, as used by classic tick-based range bars on
+/// charting platforms. Both feed the same breach logic and produce identical
+/// `RangeBar` output.
+///
+/// Deserialization rejects absolute distances that are not positive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", try_from = "BarRangeRepr")]
+pub enum BarRange {
+    /// Percentage distances from the bar open
+    Relative(BreachThresholds),
+
+    /// Fixed price distances from the bar open
+    Absolute {
+        /// Distance above the open that closes the bar
+        up: FixedPoint,
+
+        /// Distance below the open that closes the bar
+        down: FixedPoint,
+    },
+}
+
+/// Unvalidated [`BarRange`], checked by `TryFrom` on deserialization
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BarRangeRepr {
+    Relative(BreachThresholds),
+    Absolute { up: FixedPoint, down: FixedPoint },
+}
+
+impl TryFrom<BarRangeRepr> for BarRange {
+    type Error = ThresholdError;
+
+    fn try_from(repr: BarRangeRepr) -> Result<Self, Self::Error> {
+        match repr {
+            BarRangeRepr::Relative(thresholds) => Ok(Self::Relative(thresholds)),
+            BarRangeRepr::Absolute { up, down } => Ok(Self::Absolute {
+                up: positive_distance(up)?,
+                down: positive_distance(down)?,
+            }),
+        }
+    }
+}
+
+/// `distance` if it is above zero
+fn positive_distance(distance: FixedPoint) -> Result<FixedPoint, ThresholdError> {
+    if distance.0 > 0 {
+        Ok(distance)
+    } else {
+        Err(ThresholdError::NonPositiveDistance(distance))
+    }
+}
+
+impl BarRange {
+    /// Same absolute price distance in both directions
+    ///
+    /// # Panics
+    ///
+    /// If `delta` is not positive; see [`checked_absolute`](Self::checked_absolute)
+    pub fn absolute(delta: FixedPoint) -> Self {
+        Self::checked_absolute(delta).expect("absolute range must be positive")
+    }
+
+    /// [`absolute`](Self::absolute), failing if `delta` is zero or negative
+    pub fn checked_absolute(delta: FixedPoint) -> Result<Self, ThresholdError> {
+        let delta = positive_distance(delta)?;
+        Ok(Self::Absolute {
+            up: delta,
+            down: delta,
+        })
+    }
+
+    /// Range of `ticks` price ticks of `tick_size` in both directions
+    ///
+    /// # Panics
+    ///
+    /// If the distance is not positive or overflows; see
+    /// [`checked_ticks`](Self::checked_ticks)
+    pub fn ticks(ticks: u32, tick_size: FixedPoint) -> Self {
+        Self::checked_ticks(ticks, tick_size).expect("invalid tick range")
+    }
+
+    /// [`ticks`](Self::ticks), failing if the distance is not positive or overflows
+    pub fn checked_ticks(ticks: u32, tick_size: FixedPoint) -> Result<Self, ThresholdError> {
+        let delta = tick_size
+            .0
+            .checked_mul(ticks as i64)
+            .ok_or_else(|| ThresholdError::Overflow(format!("{} ticks of {}", ticks, tick_size)))?;
+        Self::checked_absolute(FixedPoint(delta))
+    }
+
+    /// Compute (upper, lower) breach levels, fixed from the bar open
+    ///
+    /// # Panics
+    ///
+    /// If a level overflows; see [`checked_breach_levels`](Self::checked_breach_levels)
+    pub fn breach_levels(&self, open: FixedPoint) -> (FixedPoint, FixedPoint) {
+        self.checked_breach_levels(open)
+            .expect("breach level overflows FixedPoint")
+    }
+
+    /// [`breach_levels`](Self::breach_levels), failing on overflow
+    pub fn checked_breach_levels(
+        &self,
+        open: FixedPoint,
+    ) -> Result<(FixedPoint, FixedPoint), FixedPointError> {
+        match *self {
+            Self::Relative(thresholds) => open.checked_breach_levels(thresholds),
+            Self::Absolute { up, down } => open
+                .checked_add(up)
+                .zip(open.checked_sub(down))
+                .ok_or(FixedPointError::Overflow),
+        }
+    }
+}
+
+impl Default for BarRange {
+    fn default() -> Self {
+        Self::Relative(BreachThresholds::symmetric(Threshold::from_bps(25)))
+    }
+}
+
+impl From<BreachThresholds> for BarRange {
+    fn from(thresholds: BreachThresholds) -> Self {
+        Self::Relative(thresholds)
+    }
+}
+
+impl From<Threshold> for BarRange {
+    fn from(threshold: Threshold) -> Self {
+        Self::Relative(threshold.into())
+    }
+}
+
+impl From<u32> for BarRange {
+    fn from(bps: u32) -> Self {
+        Self::Relative(bps.into())
+    }
+}
+
+impl fmt::Display for BarRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Relative(thresholds) => write!(f, "{}", thresholds),
+            Self::Absolute { up, down } if up == down => write!(f, "±{}", up),
+            Self::Absolute { up, down } => write!(f, "+{}/-{}", up, down),
+        }
+    }
+}
+
+/// Serialized as a number of basis points (`25` or `0.5`)
+impl Serialize for Threshold {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.whole_bps() {
+            Some(bps) => serializer.serialize_u32(bps),
+            None => serializer.serialize_f64(self.as_bps()),
+        }
+    }
+}
+
+/// Accepts a number of basis points or any string [`FromStr`] understands
+impl<'de> Deserialize<'de> for Threshold {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ThresholdVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ThresholdVisitor {
+            type Value = Threshold;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a threshold in basis points or a string like \"0.25%\"")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Threshold, E> {
+                let bps = u32::try_from(v)
+                    .map_err(|_| E::custom(ThresholdError::Overflow(v.to_string())))?;
+                Threshold::checked_from_bps(bps).map_err(E::custom)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Threshold, E> {
+                let v = u64::try_from(v)
+                    .map_err(|_| E::custom(ThresholdError::InvalidFormat(v.to_string())))?;
+                self.visit_u64(v)
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Threshold, E> {
+                Threshold::from_percent(v / 100.0).map_err(E::custom)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Threshold, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            // serde_json's `arbitrary_precision` hands numbers over as a
+            // single-entry map holding the literal digits
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Threshold, A::Error> {
+                let (_, digits): (String, String) = map
+                    .next_entry()?
+                    .ok_or_else(|| serde::de::Error::custom("empty threshold map"))?;
+                digits.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(ThresholdVisitor)
+    }
+}
+
+/// Threshold construction and validation errors
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ThresholdError {
+    #[error("Invalid threshold '{0}'")]
+    InvalidFormat(String),
+
+    #[error("Threshold '{0}' is finer than 0.1 bps")]
+    TooPrecise(String),
+
+    #[error("Threshold '{0}' is too large")]
+    Overflow(String),
+
+    #[error("Threshold {threshold} is below minimum {min}")]
+    BelowMinimum {
+        threshold: Threshold,
+        min: Threshold,
+    },
+
+    #[error("Threshold {threshold} exceeds maximum {max}")]
+    AboveMaximum {
+        threshold: Threshold,
+        max: Threshold,
+    },
+
+    #[error("Absolute range distance {0} must be positive")]
+    NonPositiveDistance(FixedPoint),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AlgorithmConfig;
+
+    #[test]
+    fn test_constructors_agree() {
+        assert_eq!(Threshold::from_bps(25), Threshold::from_decibps(250));
+        assert_eq!(
+            Threshold::from_percent(0.25).unwrap(),
+            Threshold::from_bps(25)
+        );
+        assert_eq!(
+            Threshold::from_percent(0.005).unwrap(),
+            Threshold::from_decibps(5)
+        );
+        assert_eq!(Threshold::from(80u32), Threshold::from_bps(80));
+
+        assert!(Threshold::from_percent(0.00001).is_err());
+        assert!(Threshold::from_percent(-1.0).is_err());
+        assert!(Threshold::from_percent(f64::NAN).is_err());
+
+        assert_eq!(Threshold::checked_from_bps(25), Ok(Threshold::from_bps(25)));
+        assert!(matches!(
+            Threshold::checked_from_bps(u32::MAX / 5),
+            Err(ThresholdError::Overflow(_))
+        ));
+        assert!(serde_json::from_str::<Threshold>(&(u32::MAX / 5).to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            ("25", 250),
+            ("25bps", 250),
+            (" 0.5 bps ", 5),
+            ("0.5BPS", 5),
+            ("5dbps", 5),
+            ("5decibps", 5),
+            ("0.25%", 250),
+            ("0.005%", 5),
+            ("1.50", 15),
+            (".5", 5),
+        ];
+        for (input, decibps) in cases {
+            assert_eq!(
+                input.parse::<Threshold>().unwrap().decibps(),
+                decibps,
+                "{}",
+                input
+            );
+        }
+
+        assert!(matches!(
+            "0.25bps".parse::<Threshold>(),
+            Err(ThresholdError::TooPrecise(_))
+        ));
+        assert!(matches!(
+            "0.5dbps".parse::<Threshold>(),
+            Err(ThresholdError::TooPrecise(_))
+        ));
+        for input in ["", "bps", "-1", "abc", "1.2.3", "1e3", "99999999999"] {
+            assert!(input.parse::<Threshold>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for decibps in [1, 5, 10, 15, 250, 10_000] {
+            let threshold = Threshold::from_decibps(decibps);
+            assert_eq!(
+                threshold.to_string().parse::<Threshold>().unwrap(),
+                threshold
+            );
+        }
+        assert_eq!(Threshold::from_bps(25).to_string(), "25bps");
+        assert_eq!(Threshold::from_decibps(5).to_string(), "0.5bps");
+    }
+
+    #[test]
+    fn test_serde() {
+        assert_eq!(
+            serde_json::to_string(&Threshold::from_bps(25)).unwrap(),
+            "25"
+        );
+        assert_eq!(
+            serde_json::to_string(&Threshold::from_decibps(5)).unwrap(),
+            "0.5"
+        );
+
+        for (json, decibps) in [
+            ("25", 250),
+            ("0.5", 5),
+            ("\"0.25%\"", 250),
+            ("\"5dbps\"", 5),
+        ] {
+            let threshold: Threshold = serde_json::from_str(json).unwrap();
+            assert_eq!(threshold.decibps(), decibps, "{}", json);
+        }
+        assert!(serde_json::from_str::<Threshold>("-5").is_err());
+        assert!(serde_json::from_str::<Threshold>("0.25").is_err());
+    }
+
+    #[test]
+    fn test_bar_range_levels() {
+        let open = FixedPoint::from_str("50000.0").unwrap();
+        let tick = FixedPoint::from_str("0.1").unwrap();
+
+        let (upper, lower) = BarRange::ticks(5, tick).breach_levels(open);
+        assert_eq!(upper.to_string(), "50000.50000000");
+        assert_eq!(lower.to_string(), "49999.50000000");
+
+        let (upper, lower) = BarRange::from(25).breach_levels(open);
+        assert_eq!(upper.to_string(), "50125.00000000");
+        assert_eq!(lower.to_string(), "49875.00000000");
+
+        let range = BarRange::Absolute {
+            up: FixedPoint::from_str("30").unwrap(),
+            down: FixedPoint::from_str("20").unwrap(),
+        };
+        assert_eq!(range.breach_levels(open).1.to_string(), "49980.00000000");
+
+        let json = serde_json::to_string(&range).unwrap();
+        assert_eq!(serde_json::from_str::<BarRange>(&json).unwrap(), range);
+
+        assert_eq!(
+            BarRange::checked_ticks(5, tick),
+            Ok(BarRange::ticks(5, tick))
+        );
+        assert!(matches!(
+            BarRange::checked_ticks(u32::MAX, FixedPoint(i64::MAX / 2)),
+            Err(ThresholdError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_absolute_range_must_be_positive() {
+        let tick = FixedPoint::from_str("0.1").unwrap();
+        assert_eq!(
+            BarRange::checked_absolute(FixedPoint(0)),
+            Err(ThresholdError::NonPositiveDistance(FixedPoint(0)))
+        );
+        assert!(BarRange::checked_absolute(FixedPoint(-1)).is_err());
+        assert!(BarRange::checked_ticks(0, tick).is_err());
+        assert!(BarRange::checked_ticks(5, FixedPoint(-10)).is_err());
+
+        let valid = r#"{"absolute":{"up":100,"down":50}}"#;
+        let negative = r#"{"absolute":{"up":100,"down":-50}}"#;
+        let zero = r#"{"absolute":{"up":0,"down":50}}"#;
+        assert_eq!(
+            serde_json::from_str::<BarRange>(valid).unwrap(),
+            BarRange::Absolute {
+                up: FixedPoint(100),
+                down: FixedPoint(50)
+            }
+        );
+        let error = serde_json::from_str::<BarRange>(negative).unwrap_err();
+        assert!(error.to_string().contains("must be positive"), "{}", error);
+        assert!(serde_json::from_str::<BarRange>(zero).is_err());
+
+        let relative = serde_json::to_string(&BarRange::from(25)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BarRange>(&relative).unwrap(),
+            BarRange::from(25)
+        );
+    }
+
+    #[test]
+    fn test_check_bounds() {
+        let config = AlgorithmConfig::default();
+        assert!(config.check_threshold(Threshold::from_bps(25)).is_ok());
+        assert!(config.check_threshold(Threshold::from_bps(1000)).is_ok());
+        assert!(matches!(
+            config.check_threshold(Threshold::from_decibps(5)),
+            Err(ThresholdError::BelowMinimum { .. })
+        ));
+        assert!(matches!(
+            config.check_threshold(Threshold::from_bps(1001)),
+            Err(ThresholdError::AboveMaximum { .. })
+        ));
+
+        // Sub-bps thresholds are allowed once the configured minimum permits them
+        let config = AlgorithmConfig {
+            min_threshold_bps: Threshold::from_decibps(5),
+            ..Default::default()
+        };
+        assert!(config.check_threshold(Threshold::from_decibps(5)).is_ok());
+        assert!(config.check_threshold(Threshold::from_decibps(4)).is_err());
+
+        let config = AlgorithmConfig {
+            min_threshold_bps: Threshold::from_bps(0),
+            ..Default::default()
+        };
+        assert!(config.check_threshold(Threshold::from_bps(0)).is_err());
+    }
+}
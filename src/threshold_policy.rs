@@ -0,0 +1,533 @@
+//! Adaptive threshold policies
+//!
+//! A [`ThresholdPolicy`] chooses the breach distance for each new range bar.
+//! The processor asks it exactly once per bar, at the opening trade, and
+//! reports every completed bar back to it. Those two calls are the only inputs
+//! a policy ever sees, so its decision for a bar can depend on the opening
+//! trade and on bars that closed before it, never on trades inside the bar.
+//!
+//! The distance chosen for a bar is recorded on the bar itself
+//! ([`RangeBar::upper_threshold`] and [`RangeBar::lower_threshold`]), which
+//! keeps adaptive runs auditable after the fact.
+
+use crate::threshold::{BarRange, BreachThresholds, THRESHOLD_SCALE, Threshold};
+use crate::trade::Trade;
+use crate::types::RangeBar;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Milliseconds per hour, for bar rate calculations
+const MS_PER_HOUR: f64 = 3_600_000.0;
+
+/// Chooses the breach distance for each new range bar
+///
+/// Implementations must be deterministic: the same sequence of calls yields
+/// the same ranges, which is what makes checkpointed and chunked runs
+/// reproducible.
+pub trait ThresholdPolicy: Send + Sync {
+    /// Stable identifier recorded in processor checkpoints
+    fn name(&self) -> &'static str;
+
+    /// Breach distance for the bar opened by `opening_trade`
+    fn next_range(&mut self, opening_trade: &dyn Trade) -> BarRange;
+
+    /// Observe a completed bar (called before the next bar opens)
+    fn on_bar_closed(&mut self, bar: &RangeBar);
+
+    /// Serializable internal state for processor checkpoints
+    fn checkpoint_state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Restore state produced by [`checkpoint_state`](Self::checkpoint_state)
+    fn restore_state(&mut self, _state: serde_json::Value) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+}
+
+impl<P: ThresholdPolicy + ?Sized> ThresholdPolicy for Box<P> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn next_range(&mut self, opening_trade: &dyn Trade) -> BarRange {
+        (**self).next_range(opening_trade)
+    }
+
+    fn on_bar_closed(&mut self, bar: &RangeBar) {
+        (**self).on_bar_closed(bar)
+    }
+
+    fn checkpoint_state(&self) -> serde_json::Value {
+        (**self).checkpoint_state()
+    }
+
+    fn restore_state(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        (**self).restore_state(state)
+    }
+}
+
+/// Same breach distance for every bar (the classic range bar)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FixedThreshold(pub BarRange);
+
+impl FixedThreshold {
+    /// Checkpoint identifier
+    pub const NAME: &'static str = "fixed";
+}
+
+impl ThresholdPolicy for FixedThreshold {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn next_range(&mut self, _opening_trade: &dyn Trade) -> BarRange {
+        self.0
+    }
+
+    fn on_bar_closed(&mut self, _bar: &RangeBar) {}
+
+    fn checkpoint_state(&self) -> serde_json::Value {
+        serde_json::to_value(self.0).expect("BarRange serializes to JSON")
+    }
+
+    fn restore_state(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        self.0 = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+impl<T: Into<BarRange>> From<T> for FixedThreshold {
+    fn from(range: T) -> Self {
+        Self(range.into())
+    }
+}
+
+/// Threshold used before any history exists and the range adaptive policies stay within
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdaptiveBounds {
+    /// Threshold for bars opened before the policy has enough history
+    pub initial: Threshold,
+
+    /// Smallest threshold the policy may choose
+    pub min: Threshold,
+
+    /// Largest threshold the policy may choose
+    pub max: Threshold,
+}
+
+impl AdaptiveBounds {
+    /// Clamp a threshold expressed in decibps, falling back to `initial` for non-finite input
+    fn clamp(&self, decibps: f64) -> Threshold {
+        if !decibps.is_finite() {
+            return self.initial;
+        }
+        let min = self.min.decibps().max(1) as f64;
+        let max = (self.max.decibps() as f64).max(min);
+        Threshold::from_decibps(decibps.round().clamp(min, max) as u32)
+    }
+}
+
+impl Default for AdaptiveBounds {
+    fn default() -> Self {
+        Self {
+            initial: Threshold::from_bps(25),
+            min: Threshold::from_bps(1),
+            max: Threshold::from_bps(1000),
+        }
+    }
+}
+
+/// Relative high-low range of a bar in decibps of its open
+fn bar_range_decibps(bar: &RangeBar) -> f64 {
+    (bar.high.0 - bar.low.0) as f64 / bar.open.0 as f64 * THRESHOLD_SCALE as f64
+}
+
+/// Symmetric relative range for an adaptive threshold
+fn symmetric(threshold: Threshold) -> BarRange {
+    BarRange::Relative(BreachThresholds::symmetric(threshold))
+}
+
+/// Threshold follows an exponentially weighted average of completed bar ranges
+///
+/// `threshold = multiplier * ewma(high - low) / open`, clamped to the bounds.
+/// A completed bar spans at least its own threshold, so multipliers of `1.0`
+/// or more ratchet the threshold upwards; values around `0.5` are stable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EwmaRangePolicy {
+    /// Smoothing factor in `(0, 1]`; higher reacts faster
+    pub alpha: f64,
+
+    /// Scale applied to the averaged range
+    pub multiplier: f64,
+
+    /// Initial threshold and clamp range
+    pub bounds: AdaptiveBounds,
+
+    /// Average relative bar range in decibps (`None` until a bar completes)
+    ewma_decibps: Option<f64>,
+}
+
+impl EwmaRangePolicy {
+    /// Checkpoint identifier
+    pub const NAME: &'static str = "ewma_range";
+
+    /// Create policy with the given smoothing factor and range multiplier
+    pub fn new(alpha: f64, multiplier: f64, bounds: AdaptiveBounds) -> Self {
+        Self {
+            alpha: alpha.clamp(f64::MIN_POSITIVE, 1.0),
+            multiplier,
+            bounds,
+            ewma_decibps: None,
+        }
+    }
+}
+
+impl ThresholdPolicy for EwmaRangePolicy {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn next_range(&mut self, _opening_trade: &dyn Trade) -> BarRange {
+        let threshold = match self.ewma_decibps {
+            Some(ewma) => self.bounds.clamp(ewma * self.multiplier),
+            None => self.bounds.initial,
+        };
+        symmetric(threshold)
+    }
+
+    fn on_bar_closed(&mut self, bar: &RangeBar) {
+        let range = bar_range_decibps(bar);
+        self.ewma_decibps = Some(match self.ewma_decibps {
+            Some(ewma) => ewma + self.alpha * (range - ewma),
+            None => range,
+        });
+    }
+
+    fn checkpoint_state(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("policy serializes to JSON")
+    }
+
+    fn restore_state(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+/// Threshold follows rolling realized volatility of completed bar closes
+///
+/// Realized volatility is the root mean square of the last `window`
+/// close-to-close log returns; `threshold = multiplier * volatility`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealizedVolatilityPolicy {
+    /// Number of close-to-close returns in the rolling window
+    pub window: usize,
+
+    /// Scale applied to the realized volatility
+    pub multiplier: f64,
+
+    /// Initial threshold and clamp range
+    pub bounds: AdaptiveBounds,
+
+    /// Close of the most recent completed bar
+    last_close: Option<i64>,
+
+    /// Recent log returns, oldest first
+    returns: VecDeque<f64>,
+}
+
+impl RealizedVolatilityPolicy {
+    /// Checkpoint identifier
+    pub const NAME: &'static str = "realized_volatility";
+
+    /// Create policy over a rolling window of `window` bar returns
+    pub fn new(window: usize, multiplier: f64, bounds: AdaptiveBounds) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            multiplier,
+            bounds,
+            last_close: None,
+            returns: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Realized volatility over the current window (`None` until the window is full)
+    pub fn realized_volatility(&self) -> Option<f64> {
+        if self.returns.len() < self.window {
+            return None;
+        }
+        let sum_sq: f64 = self.returns.iter().map(|r| r * r).sum();
+        Some((sum_sq / self.returns.len() as f64).sqrt())
+    }
+}
+
+impl ThresholdPolicy for RealizedVolatilityPolicy {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn next_range(&mut self, _opening_trade: &dyn Trade) -> BarRange {
+        let threshold = match self.realized_volatility() {
+            Some(volatility) => self
+                .bounds
+                .clamp(volatility * self.multiplier * THRESHOLD_SCALE as f64),
+            None => self.bounds.initial,
+        };
+        symmetric(threshold)
+    }
+
+    fn on_bar_closed(&mut self, bar: &RangeBar) {
+        if let Some(prev) = self.last_close {
+            if self.returns.len() == self.window {
+                self.returns.pop_front();
+            }
+            self.returns
+                .push_back((bar.close.0 as f64 / prev as f64).ln());
+        }
+        self.last_close = Some(bar.close.0);
+    }
+
+    fn checkpoint_state(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("policy serializes to JSON")
+    }
+
+    fn restore_state(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+/// Feedback controller steering the bar rate towards a target
+///
+/// Every `window` completed bars the observed rate is compared with the
+/// target and the threshold is scaled by `(observed / target)^gain`: too many
+/// bars widen the threshold, too few narrow it. Each step is limited to a
+/// factor of two in either direction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetBarsPerHourPolicy {
+    /// Desired number of bars per hour
+    pub target_bars_per_hour: f64,
+
+    /// Completed bars per adjustment step
+    pub window: usize,
+
+    /// Controller exponent; `1.0` corrects fully in one step
+    pub gain: f64,
+
+    /// Initial threshold and clamp range
+    pub bounds: AdaptiveBounds,
+
+    /// Threshold for the next bar
+    current: Threshold,
+
+    /// Open time of the first bar in the current window
+    window_start: Option<i64>,
+
+    /// Bars completed in the current window
+    window_bars: usize,
+}
+
+impl TargetBarsPerHourPolicy {
+    /// Checkpoint identifier
+    pub const NAME: &'static str = "target_bars_per_hour";
+
+    /// Create controller adjusting every `window` bars with a gain of `0.5`
+    pub fn new(target_bars_per_hour: f64, window: usize, bounds: AdaptiveBounds) -> Self {
+        Self {
+            target_bars_per_hour,
+            window: window.max(1),
+            gain: 0.5,
+            bounds,
+            current: bounds.initial,
+            window_start: None,
+            window_bars: 0,
+        }
+    }
+
+    /// Threshold the next bar will be opened with
+    pub fn current_threshold(&self) -> Threshold {
+        self.current
+    }
+}
+
+impl ThresholdPolicy for TargetBarsPerHourPolicy {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn next_range(&mut self, _opening_trade: &dyn Trade) -> BarRange {
+        symmetric(self.current)
+    }
+
+    fn on_bar_closed(&mut self, bar: &RangeBar) {
+        let start = *self.window_start.get_or_insert(bar.open_time);
+        self.window_bars += 1;
+        if self.window_bars < self.window {
+            return;
+        }
+
+        // Zero-length windows count as one millisecond (a burst far above target)
+        let hours = (bar.close_time - start).max(1) as f64 / MS_PER_HOUR;
+        let observed = self.window_bars as f64 / hours;
+        let factor = (observed / self.target_bars_per_hour)
+            .powf(self.gain)
+            .clamp(0.5, 2.0);
+        self.current = self.bounds.clamp(self.current.decibps() as f64 * factor);
+
+        self.window_start = None;
+        self.window_bars = 0;
+    }
+
+    fn checkpoint_state(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("policy serializes to JSON")
+    }
+
+    fn restore_state(&mut self, state: serde_json::Value) -> Result<(), serde_json::Error> {
+        *self = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_point::FixedPoint;
+    use crate::types::AggTrade;
+
+    fn trade(id: i64, price: &str, timestamp: i64) -> AggTrade {
+        AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint::from_str(price).unwrap(),
+            volume: FixedPoint::from_str("1.0").unwrap(),
+            first_trade_id: id,
+            last_trade_id: id,
+            timestamp,
+            is_buyer_maker: false,
+        }
+    }
+
+    fn bar(
+        open: &str,
+        high: &str,
+        low: &str,
+        close: &str,
+        open_time: i64,
+        close_time: i64,
+    ) -> RangeBar {
+        let mut bar = RangeBar::new(&trade(1, open, open_time));
+        bar.high = FixedPoint::from_str(high).unwrap();
+        bar.low = FixedPoint::from_str(low).unwrap();
+        bar.close = FixedPoint::from_str(close).unwrap();
+        bar.close_time = close_time;
+        bar
+    }
+
+    fn relative(range: BarRange) -> Threshold {
+        match range {
+            BarRange::Relative(thresholds) => {
+                assert!(thresholds.is_symmetric());
+                thresholds.up
+            }
+            BarRange::Absolute { .. } => panic!("adaptive policies are relative"),
+        }
+    }
+
+    #[test]
+    fn test_fixed_threshold() {
+        let mut policy = FixedThreshold::from(25);
+        assert_eq!(policy.next_range(&trade(1, "100.0", 0)), BarRange::from(25));
+        policy.on_bar_closed(&bar("100.0", "100.3", "100.0", "100.3", 0, 1));
+        assert_eq!(policy.next_range(&trade(2, "200.0", 2)), BarRange::from(25));
+    }
+
+    #[test]
+    fn test_ewma_range_policy() {
+        let mut policy = EwmaRangePolicy::new(0.5, 1.0, AdaptiveBounds::default());
+        let opening = trade(1, "100.0", 0);
+        assert_eq!(
+            relative(policy.next_range(&opening)),
+            Threshold::from_bps(25)
+        );
+
+        // 40 bps range seeds the average, 20 bps pulls it halfway down
+        policy.on_bar_closed(&bar("100.0", "100.4", "100.0", "100.4", 0, 1));
+        assert_eq!(
+            relative(policy.next_range(&opening)),
+            Threshold::from_bps(40)
+        );
+        policy.on_bar_closed(&bar("100.0", "100.2", "100.0", "100.2", 1, 2));
+        assert_eq!(
+            relative(policy.next_range(&opening)),
+            Threshold::from_bps(30)
+        );
+
+        // Clamped to the bounds
+        policy.on_bar_closed(&bar("100.0", "200.0", "100.0", "200.0", 2, 3));
+        assert_eq!(
+            relative(policy.next_range(&opening)),
+            Threshold::from_bps(1000)
+        );
+    }
+
+    #[test]
+    fn test_realized_volatility_policy() {
+        let mut policy = RealizedVolatilityPolicy::new(2, 1.0, AdaptiveBounds::default());
+        let opening = trade(1, "100.0", 0);
+
+        // Two returns are needed before the initial threshold is replaced
+        policy.on_bar_closed(&bar("100.0", "100.0", "100.0", "100.0", 0, 1));
+        policy.on_bar_closed(&bar("100.0", "101.0", "100.0", "101.0", 1, 2));
+        assert_eq!(
+            relative(policy.next_range(&opening)),
+            Threshold::from_bps(25)
+        );
+        policy.on_bar_closed(&bar("101.0", "101.0", "100.0", "100.0", 2, 3));
+
+        // |ln(1.01)| ≈ |ln(100/101)| ≈ 99.5 bps
+        let threshold = relative(policy.next_range(&opening));
+        assert!((990..=1000).contains(&threshold.decibps()), "{}", threshold);
+        assert!(policy.realized_volatility().unwrap() > 0.0099);
+    }
+
+    #[test]
+    fn test_target_bars_per_hour_policy() {
+        let bounds = AdaptiveBounds::default();
+        let mut policy = TargetBarsPerHourPolicy::new(10.0, 2, bounds);
+        policy.gain = 1.0;
+
+        // 2 bars in 3 minutes = 40 bars/hour, 4x the target: widen (capped at 2x)
+        policy.on_bar_closed(&bar("100.0", "100.3", "100.0", "100.3", 0, 60_000));
+        assert_eq!(policy.current_threshold(), Threshold::from_bps(25));
+        policy.on_bar_closed(&bar("100.3", "100.3", "100.0", "100.0", 60_000, 180_000));
+        assert_eq!(policy.current_threshold(), Threshold::from_bps(50));
+
+        // 2 bars in 24 minutes = 5 bars/hour: narrow by half
+        policy.on_bar_closed(&bar("100.0", "100.5", "100.0", "100.5", 180_000, 900_000));
+        policy.on_bar_closed(&bar("100.5", "100.5", "100.0", "100.0", 900_000, 1_620_000));
+        assert_eq!(policy.current_threshold(), Threshold::from_bps(25));
+        assert_eq!(
+            relative(policy.next_range(&trade(1, "100.0", 1_620_001))),
+            Threshold::from_bps(25)
+        );
+    }
+
+    #[test]
+    fn test_policy_state_round_trip() {
+        let mut policy = RealizedVolatilityPolicy::new(3, 2.0, AdaptiveBounds::default());
+        for (i, close) in ["100.0", "100.5", "100.1", "99.8"].iter().enumerate() {
+            policy.on_bar_closed(&bar(
+                "100.0",
+                "100.5",
+                "99.8",
+                close,
+                i as i64,
+                i as i64 + 1,
+            ));
+        }
+
+        let mut restored = RealizedVolatilityPolicy::new(1, 1.0, AdaptiveBounds::default());
+        restored.restore_state(policy.checkpoint_state()).unwrap();
+        assert_eq!(restored, policy);
+    }
+}
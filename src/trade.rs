@@ -0,0 +1,207 @@
+//! Source-independent trade input
+//!
+//! [`RangeBarProcessor`](crate::range_bars::RangeBarProcessor) accepts any
+//! [`Trade`]: a price, a size, a timestamp and a sequence id, plus the
+//! aggressor side and trade count when the venue reports them. Binance
+//! [`AggTrade`]s are one implementation; [`TradePrint`] covers venues and
+//! execution logs that report individual prints.
+//!
+//! Equivalent data yields identical bars whatever the source: a Binance
+//! aggregate of one trade and a print with the same fields build the same bar.
+
+use crate::fixed_point::FixedPoint;
+use crate::types::AggTrade;
+use serde::{Deserialize, Serialize};
+
+/// Side that took liquidity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    /// Buyer lifted the offer (buy pressure)
+    Buy,
+
+    /// Seller hit the bid (sell pressure)
+    Sell,
+}
+
+/// One trade as seen by the bar engines
+///
+/// Trades must arrive sorted by `(timestamp, sequence_id)`. Bars record the
+/// sequence ids of their first and last trade in `first_id` and `last_id`.
+pub trait Trade {
+    /// Execution price
+    fn price(&self) -> FixedPoint;
+
+    /// Executed quantity in base-asset units
+    fn size(&self) -> FixedPoint;
+
+    /// Timestamp in milliseconds
+    fn timestamp(&self) -> i64;
+
+    /// Venue sequence number, strictly increasing within a timestamp
+    fn sequence_id(&self) -> i64;
+
+    /// Aggressor side, if the venue reports it
+    ///
+    /// Trades without one count towards neither buy nor sell totals.
+    fn aggressor(&self) -> Option<Side>;
+
+    /// Individual trades represented (more than one for aggregates)
+    fn trade_count(&self) -> i64 {
+        1
+    }
+
+    /// Turnover (price * size) as i128 to prevent overflow
+    fn turnover(&self) -> i128 {
+        (self.price().0 as i128) * (self.size().0 as i128)
+    }
+}
+
+impl Trade for AggTrade {
+    fn price(&self) -> FixedPoint {
+        self.price
+    }
+
+    fn size(&self) -> FixedPoint {
+        self.volume
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    fn sequence_id(&self) -> i64 {
+        self.agg_trade_id
+    }
+
+    /// Always known: a buyer-maker trade was sold into
+    fn aggressor(&self) -> Option<Side> {
+        Some(if self.is_buyer_maker {
+            Side::Sell
+        } else {
+            Side::Buy
+        })
+    }
+
+    fn trade_count(&self) -> i64 {
+        AggTrade::trade_count(self)
+    }
+}
+
+/// A single venue-reported trade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct TradePrint {
+    /// Venue sequence number or trade id
+    pub sequence_id: i64,
+
+    /// Price as fixed-point integer
+    pub price: FixedPoint,
+
+    /// Size as fixed-point integer
+    pub size: FixedPoint,
+
+    /// Timestamp in milliseconds
+    pub timestamp: i64,
+
+    /// Aggressor side, if reported
+    pub aggressor: Option<Side>,
+}
+
+impl Trade for TradePrint {
+    fn price(&self) -> FixedPoint {
+        self.price
+    }
+
+    fn size(&self) -> FixedPoint {
+        self.size
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    fn sequence_id(&self) -> i64 {
+        self.sequence_id
+    }
+
+    fn aggressor(&self) -> Option<Side> {
+        self.aggressor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range_bars::RangeBarProcessor;
+
+    fn create_test_print(id: i64, price: &str, aggressor: Option<Side>) -> TradePrint {
+        TradePrint {
+            sequence_id: id,
+            price: FixedPoint::from_str(price).unwrap(),
+            size: FixedPoint::from_str("0.5").unwrap(),
+            timestamp: 1_000 + id,
+            aggressor,
+        }
+    }
+
+    fn as_agg_trade(print: &TradePrint) -> AggTrade {
+        AggTrade {
+            agg_trade_id: print.sequence_id,
+            price: print.price,
+            volume: print.size,
+            first_trade_id: print.sequence_id,
+            last_trade_id: print.sequence_id,
+            timestamp: print.timestamp,
+            is_buyer_maker: print.aggressor == Some(Side::Sell),
+        }
+    }
+
+    #[test]
+    fn test_prints_and_agg_trades_build_identical_bars() {
+        let prices = ["100.0", "100.1", "99.9", "100.3", "100.2", "99.6", "99.8"];
+        let prints: Vec<_> = prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                let side = if i % 3 == 0 { Side::Sell } else { Side::Buy };
+                create_test_print(i as i64, price, Some(side))
+            })
+            .collect();
+        let agg_trades: Vec<_> = prints.iter().map(as_agg_trade).collect();
+
+        let mut from_prints = RangeBarProcessor::new(25);
+        let mut from_agg_trades = RangeBarProcessor::new(25);
+        let bars = from_prints.process_trades(&prints).unwrap();
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars, from_agg_trades.process_trades(&agg_trades).unwrap());
+        assert_eq!(
+            from_prints.finish(),
+            from_agg_trades.finish(),
+            "open bars must match too"
+        );
+    }
+
+    #[test]
+    fn test_unknown_aggressor_counts_towards_neither_side() {
+        let prints = [
+            create_test_print(1, "100.0", Some(Side::Buy)),
+            create_test_print(2, "100.0", None),
+            create_test_print(3, "100.0", Some(Side::Sell)),
+        ];
+        let mut processor = RangeBarProcessor::new(25);
+        processor.process_trades(&prints).unwrap();
+        let bar = processor.finish().unwrap();
+
+        assert_eq!(bar.trade_count, 3);
+        assert_eq!((bar.buy_trade_count, bar.sell_trade_count), (1, 1));
+        assert_eq!(bar.volume, FixedPoint::from_str("1.5").unwrap());
+        assert_eq!(
+            bar.buy_volume + bar.sell_volume,
+            FixedPoint::from_str("1.0").unwrap()
+        );
+        assert_eq!((bar.first_id, bar.last_id), (1, 3));
+    }
+}
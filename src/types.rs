@@ -1,10 +1,37 @@
 //! Type definitions for range bar processing
 
-use crate::fixed_point::FixedPoint;
+use crate::fixed_point::{BASIS_POINTS_SCALE, FixedPoint, FixedPointError, Rounding, Scale};
+use crate::trade::{Side, Trade};
 use serde::{Deserialize, Serialize};
 
+/// Decimal scales of one instrument's prices and volumes
+///
+/// Trades and bars hold raw integers; this records how to read them. Turnover
+/// carries the sum of both scales. Binance data uses 8 decimals for both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct TradeScale {
+    /// Decimals of prices (and of everything measured in price units)
+    pub price: Scale,
+
+    /// Decimals of base-asset volumes
+    pub volume: Scale,
+}
+
+impl TradeScale {
+    /// Scales for prices and volumes
+    pub const fn new(price: Scale, volume: Scale) -> Self {
+        Self { price, volume }
+    }
+
+    /// Raw turnover units per whole quote unit: `10^(price + volume decimals)`
+    pub fn turnover_factor(&self) -> i128 {
+        self.price.factor() as i128 * self.volume.factor() as i128
+    }
+}
+
 /// Aggregate trade data from Binance UM Futures
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 pub struct AggTrade {
     /// Aggregate trade ID
@@ -40,10 +67,73 @@ impl AggTrade {
     pub fn turnover(&self) -> i128 {
         (self.price.0 as i128) * (self.volume.0 as i128)
     }
+
+    /// Same trade with price and volume converted from `from` to `to` losslessly
+    pub fn rescale(&self, from: TradeScale, to: TradeScale) -> Result<AggTrade, FixedPointError> {
+        Ok(AggTrade {
+            price: self.price.rescale(from.price, to.price)?,
+            volume: self.volume.rescale(from.volume, to.volume)?,
+            ..self.clone()
+        })
+    }
+}
+
+/// Convert raw turnover of `from` into raw turnover of `to` losslessly
+fn rescale_turnover(
+    turnover: i128,
+    from: TradeScale,
+    to: TradeScale,
+) -> Result<i128, FixedPointError> {
+    let (from, to) = (from.turnover_factor(), to.turnover_factor());
+    if to >= from {
+        turnover
+            .checked_mul(to / from)
+            .ok_or(FixedPointError::Overflow)
+    } else if turnover % (from / to) == 0 {
+        Ok(turnover / (from / to))
+    } else {
+        Err(FixedPointError::PrecisionLoss)
+    }
+}
+
+/// Direction of the threshold breach that closed a range bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BreachDirection {
+    /// Price reached the upper threshold
+    Up,
+
+    /// Price reached the lower threshold
+    Down,
+}
+
+/// Why a range bar was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CloseReason {
+    /// Price reached the upper or lower threshold
+    Breach,
+
+    /// Input ended while the bar was open and it was closed by force
+    EndOfData,
+
+    /// The bar was cut at a trading session boundary
+    SessionCut,
+
+    /// The bar was cut after reaching its maximum duration
+    MaxDuration,
+
+    /// The bar was cut after reaching its maximum trade count
+    MaxTrades,
+
+    /// Tick, volume or turnover target of an event-driven bar was reached
+    TargetReached,
 }
 
 /// Range bar with OHLCV data and market microstructure enhancements
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 pub struct RangeBar {
     /// Opening timestamp (first trade)
@@ -81,7 +171,8 @@ pub struct RangeBar {
 
     // === MARKET MICROSTRUCTURE ENHANCEMENTS ===
     /// Volume from buy-side trades (is_buyer_maker = false)
-    /// Represents aggressive buying pressure
+    /// Represents aggressive buying pressure; trades without a known
+    /// aggressor count towards neither side
     pub buy_volume: FixedPoint,
 
     /// Volume from sell-side trades (is_buyer_maker = true)
@@ -103,105 +194,217 @@ pub struct RangeBar {
 
     /// Turnover from sell-side trades (sell pressure)
     pub sell_turnover: i128,
+
+    /// Direction of the breach that closed the bar (`None` while the bar is open)
+    #[serde(default)]
+    pub close_direction: Option<BreachDirection>,
+
+    /// Upper breach level chosen when the bar opened (`None` if not threshold-driven)
+    #[serde(default)]
+    pub upper_threshold: Option<FixedPoint>,
+
+    /// Lower breach level chosen when the bar opened (`None` if not threshold-driven)
+    #[serde(default)]
+    pub lower_threshold: Option<FixedPoint>,
+
+    /// Why the bar closed (`None` while the bar is open)
+    #[serde(default)]
+    pub close_reason: Option<CloseReason>,
+
+    /// Distance the closing price went past the breached threshold (breaches only)
+    #[serde(default)]
+    pub overshoot: Option<FixedPoint>,
+
+    /// [`overshoot`](Self::overshoot) in basis points of the open price
+    /// (`None` when the bar opened at zero)
+    #[serde(default)]
+    pub overshoot_bps: Option<f64>,
+
+    /// `agg_trade_id`s missing inside `first_id..=last_id` (`None` if not audited)
+    #[serde(default)]
+    pub missing_agg_trade_ids: Option<i64>,
 }
 
 impl RangeBar {
     /// Create new range bar from opening trade
-    pub fn new(trade: &AggTrade) -> Self {
+    pub fn new<T: Trade>(trade: &T) -> Self {
+        let price = trade.price();
         let trade_turnover = trade.turnover();
         let trade_count = trade.trade_count();
 
-        // Segregate order flow based on is_buyer_maker
-        let (buy_volume, sell_volume) = if trade.is_buyer_maker {
-            (FixedPoint(0), trade.volume) // Seller aggressive = sell pressure
-        } else {
-            (trade.volume, FixedPoint(0)) // Buyer aggressive = buy pressure
-        };
-
-        let (buy_trade_count, sell_trade_count) = if trade.is_buyer_maker {
-            (0, trade_count)
-        } else {
-            (trade_count, 0)
-        };
-
-        let (buy_turnover, sell_turnover) = if trade.is_buyer_maker {
-            (0, trade_turnover)
-        } else {
-            (trade_turnover, 0)
+        // Segregate order flow by aggressor side
+        let flow = (trade.size(), trade_count, trade_turnover);
+        let none = (FixedPoint(0), 0, 0);
+        let (
+            (buy_volume, buy_trade_count, buy_turnover),
+            (sell_volume, sell_trade_count, sell_turnover),
+        ) = match trade.aggressor() {
+            Some(Side::Buy) => (flow, none),  // Buyer aggressive = buy pressure
+            Some(Side::Sell) => (none, flow), // Seller aggressive = sell pressure
+            None => (none, none),
         };
 
         Self {
-            open_time: trade.timestamp,
-            close_time: trade.timestamp,
-            open: trade.price,
-            high: trade.price,
-            low: trade.price,
-            close: trade.price,
-            volume: trade.volume,
+            open_time: trade.timestamp(),
+            close_time: trade.timestamp(),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: trade.size(),
             turnover: trade_turnover,
             trade_count,
-            first_id: trade.agg_trade_id,
-            last_id: trade.agg_trade_id,
+            first_id: trade.sequence_id(),
+            last_id: trade.sequence_id(),
             // Market microstructure fields
             buy_volume,
             sell_volume,
             buy_trade_count,
             sell_trade_count,
-            vwap: trade.price, // Initial VWAP equals opening price
+            vwap: price, // Initial VWAP equals opening price
             buy_turnover,
             sell_turnover,
+            close_direction: None,
+            upper_threshold: None,
+            lower_threshold: None,
+            close_reason: None,
+            overshoot: None,
+            overshoot_bps: None,
+            missing_agg_trade_ids: None,
         }
     }
 
+    /// Same bar with every price, volume and turnover converted from `from` to `to`
+    ///
+    /// Lossless: fails with [`FixedPointError::PrecisionLoss`] rather than
+    /// rounding. VWAP is rounded when built, so it is rescaled with
+    /// [`Rounding::HalfEven`] when `to` is coarser.
+    pub fn rescale(&self, from: TradeScale, to: TradeScale) -> Result<RangeBar, FixedPointError> {
+        let price = |value: FixedPoint| value.rescale(from.price, to.price);
+        let volume = |value: FixedPoint| value.rescale(from.volume, to.volume);
+        let turnover = |value: i128| rescale_turnover(value, from, to);
+
+        Ok(RangeBar {
+            open: price(self.open)?,
+            high: price(self.high)?,
+            low: price(self.low)?,
+            close: price(self.close)?,
+            volume: volume(self.volume)?,
+            turnover: turnover(self.turnover)?,
+            buy_volume: volume(self.buy_volume)?,
+            sell_volume: volume(self.sell_volume)?,
+            vwap: self
+                .vwap
+                .rescale_rounded(from.price, to.price, Rounding::HalfEven)
+                .ok_or(FixedPointError::Overflow)?,
+            buy_turnover: turnover(self.buy_turnover)?,
+            sell_turnover: turnover(self.sell_turnover)?,
+            upper_threshold: self.upper_threshold.map(price).transpose()?,
+            lower_threshold: self.lower_threshold.map(price).transpose()?,
+            overshoot: self.overshoot.map(price).transpose()?,
+            ..self.clone()
+        })
+    }
+
+    /// Mark the bar closed by a breach of `level` in `direction`
+    ///
+    /// Call after the breaching trade has been applied with
+    /// [`update_with_trade`](Self::update_with_trade).
+    pub fn record_breach(&mut self, direction: BreachDirection, level: FixedPoint) {
+        let overshoot = match direction {
+            BreachDirection::Up => self.close - level,
+            BreachDirection::Down => level - self.close,
+        };
+        self.close_direction = Some(direction);
+        self.close_reason = Some(CloseReason::Breach);
+        self.overshoot = Some(overshoot);
+        self.overshoot_bps = (self.open.0 != 0)
+            .then(|| overshoot.0 as f64 / self.open.0 as f64 * BASIS_POINTS_SCALE as f64);
+    }
+
+    /// Mark the bar closed without a breach (no direction or overshoot)
+    pub fn force_close(&mut self, reason: CloseReason) {
+        self.close_reason = Some(reason);
+    }
+
     /// Update bar with new trade data (always call before checking breach)
     /// Maintains market microstructure metrics incrementally
-    pub fn update_with_trade(&mut self, trade: &AggTrade) {
+    ///
+    /// Fails with [`FixedPointError::Overflow`] if a running total overflows,
+    /// leaving the bar unchanged.
+    pub fn update_with_trade<T: Trade>(&mut self, trade: &T) -> Result<(), FixedPointError> {
+        // Cache trade metrics for efficiency
+        let price = trade.price();
+        let trade_turnover = trade.turnover();
+        let trade_count = trade.trade_count();
+        let aggressor = trade.aggressor();
+
+        // Checked totals first, so an overflow leaves the bar untouched
+        let volume = self
+            .volume
+            .checked_add(trade.size())
+            .ok_or(FixedPointError::Overflow)?;
+        let turnover = self
+            .turnover
+            .checked_add(trade_turnover)
+            .ok_or(FixedPointError::Overflow)?;
+        let side_volume = match aggressor {
+            Some(Side::Buy) => self.buy_volume,
+            Some(Side::Sell) => self.sell_volume,
+            None => FixedPoint(0),
+        }
+        .checked_add(trade.size())
+        .ok_or(FixedPointError::Overflow)?;
+
+        // VWAP = total_turnover / total_volume
+        // turnover is in (price * volume) units, volume is in volume units,
+        // so the quotient is in price units; it lies within [low, high]
+        let vwap = if volume.0 > 0 {
+            FixedPoint::checked_ratio(turnover, volume.0 as i128, Rounding::Floor)
+                .ok_or(FixedPointError::Overflow)?
+        } else {
+            self.vwap
+        };
+
         // Update price extremes
-        if trade.price > self.high {
-            self.high = trade.price;
+        if price > self.high {
+            self.high = price;
         }
-        if trade.price < self.low {
-            self.low = trade.price;
+        if price < self.low {
+            self.low = price;
         }
 
         // Update closing data
-        self.close = trade.price;
-        self.close_time = trade.timestamp;
-        self.last_id = trade.agg_trade_id;
-
-        // Cache trade metrics for efficiency
-        let trade_turnover = trade.turnover();
-        let trade_count = trade.trade_count();
+        self.close = price;
+        self.close_time = trade.timestamp();
+        self.last_id = trade.sequence_id();
 
         // Update total volume and trade count
-        self.volume = FixedPoint(self.volume.0 + trade.volume.0);
-        self.turnover += trade_turnover;
+        self.volume = volume;
+        self.turnover = turnover;
         self.trade_count += trade_count;
 
         // === MARKET MICROSTRUCTURE INCREMENTAL UPDATES ===
 
         // Update order flow segregation
-        if trade.is_buyer_maker {
-            // Seller aggressive = sell pressure
-            self.sell_volume = FixedPoint(self.sell_volume.0 + trade.volume.0);
-            self.sell_trade_count += trade_count;
-            self.sell_turnover += trade_turnover;
-        } else {
-            // Buyer aggressive = buy pressure
-            self.buy_volume = FixedPoint(self.buy_volume.0 + trade.volume.0);
-            self.buy_trade_count += trade_count;
-            self.buy_turnover += trade_turnover;
+        match aggressor {
+            Some(Side::Buy) => {
+                // Buyer aggressive = buy pressure
+                self.buy_volume = side_volume;
+                self.buy_trade_count += trade_count;
+                self.buy_turnover += trade_turnover;
+            }
+            Some(Side::Sell) => {
+                // Seller aggressive = sell pressure
+                self.sell_volume = side_volume;
+                self.sell_trade_count += trade_count;
+                self.sell_turnover += trade_turnover;
+            }
+            None => {}
         }
 
-        // Update VWAP incrementally: VWAP = total_turnover / total_volume
-        // Using integer arithmetic to maintain precision
-        if self.volume.0 > 0 {
-            // Calculate VWAP: turnover / volume, but maintain FixedPoint precision
-            // turnover is in (price * volume) units, volume is in volume units
-            // VWAP should be in price units
-            let vwap_raw = self.turnover / (self.volume.0 as i128);
-            self.vwap = FixedPoint(vwap_raw as i64);
-        }
+        self.vwap = vwap;
+        Ok(())
     }
 
     /// Check if price breaches the range thresholds
@@ -221,7 +424,24 @@ impl RangeBar {
         upper_threshold: FixedPoint,
         lower_threshold: FixedPoint,
     ) -> bool {
-        price >= upper_threshold || price <= lower_threshold
+        self.breach_direction(price, upper_threshold, lower_threshold)
+            .is_some()
+    }
+
+    /// Direction in which price breaches the range thresholds, if any
+    pub fn breach_direction(
+        &self,
+        price: FixedPoint,
+        upper_threshold: FixedPoint,
+        lower_threshold: FixedPoint,
+    ) -> Option<BreachDirection> {
+        if price >= upper_threshold {
+            Some(BreachDirection::Up)
+        } else if price <= lower_threshold {
+            Some(BreachDirection::Down)
+        } else {
+            None
+        }
     }
 }
 
@@ -289,7 +509,7 @@ mod tests {
             is_buyer_maker: true, // Sell pressure
         };
 
-        bar.update_with_trade(&trade2);
+        bar.update_with_trade(&trade2).unwrap();
 
         assert_eq!(bar.open.to_string(), "50000.00000000");
         assert_eq!(bar.high.to_string(), "50100.00000000");
@@ -299,6 +519,67 @@ mod tests {
         assert_eq!(bar.trade_count, 2);
     }
 
+    #[test]
+    fn test_overshoot_bps_at_zero_open() {
+        let trade = AggTrade {
+            agg_trade_id: 12345,
+            price: FixedPoint(0),
+            volume: FixedPoint::from_str("1.0").unwrap(),
+            first_trade_id: 100,
+            last_trade_id: 100,
+            timestamp: 1640995200000,
+            is_buyer_maker: false,
+        };
+
+        let mut bar = RangeBar::new(&trade);
+        bar.update_with_trade(&AggTrade {
+            agg_trade_id: 12346,
+            price: FixedPoint::from_str("0.5").unwrap(),
+            ..trade
+        })
+        .unwrap();
+        bar.record_breach(BreachDirection::Up, FixedPoint::from_str("0.25").unwrap());
+
+        assert_eq!(bar.overshoot, Some(FixedPoint::from_str("0.25").unwrap()));
+        assert_eq!(bar.overshoot_bps, None);
+    }
+
+    #[test]
+    fn test_rescale_round_trip() {
+        let coarse = TradeScale::default();
+        let fine = TradeScale::new(Scale::new(12).unwrap(), Scale::new(10).unwrap());
+        let trade = AggTrade {
+            agg_trade_id: 1,
+            price: FixedPoint::from_str("50000.5").unwrap(),
+            volume: FixedPoint::from_str("0.25").unwrap(),
+            first_trade_id: 1,
+            last_trade_id: 1,
+            timestamp: 1640995200000,
+            is_buyer_maker: false,
+        };
+
+        let fine_trade = trade.rescale(coarse, fine).unwrap();
+        assert_eq!(
+            fine_trade.price.to_string_scaled(fine.price),
+            "50000.500000000000"
+        );
+        assert_eq!(fine_trade.rescale(fine, coarse).unwrap(), trade);
+
+        // Bars built at either scale describe the same values
+        let bar = RangeBar::new(&trade);
+        let fine_bar = RangeBar::new(&fine_trade);
+        assert_eq!(bar.rescale(coarse, fine).unwrap(), fine_bar);
+        assert_eq!(fine_bar.rescale(fine, coarse).unwrap(), bar);
+        assert_eq!(fine_bar.turnover, bar.turnover * 1_000_000);
+
+        let mut precise = fine_trade.clone();
+        precise.price.0 += 1;
+        assert_eq!(
+            precise.rescale(fine, coarse),
+            Err(FixedPointError::PrecisionLoss)
+        );
+    }
+
     #[test]
     fn test_microstructure_segregation() {
         // Create buy trade (is_buyer_maker = false)
@@ -325,7 +606,7 @@ mod tests {
             is_buyer_maker: true, // Sell pressure (taker selling to maker)
         };
 
-        bar.update_with_trade(&sell_trade);
+        bar.update_with_trade(&sell_trade).unwrap();
 
         // Verify order flow segregation
         assert_eq!(bar.buy_volume.to_string(), "1.50000000"); // Only first trade
@@ -0,0 +1,128 @@
+//! Aggregate-trade-id continuity audit tests
+//!
+//! The audit must survive being split across export checkpoints, and bars
+//! whose id range crosses a gap must be the only ones annotated as missing ids.
+
+use rangebar::audit::{AuditFinding, IdAuditor};
+use rangebar::fixed_point::FixedPoint;
+use rangebar::ladder::LadderProcessor;
+use rangebar::types::AggTrade;
+
+/// 2024-01-01 23:59:00 UTC
+const BASE_TIMESTAMP: i64 = 1_704_153_540_000;
+
+fn create_test_trade(id: i64, price: &str, timestamp: i64) -> AggTrade {
+    AggTrade {
+        agg_trade_id: id,
+        price: FixedPoint::from_str(price).unwrap(),
+        volume: FixedPoint::from_str("1.0").unwrap(),
+        first_trade_id: id * 10,
+        last_trade_id: id * 10 + 9,
+        timestamp,
+        is_buyer_maker: false,
+    }
+}
+
+/// Trades crossing midnight with agg ids 6..=9 missing on the second day
+fn trades_with_gap() -> Vec<AggTrade> {
+    let prices = [
+        (1, "100.0"),
+        (2, "100.1"),
+        (3, "101.0"), // Breaches 25 bps
+        (4, "101.1"),
+        (5, "101.2"),
+        (10, "101.3"),
+        (11, "102.0"), // Breaches 25 bps
+        (12, "102.1"),
+    ];
+    prices
+        .iter()
+        .map(|&(id, price)| create_test_trade(id, price, BASE_TIMESTAMP + id * 10_000))
+        .collect()
+}
+
+#[test]
+fn test_audit_resumes_from_checkpoint() {
+    let trades = trades_with_gap();
+
+    let mut whole = IdAuditor::new("BTCUSDT");
+    whole.observe_all(&trades);
+
+    let mut first = IdAuditor::new("BTCUSDT");
+    first.observe_all(&trades[..5]);
+    let json = serde_json::to_string(&first).unwrap();
+    let mut resumed: IdAuditor = serde_json::from_str(&json).unwrap();
+    resumed.observe_all(&trades[5..]);
+
+    assert_eq!(resumed.report(), whole.report());
+    let report = whole.report();
+    assert_eq!(report.days.len(), 2);
+    assert_eq!(report.missing_agg_trade_ids(), 4);
+
+    let (_, second_day) = report.days.iter().nth(1).unwrap();
+    assert!(matches!(
+        second_day.findings[..],
+        [AuditFinding::AggIdGap {
+            after: 5,
+            before: 10,
+            missing: 4,
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn test_annotate_marks_only_gap_crossing_bars() {
+    let trades = trades_with_gap();
+    let mut auditor = IdAuditor::new("BTCUSDT");
+    auditor.observe_all(&trades);
+
+    let mut ladder = LadderProcessor::new([25u32]).unwrap();
+    let mut bars = ladder.process_trades(&trades).unwrap().remove(0);
+    bars.extend(ladder.finish().remove(0));
+    for bar in &mut bars {
+        auditor.annotate(bar);
+    }
+
+    let annotated: Vec<_> = bars
+        .iter()
+        .map(|bar| (bar.first_id, bar.last_id, bar.missing_agg_trade_ids))
+        .collect();
+    assert_eq!(
+        annotated,
+        vec![(1, 3, Some(0)), (4, 11, Some(4)), (12, 12, Some(0))]
+    );
+}
+
+#[test]
+fn test_dedup_lets_a_repeating_feed_through() {
+    let clean = trades_with_gap();
+    let mut conflicting = clean[3].clone();
+    conflicting.price = FixedPoint::from_str("99.0").unwrap();
+    let mut feed = clean.clone();
+    feed.insert(2, clean[1].clone()); // Exact duplicate
+    feed.insert(5, conflicting); // Same id and time, different price
+
+    // The raw feed is rejected by the engine
+    assert!(
+        LadderProcessor::new([25u32])
+            .unwrap()
+            .process_trades(&feed)
+            .is_err()
+    );
+
+    let mut auditor = IdAuditor::new("BTCUSDT");
+    auditor.observe_and_dedup(&mut feed);
+    assert_eq!(feed, clean);
+
+    let day = auditor.report().days.values().next().unwrap();
+    assert_eq!((day.exact_duplicates, day.conflicting_duplicates), (1, 1));
+    assert_eq!(auditor.report().missing_agg_trade_ids(), 4);
+
+    let mut ladder = LadderProcessor::new([25u32]).unwrap();
+    let mut expected = LadderProcessor::new([25u32]).unwrap();
+    assert_eq!(
+        ladder.process_trades(&feed).unwrap(),
+        expected.process_trades(&clean).unwrap()
+    );
+}
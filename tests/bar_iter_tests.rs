@@ -0,0 +1,76 @@
+//! Lazy iterator adapter against the batch engine
+
+mod common;
+
+use common::synthetic_trade;
+use rangebar::{AggTrade, RangeBar, RangeBarIteratorExt, RangeBarProcessor, TradeSourceError};
+
+#[test]
+fn test_lazy_matches_chunked_batch_on_long_stream() {
+    const TRADES: i64 = 1_000_000;
+
+    // Never materialized: trades are generated as the adapter pulls them
+    let mut lazy_count = 0usize;
+    let mut lazy_last: Option<RangeBar> = None;
+    for bar in (0..TRADES).map(synthetic_trade).range_bars(25) {
+        lazy_count += 1;
+        lazy_last = Some(bar.unwrap());
+    }
+
+    let mut processor = RangeBarProcessor::new(25);
+    let mut batch_count = 0usize;
+    let mut batch_last = None;
+    for start in (0..TRADES).step_by(50_000) {
+        let chunk: Vec<_> = (start..start + 50_000).map(synthetic_trade).collect();
+        let bars = processor.process_trades(&chunk).unwrap();
+        batch_count += bars.len();
+        batch_last = bars.last().cloned().or(batch_last);
+    }
+
+    assert!(lazy_count > 100, "expected many bars, got {lazy_count}");
+    assert_eq!(lazy_count, batch_count);
+    assert_eq!(lazy_last, batch_last);
+}
+
+#[test]
+fn test_composes_with_csv_deserializer() {
+    let mut csv = String::from(
+        "agg_trade_id,price,volume,first_trade_id,last_trade_id,timestamp,is_buyer_maker\n",
+    );
+    for i in 0..2_000 {
+        let trade = synthetic_trade(i);
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            trade.agg_trade_id,
+            trade.price.0,
+            trade.volume.0,
+            trade.first_trade_id,
+            trade.last_trade_id,
+            trade.timestamp,
+            trade.is_buyer_maker
+        ));
+    }
+
+    let expected = RangeBarProcessor::new(25)
+        .process_trades_with_incomplete(&(0..2_000).map(synthetic_trade).collect::<Vec<_>>())
+        .unwrap();
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let bars: Vec<_> = reader
+        .deserialize::<AggTrade>()
+        .range_bars(25)
+        .with_incomplete()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(bars, expected);
+
+    // A malformed row surfaces as a source error, after the bars before it
+    let broken = csv.replacen("\n1000,", "\nnot-a-number,", 1);
+    let mut reader = csv::Reader::from_reader(broken.as_bytes());
+    let results: Vec<_> = reader.deserialize::<AggTrade>().range_bars(25).collect();
+    assert!(matches!(
+        results.last(),
+        Some(Err(TradeSourceError::Source(_)))
+    ));
+    assert!(results[..results.len() - 1].iter().all(Result::is_ok));
+}
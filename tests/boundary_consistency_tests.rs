@@ -298,7 +298,9 @@ fn process_batch_style(trades: &[AggTrade], threshold_bps: u32) -> Vec<RangeBar>
     let mut processor = ExportRangeBarProcessor::new(threshold_bps);
 
     // Process all trades continuously (simulating boundary-safe mode)
-    processor.process_trades_continuously(trades);
+    processor
+        .process_trades_continuously(trades)
+        .expect("trades should be sorted");
 
     // Get all completed bars
     let mut bars = processor.get_all_completed_bars();
@@ -328,7 +330,9 @@ async fn process_streaming_style(trades: &[AggTrade], threshold_bps: u32) -> Vec
     let mut all_bars = Vec::new();
 
     for chunk in trades.chunks(chunk_size) {
-        range_processor.process_trades_continuously(chunk);
+        range_processor
+            .process_trades_continuously(chunk)
+            .expect("trades should be sorted");
         // Get completed bars from this chunk and clear state
         let chunk_bars = range_processor.get_all_completed_bars();
         all_bars.extend(chunk_bars);
@@ -0,0 +1,129 @@
+//! Bar fan-out from `StreamingProcessor` to several subscribers
+
+mod common;
+
+use common::synthetic_trade;
+use futures::StreamExt;
+use rangebar::streaming_processor::{StreamingError, StreamingProcessor, StreamingProcessorConfig};
+use rangebar::{
+    AggTrade, LagPolicy, RangeBar, RangeBarProcessor, RangeBarStream, SubscriptionConfig,
+};
+
+fn expected_bars(trades: &[AggTrade]) -> Vec<RangeBar> {
+    RangeBarProcessor::new(25)
+        .process_trades_with_incomplete(trades)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_strategy_writer_and_dashboard_subscribers() {
+    let trades: Vec<_> = (0..5_000).map(synthetic_trade).collect();
+    let expected = expected_bars(&trades);
+    assert!(expected.len() > 50);
+
+    let config = StreamingProcessorConfig {
+        bar_replay_capacity: 16,
+        ..Default::default()
+    };
+    let mut processor = StreamingProcessor::with_config(25, config);
+    let trade_sender = processor.trade_sender().unwrap();
+
+    // Lossless consumers block the producer; the dashboard only wants recent bars
+    let strategy: RangeBarStream = processor.subscribe(SubscriptionConfig::default()).into();
+    let writer = processor.subscribe(SubscriptionConfig {
+        capacity: 4,
+        ..Default::default()
+    });
+    let dashboard = processor.subscribe(SubscriptionConfig {
+        capacity: 8,
+        lag: LagPolicy::DropOldest,
+        replay: 0,
+    });
+    let subscriptions = processor.subscriptions();
+
+    let process_task = tokio::spawn(async move {
+        processor.start_processing().await.unwrap();
+        processor.metrics().summary()
+    });
+    let strategy_task = tokio::spawn(strategy.map(Result::unwrap).collect::<Vec<_>>());
+    let writer_task = tokio::spawn(writer.map(Result::unwrap).collect::<Vec<_>>());
+
+    let (first_half, second_half) = trades.split_at(trades.len() / 2);
+    for trade in first_half {
+        trade_sender.send(trade.clone()).await.unwrap();
+    }
+
+    // Joins mid-stream and starts from a replay of the latest bars
+    let late = subscriptions.subscribe(SubscriptionConfig {
+        replay: 3,
+        ..Default::default()
+    });
+    let late_task = tokio::spawn(late.map(Result::unwrap).collect::<Vec<_>>());
+
+    for trade in second_half {
+        trade_sender.send(trade.clone()).await.unwrap();
+    }
+    drop(trade_sender);
+
+    let summary = process_task.await.unwrap();
+    assert_eq!(strategy_task.await.unwrap(), expected);
+    assert_eq!(writer_task.await.unwrap(), expected);
+
+    // The late subscriber's bars are a contiguous tail of the full sequence
+    let late_bars = late_task.await.unwrap();
+    let start = expected.len() - late_bars.len();
+    assert!(late_bars.len() >= 3);
+    assert_eq!(late_bars, expected[start..]);
+
+    // Never read during the run: only the newest bars survive, the rest are counted
+    let dashboard_bars: Vec<_> = dashboard.map(Result::unwrap).collect().await;
+    assert_eq!(dashboard_bars, expected[expected.len() - 8..]);
+    assert_eq!(summary.subscriber_bars_dropped, (expected.len() - 8) as u64);
+    assert_eq!(summary.subscribers_disconnected, 0);
+}
+
+#[tokio::test]
+async fn test_slow_subscriber_is_disconnected_without_stalling() {
+    let trades: Vec<_> = (0..2_000).map(synthetic_trade).collect();
+    let expected = expected_bars(&trades);
+
+    let mut processor = StreamingProcessor::new(25);
+    let trade_sender = processor.trade_sender().unwrap();
+    let mut bar_receiver = processor.bar_receiver().unwrap();
+    let slow = processor.subscribe(SubscriptionConfig {
+        capacity: 2,
+        lag: LagPolicy::Disconnect,
+        replay: 0,
+    });
+
+    let process_task = tokio::spawn(async move {
+        processor.start_processing().await.unwrap();
+        processor.metrics().summary()
+    });
+    let receive_task = tokio::spawn(async move {
+        let mut bars = Vec::new();
+        while let Some(bar) = bar_receiver.recv().await {
+            bars.push(bar);
+        }
+        bars
+    });
+
+    for trade in trades {
+        trade_sender.send(trade).await.unwrap();
+    }
+    drop(trade_sender);
+
+    let summary = process_task.await.unwrap();
+    assert_eq!(receive_task.await.unwrap(), expected);
+    assert_eq!(summary.subscribers_disconnected, 1);
+
+    let results: Vec<_> = slow.collect().await;
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Ok(_),
+            Ok(_),
+            Err(StreamingError::SubscriberLagged { capacity: 2 })
+        ]
+    ));
+}
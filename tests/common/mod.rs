@@ -0,0 +1,45 @@
+//! Helpers shared by the integration tests
+
+// Each test crate uses its own subset
+#![allow(dead_code)]
+
+use rangebar::{AggTrade, FixedPoint};
+
+/// Deterministic xorshift generator so failures are reproducible
+pub struct XorShift(pub u64);
+
+impl XorShift {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn range(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Deterministic zig-zag walk around 10000 with occasional jumps
+pub fn synthetic_trade(i: i64) -> AggTrade {
+    synthetic_symbol_trade(0, i)
+}
+
+/// [`synthetic_trade`] feed of symbol number `symbol`, shifted along the walk
+/// and 1000 higher per symbol so symbols differ
+pub fn synthetic_symbol_trade(symbol: usize, i: i64) -> AggTrade {
+    let symbol = symbol as i64;
+    let phase = i + symbol * 131;
+    let step = (phase * 7_919) % 400 - 200;
+    let jump = if phase % 997 == 0 { 300 } else { 0 };
+    AggTrade {
+        agg_trade_id: i,
+        price: FixedPoint((10_000 + 1_000 * symbol + step + jump) * 100_000_000),
+        volume: FixedPoint(10_000_000 + (i % 13) * 1_000_000),
+        first_trade_id: 2 * i,
+        last_trade_id: 2 * i + i % 2,
+        timestamp: 1_609_459_200_000 + i * 10,
+        is_buyer_maker: i % 3 == 0,
+    }
+}
@@ -196,7 +196,9 @@ fn benchmark_batch_processing(trades: &[AggTrade], threshold_bps: u32) -> Proces
 
     let start_time = Instant::now();
     let mut processor = ExportRangeBarProcessor::new(threshold_bps);
-    processor.process_trades_continuously(trades);
+    processor
+        .process_trades_continuously(trades)
+        .expect("trades should be sorted");
     let mut bars = processor.get_all_completed_bars();
 
     if let Some(incomplete) = processor.get_incomplete_bar() {
@@ -389,10 +391,10 @@ fn get_memory_usage_kb() -> u64 {
         if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
             for line in status.lines() {
                 if line.starts_with("VmRSS:") {
-                    if let Some(kb_str) = line.split_whitespace().nth(1) {
-                        if let Ok(rss_kb) = kb_str.parse::<u64>() {
-                            return rss_kb;
-                        }
+                    if let Some(kb_str) = line.split_whitespace().nth(1)
+                        && let Ok(rss_kb) = kb_str.parse::<u64>()
+                    {
+                        return rss_kb;
                     }
                     break;
                 }
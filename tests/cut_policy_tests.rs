@@ -0,0 +1,131 @@
+//! Session-boundary and maximum-duration cut tests
+//!
+//! Cut bars must never span a session or exceed their caps, must never be
+//! labelled as breaches, and must be reproducible across chunking and
+//! checkpoint/restore like every other bar.
+
+mod common;
+
+use common::XorShift;
+use rangebar::cut_policy::{CutPolicy, SessionBoundary};
+use rangebar::fixed_point::FixedPoint;
+use rangebar::ladder::LadderProcessor;
+use rangebar::range_bars::RangeBarProcessor;
+use rangebar::types::{AggTrade, CloseReason, RangeBar};
+
+const HOUR_MS: i64 = 3_600_000;
+
+/// A week of trading starting Friday 2024-01-05 00:00 UTC, sparse over the weekend
+fn generate_week(seed: u64) -> Vec<AggTrade> {
+    let mut rng = XorShift(seed);
+    let mut price: i64 = 40_000 * 100_000_000;
+    let mut timestamp: i64 = 1_704_412_800_000;
+    let end = timestamp + 7 * 24 * HOUR_MS;
+    let mut trades = Vec::new();
+
+    while timestamp < end {
+        // Saturday and Sunday: one quiet trade every few hours
+        let weekday = (timestamp / (24 * HOUR_MS) + 3) % 7; // 0 = Monday
+        let (gap, step) = if weekday >= 5 {
+            (rng.range(4 * HOUR_MS as u64) as i64 + 1, 10_000)
+        } else {
+            (rng.range(120_000) as i64 + 1, 2_000_000)
+        };
+        timestamp += gap;
+        price += (rng.range(2_001) as i64 - 1_000) * step;
+
+        let id = trades.len() as i64 + 1;
+        trades.push(AggTrade {
+            agg_trade_id: id,
+            price: FixedPoint(price),
+            volume: FixedPoint(rng.range(100_000_000) as i64 + 1),
+            first_trade_id: id,
+            last_trade_id: id,
+            timestamp,
+            is_buyer_maker: rng.range(2) == 0,
+        });
+    }
+
+    trades
+}
+
+fn cuts() -> CutPolicy {
+    CutPolicy {
+        session: Some(SessionBoundary::UtcDay),
+        max_duration_ms: Some(6 * HOUR_MS),
+        max_trades: Some(500),
+    }
+}
+
+fn run(trades: &[AggTrade]) -> Vec<RangeBar> {
+    let mut processor = RangeBarProcessor::new(50).with_cut_policy(cuts());
+    processor.process_trades_with_incomplete(trades).unwrap()
+}
+
+#[test]
+fn test_cut_bars_respect_sessions_and_caps() {
+    let trades = generate_week(17);
+    let bars = run(&trades);
+
+    let reasons = |reason| {
+        bars.iter()
+            .filter(|b| b.close_reason == Some(reason))
+            .count()
+    };
+    assert!(reasons(CloseReason::Breach) > 0);
+    assert!(reasons(CloseReason::SessionCut) >= 6);
+    assert!(
+        reasons(CloseReason::MaxDuration) > 0,
+        "quiet weekend bars are capped"
+    );
+
+    let day = |ts: i64| ts.div_euclid(24 * HOUR_MS);
+    for bar in &bars {
+        assert_eq!(day(bar.open_time), day(bar.close_time));
+        assert!(bar.close_time - bar.open_time <= 6 * HOUR_MS);
+        assert!(bar.trade_count <= 500);
+
+        // Only breaches carry a direction
+        assert_eq!(
+            bar.close_direction.is_some(),
+            bar.close_reason == Some(CloseReason::Breach)
+        );
+    }
+
+    // Every trade lands in exactly one bar
+    let total: i64 = bars.iter().map(|b| b.trade_count).sum();
+    assert_eq!(total, trades.len() as i64);
+}
+
+#[test]
+fn test_cuts_are_reproducible_across_chunks_restore_and_ladder() {
+    let trades = generate_week(23);
+    let expected = run(&trades);
+
+    let (head, tail) = trades.split_at(trades.len() / 3);
+    let mut first = RangeBarProcessor::new(50).with_cut_policy(cuts());
+    let mut bars = Vec::new();
+    for chunk in head.chunks(97) {
+        bars.extend(first.process_trades(chunk).unwrap());
+    }
+    let json = serde_json::to_string(&first.checkpoint()).unwrap();
+    let mut second = RangeBarProcessor::restore(serde_json::from_str(&json).unwrap()).unwrap();
+    bars.extend(second.process_trades_with_incomplete(tail).unwrap());
+    assert_eq!(expected, bars);
+
+    // Each ladder rung keeps its own cuts
+    let mut ladder = LadderProcessor::from_processors(vec![
+        RangeBarProcessor::new(50).with_cut_policy(cuts()),
+        RangeBarProcessor::new(50),
+    ])
+    .unwrap();
+    let mut rungs = ladder.process_trades(&trades).unwrap();
+    for (rung, bar) in ladder.finish().into_iter().enumerate() {
+        rungs[rung].extend(bar);
+    }
+    assert_eq!(expected, rungs[0]);
+    assert!(rungs[1].iter().all(|b| matches!(
+        b.close_reason,
+        Some(CloseReason::Breach | CloseReason::EndOfData)
+    )));
+}
@@ -0,0 +1,138 @@
+//! Decimal parser fuzz tests against `rust_decimal`
+//!
+//! `FixedPoint::from_bytes` must agree with an independent decimal
+//! implementation on every sign, exponent, scale and excess-precision policy.
+
+use proptest::prelude::*;
+use rangebar::fixed_point::{ExcessPrecision, FixedPoint, FixedPointError, Scale};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Generated decimal literal and the parts it was built from
+#[derive(Debug, Clone)]
+struct Literal {
+    text: String,
+    negative: bool,
+    digits: String,
+    frac_len: u32,
+    exponent: i32,
+}
+
+fn literal() -> impl Strategy<Value = Literal> {
+    (
+        prop_oneof![Just(""), Just("+"), Just("-")],
+        "[0-9]{0,12}",
+        proptest::option::of("[0-9]{0,14}"),
+        proptest::option::of((
+            prop_oneof![Just("e"), Just("E")],
+            -12i32..=12,
+            any::<bool>(),
+        )),
+    )
+        .prop_filter("needs a digit", |(_, int, frac, _)| {
+            !int.is_empty() || frac.as_ref().is_some_and(|frac| !frac.is_empty())
+        })
+        .prop_map(|(sign, int, frac, exp)| {
+            let mut text = format!("{}{}", sign, int);
+            if let Some(ref frac) = frac {
+                text.push('.');
+                text.push_str(frac);
+            }
+            let exponent = match exp {
+                Some((marker, exponent, explicit_plus)) => {
+                    let plus = if explicit_plus && exponent >= 0 {
+                        "+"
+                    } else {
+                        ""
+                    };
+                    text.push_str(&format!("{}{}{}", marker, plus, exponent));
+                    exponent
+                }
+                None => 0,
+            };
+            let frac = frac.unwrap_or_default();
+            Literal {
+                text,
+                negative: sign == "-",
+                digits: format!("{}{}", int, frac),
+                frac_len: frac.len() as u32,
+                exponent,
+            }
+        })
+}
+
+/// Expected parse result computed with `rust_decimal`
+fn reference(
+    literal: &Literal,
+    scale: Scale,
+    excess: ExcessPrecision,
+) -> Result<FixedPoint, FixedPointError> {
+    let mantissa: i128 = literal.digits.parse().unwrap();
+    let mantissa = if literal.negative {
+        -mantissa
+    } else {
+        mantissa
+    };
+
+    // Value in raw units: mantissa * 10^(exponent - frac_len + scale)
+    let shift = literal.exponent as i64 - literal.frac_len as i64 + scale.decimals() as i64;
+    let raw = if shift <= 0 {
+        Decimal::from_i128_with_scale(mantissa, (-shift) as u32)
+    } else {
+        Decimal::from_i128_with_scale(mantissa, 0)
+            .checked_mul(Decimal::from_i128_with_scale(10_i128.pow(shift as u32), 0))
+            .map_or(Decimal::MAX, |raw| raw)
+    };
+
+    if raw.trunc().to_i64().is_none() {
+        return Err(FixedPointError::Overflow);
+    }
+    let rounded = match excess {
+        ExcessPrecision::Reject if !raw.fract().is_zero() => {
+            return Err(FixedPointError::TooManyDecimals);
+        }
+        ExcessPrecision::Reject | ExcessPrecision::Truncate => raw.trunc(),
+        ExcessPrecision::Round => {
+            raw.round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)
+        }
+    };
+    rounded
+        .to_i64()
+        .map(FixedPoint)
+        .ok_or(FixedPointError::Overflow)
+}
+
+fn excess_policy() -> impl Strategy<Value = ExcessPrecision> {
+    prop_oneof![
+        Just(ExcessPrecision::Reject),
+        Just(ExcessPrecision::Round),
+        Just(ExcessPrecision::Truncate),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(20_000))]
+
+    #[test]
+    fn test_from_bytes_matches_reference(
+        literal in literal(),
+        decimals in 0u8..=12,
+        excess in excess_policy(),
+    ) {
+        let scale = Scale::new(decimals).unwrap();
+        let parsed = FixedPoint::from_bytes(literal.text.as_bytes(), scale, excess);
+        prop_assert_eq!(parsed, reference(&literal, scale, excess), "{:?}", literal.text);
+    }
+
+    #[test]
+    fn test_round_trips_through_display(raw in any::<i64>(), decimals in 0u8..=18) {
+        let scale = Scale::new(decimals).unwrap();
+        let text = FixedPoint(raw).to_string_scaled(scale);
+        prop_assert_eq!(FixedPoint::parse_scaled(&text, scale), Ok(FixedPoint(raw)));
+    }
+
+    #[test]
+    fn test_garbage_never_panics(bytes in proptest::collection::vec(any::<u8>(), 0..40)) {
+        let _ = FixedPoint::from_bytes(&bytes, Scale::DEFAULT, ExcessPrecision::Round);
+    }
+}
@@ -0,0 +1,402 @@
+//! Differential tests for the canonical range bar engine
+//!
+//! Every processing path in the crate (batch, chunked, single-trade, export
+//! wrapper and production streaming) must drive the same `RangeBarProcessor`
+//! and therefore emit bit-identical bars for identical input.
+
+mod common;
+
+use common::XorShift;
+use rangebar::event_bars::{EventBarProcessor, EventBarRule};
+use rangebar::fixed_point::{BASIS_POINTS_SCALE, FixedPoint};
+use rangebar::imbalance_bars::{ImbalanceBarConfig, ImbalanceBarKind, ImbalanceBarProcessor};
+use rangebar::ladder::LadderProcessor;
+use rangebar::range_bars::{BarProcessor, ExportRangeBarProcessor, RangeBarProcessor};
+use rangebar::streaming_processor::{StreamingProcessor, StreamingProcessorConfig};
+use rangebar::types::{AggTrade, RangeBar};
+
+const THRESHOLDS_BPS: [u32; 4] = [1, 10, 25, 80];
+
+/// Adversarial trade path: random walk with gaps, exact threshold hits,
+/// same-millisecond bursts and multi-trade aggregates
+fn generate_adversarial_trades(seed: u64, count: usize) -> Vec<AggTrade> {
+    let mut rng = XorShift(seed);
+    let mut trades = Vec::with_capacity(count);
+    let mut price: i64 = 50_000 * 100_000_000;
+    let mut timestamp: i64 = 1_640_995_200_000;
+    let mut trade_id: i64 = 1;
+
+    for i in 0..count {
+        let step = match rng.range(20) {
+            // Large gap (several thresholds at once)
+            0 => (rng.range(400) as i64 - 200) * price / 10_000,
+            // Exact 25 bps hit relative to current price
+            1 => price * 25 / BASIS_POINTS_SCALE as i64,
+            2 => -(price * 25 / BASIS_POINTS_SCALE as i64),
+            // Ordinary tick noise
+            _ => (rng.range(2_001) as i64 - 1_000) * 100_000,
+        };
+        price = (price + step).max(100_000_000);
+
+        // Same-millisecond bursts are frequent in real aggTrades
+        if rng.range(3) != 0 {
+            timestamp += rng.range(250) as i64 + 1;
+        }
+
+        let aggregated = rng.range(4) as i64;
+        trades.push(AggTrade {
+            agg_trade_id: i as i64 + 1,
+            price: FixedPoint(price),
+            volume: FixedPoint(rng.range(500_000_000) as i64 + 1),
+            first_trade_id: trade_id,
+            last_trade_id: trade_id + aggregated,
+            timestamp,
+            is_buyer_maker: rng.range(2) == 0,
+        });
+        trade_id += aggregated + 1;
+    }
+
+    trades
+}
+
+fn reference_bars(trades: &[AggTrade], threshold_bps: u32) -> Vec<RangeBar> {
+    let mut processor = RangeBarProcessor::new(threshold_bps);
+    processor
+        .process_trades_with_incomplete(trades)
+        .expect("generated trades are sorted")
+}
+
+async fn streaming_bars(trades: &[AggTrade], threshold_bps: u32) -> Vec<RangeBar> {
+    let config = StreamingProcessorConfig {
+        trade_channel_capacity: 64,
+        bar_channel_capacity: 8,
+        ..Default::default()
+    };
+    drain_streaming(
+        StreamingProcessor::with_config(threshold_bps, config),
+        trades,
+    )
+    .await
+}
+
+async fn drain_streaming(mut processor: StreamingProcessor, trades: &[AggTrade]) -> Vec<RangeBar> {
+    let trade_sender = processor.trade_sender().unwrap();
+    let mut bar_receiver = processor.bar_receiver().unwrap();
+
+    let process_task = tokio::spawn(async move { processor.start_processing().await });
+
+    let trades_to_send = trades.to_vec();
+    let send_task = tokio::spawn(async move {
+        for trade in trades_to_send {
+            trade_sender.send(trade).await.unwrap();
+        }
+    });
+
+    let mut bars = Vec::new();
+    while let Some(bar) = bar_receiver.recv().await {
+        bars.push(bar);
+    }
+
+    send_task.await.unwrap();
+    process_task.await.unwrap().unwrap();
+    bars
+}
+
+#[test]
+fn test_chunked_and_single_trade_paths_are_identical() {
+    for seed in 1..=5 {
+        let trades = generate_adversarial_trades(seed, 5_000);
+
+        for threshold_bps in THRESHOLDS_BPS {
+            let expected = reference_bars(&trades, threshold_bps);
+
+            for chunk_size in [1, 3, 100, 4_999] {
+                let mut processor = RangeBarProcessor::new(threshold_bps);
+                let mut bars = Vec::new();
+                for chunk in trades.chunks(chunk_size) {
+                    bars.extend(processor.process_trades(chunk).unwrap());
+                }
+                bars.extend(processor.finish());
+                assert_eq!(
+                    expected, bars,
+                    "seed {} threshold {} chunk {}",
+                    seed, threshold_bps, chunk_size
+                );
+            }
+
+            let mut processor = RangeBarProcessor::new(threshold_bps);
+            let mut bars = Vec::new();
+            for trade in &trades {
+                bars.extend(processor.process_single_trade(trade.clone()).unwrap());
+            }
+            bars.extend(processor.finish());
+            assert_eq!(expected, bars, "seed {} threshold {}", seed, threshold_bps);
+        }
+    }
+}
+
+#[test]
+fn test_export_processor_is_identical() {
+    for seed in 1..=5 {
+        let trades = generate_adversarial_trades(seed, 5_000);
+
+        for threshold_bps in THRESHOLDS_BPS {
+            let mut processor = ExportRangeBarProcessor::new(threshold_bps);
+            for chunk in trades.chunks(1_000) {
+                processor.process_trades_continuously(chunk).unwrap();
+            }
+            let mut bars = processor.get_all_completed_bars();
+            bars.extend(processor.finish());
+
+            assert_eq!(reference_bars(&trades, threshold_bps), bars);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_streaming_processor_is_identical() {
+    for seed in 1..=3 {
+        let trades = generate_adversarial_trades(seed, 3_000);
+
+        for threshold_bps in THRESHOLDS_BPS {
+            let bars = streaming_bars(&trades, threshold_bps).await;
+            assert_eq!(
+                reference_bars(&trades, threshold_bps),
+                bars,
+                "seed {} threshold {}",
+                seed,
+                threshold_bps
+            );
+        }
+    }
+}
+
+#[test]
+fn test_ladder_matches_separate_processors() {
+    for seed in 1..=3 {
+        let trades = generate_adversarial_trades(seed, 5_000);
+        let expected: Vec<Vec<RangeBar>> = THRESHOLDS_BPS
+            .iter()
+            .map(|&threshold_bps| reference_bars(&trades, threshold_bps))
+            .collect();
+
+        for chunk_size in [1, 100, 4_999] {
+            let mut ladder = LadderProcessor::new(THRESHOLDS_BPS).unwrap();
+            let mut bars = vec![Vec::new(); THRESHOLDS_BPS.len()];
+            for chunk in trades.chunks(chunk_size) {
+                ladder.process_trades_into(chunk, &mut bars).unwrap();
+            }
+            ladder.finish_into(&mut bars).unwrap();
+            assert_eq!(expected, bars, "seed {} chunk {}", seed, chunk_size);
+        }
+
+        // One CSV writer per rung
+        let mut ladder = LadderProcessor::new(THRESHOLDS_BPS).unwrap();
+        let mut writers: Vec<_> = THRESHOLDS_BPS
+            .iter()
+            .map(|_| csv::Writer::from_writer(Vec::new()))
+            .collect();
+        ladder.process_trades_into(&trades, &mut writers).unwrap();
+        ladder.finish_into(&mut writers).unwrap();
+        for (writer, expected) in writers.into_iter().zip(&expected) {
+            let csv = writer.into_inner().unwrap();
+            let rows: Vec<RangeBar> = csv::Reader::from_reader(csv.as_slice())
+                .deserialize()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(
+                rows.iter()
+                    .map(|b| (b.first_id, b.last_id))
+                    .collect::<Vec<_>>(),
+                expected
+                    .iter()
+                    .map(|b| (b.first_id, b.last_id))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_streaming_ladder_routes_each_rung_to_its_own_channel() {
+    let trades = generate_adversarial_trades(4, 3_000);
+    let config = StreamingProcessorConfig {
+        trade_channel_capacity: 64,
+        bar_channel_capacity: 8,
+        ..Default::default()
+    };
+    let mut processor =
+        StreamingProcessor::with_processor(LadderProcessor::new(THRESHOLDS_BPS).unwrap(), config);
+    let trade_sender = processor.trade_sender().unwrap();
+    let bar_receivers = processor.bar_receivers().unwrap();
+    assert_eq!(bar_receivers.len(), THRESHOLDS_BPS.len());
+    assert!(processor.bar_receiver().is_none());
+
+    // Every rung is drained concurrently so no channel stalls the others
+    let drain_tasks: Vec<_> = bar_receivers
+        .into_iter()
+        .map(|mut receiver| {
+            tokio::spawn(async move {
+                let mut bars = Vec::new();
+                while let Some(bar) = receiver.recv().await {
+                    bars.push(bar);
+                }
+                bars
+            })
+        })
+        .collect();
+
+    let process_task = tokio::spawn(async move { processor.start_processing().await });
+    for trade in trades.clone() {
+        trade_sender.send(trade).await.unwrap();
+    }
+    drop(trade_sender);
+
+    for (task, threshold_bps) in drain_tasks.into_iter().zip(THRESHOLDS_BPS) {
+        assert_eq!(
+            reference_bars(&trades, threshold_bps),
+            task.await.unwrap(),
+            "threshold {}",
+            threshold_bps
+        );
+    }
+    process_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_event_bars_share_streaming_and_export_plumbing() {
+    let trades = generate_adversarial_trades(9, 3_000);
+    let rules = [
+        EventBarRule::Tick(50),
+        EventBarRule::Volume(FixedPoint::from_str("20.0").unwrap()),
+        EventBarRule::Dollar(FixedPoint::from_str("1000000.0").unwrap()),
+    ];
+
+    for rule in rules {
+        let mut reference = EventBarProcessor::new(rule).unwrap();
+        let expected = reference.process_trades_with_incomplete(&trades).unwrap();
+        assert!(expected.len() > 10, "{}", rule);
+
+        let mut export =
+            ExportRangeBarProcessor::with_processor(EventBarProcessor::new(rule).unwrap());
+        for chunk in trades.chunks(1_000) {
+            export.process_trades_continuously(chunk).unwrap();
+        }
+        let mut bars = export.get_all_completed_bars();
+        bars.extend(export.finish());
+        assert_eq!(expected, bars, "export {}", rule);
+
+        let config = StreamingProcessorConfig {
+            trade_channel_capacity: 64,
+            bar_channel_capacity: 8,
+            ..Default::default()
+        };
+        let streaming =
+            StreamingProcessor::with_processor(EventBarProcessor::new(rule).unwrap(), config);
+        assert_eq!(
+            expected,
+            drain_streaming(streaming, &trades).await,
+            "streaming {}",
+            rule
+        );
+    }
+}
+
+#[test]
+fn test_imbalance_bars_share_export_plumbing_and_format() {
+    let trades = generate_adversarial_trades(13, 5_000);
+    let kinds = [
+        (ImbalanceBarKind::TickImbalance, 20.0),
+        (ImbalanceBarKind::VolumeImbalance, 5.0),
+        (ImbalanceBarKind::DollarImbalance, 250_000.0),
+        (ImbalanceBarKind::TickRun, 40.0),
+        (ImbalanceBarKind::VolumeRun, 10.0),
+    ];
+
+    for (kind, initial_threshold) in kinds {
+        let config = ImbalanceBarConfig::new(kind, initial_threshold);
+        let mut reference = ImbalanceBarProcessor::new(config).unwrap();
+        let expected = reference.process_trades_with_incomplete(&trades).unwrap();
+        assert!(expected.len() > 10, "{:?}", kind);
+
+        let mut export =
+            ExportRangeBarProcessor::with_processor(ImbalanceBarProcessor::new(config).unwrap());
+        for chunk in trades.chunks(333) {
+            export.process_trades_continuously(chunk).unwrap();
+        }
+        let mut bars = export.get_all_completed_bars();
+        bars.extend(export.finish());
+        assert_eq!(expected, bars, "{:?}", kind);
+
+        // Order flow totals are exact across every bar
+        let buy_trades: i64 = bars.iter().map(|b| b.buy_trade_count).sum();
+        let sell_trades: i64 = bars.iter().map(|b| b.sell_trade_count).sum();
+        let total_trades: i64 = trades.iter().map(|t| t.trade_count()).sum();
+        assert_eq!(buy_trades + sell_trades, total_trades);
+
+        // Same CSV and JSON serialization as the exporter
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        for bar in &bars {
+            wtr.serialize(bar).unwrap();
+        }
+        let csv_bytes = wtr.into_inner().unwrap();
+        let from_csv: Vec<RangeBar> = csv::Reader::from_reader(csv_bytes.as_slice())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(from_csv.len(), bars.len());
+        assert_eq!(from_csv[0].buy_turnover, bars[0].buy_turnover);
+        assert_eq!(from_csv[0].close_reason, bars[0].close_reason);
+
+        let json = serde_json::to_string_pretty(&bars).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<RangeBar>>(&json).unwrap(), bars);
+    }
+}
+
+#[test]
+fn test_engine_semantics_match_specification() {
+    let trades = generate_adversarial_trades(42, 10_000);
+    let threshold_bps = 25;
+    let bars = reference_bars(&trades, threshold_bps);
+    let (completed, incomplete) = bars.split_at(bars.len() - 1);
+
+    let mut cursor = 0;
+    for bar in bars.iter() {
+        let start = trades[cursor..]
+            .iter()
+            .position(|t| t.agg_trade_id == bar.first_id)
+            .unwrap()
+            + cursor;
+        let end = trades[start..]
+            .iter()
+            .position(|t| t.agg_trade_id == bar.last_id)
+            .unwrap()
+            + start;
+        let members = &trades[start..=end];
+
+        // Next bar opens with the trade after the breach (no shared trades)
+        assert_eq!(start, cursor);
+        cursor = end + 1;
+
+        // Exact i128 turnover and individual-trade counts
+        let turnover: i128 = members.iter().map(|t| t.turnover()).sum();
+        let trade_count: i64 = members.iter().map(|t| t.trade_count()).sum();
+        assert_eq!(bar.turnover, turnover);
+        assert_eq!(bar.buy_turnover + bar.sell_turnover, turnover);
+        assert_eq!(bar.trade_count, trade_count);
+
+        // Thresholds in BASIS_POINTS_SCALE units, fixed from the open
+        let delta =
+            (bar.open.0 as i128 * threshold_bps as i128 / BASIS_POINTS_SCALE as i128) as i64;
+        let (upper, lower) = (bar.open.0 + delta, bar.open.0 - delta);
+        let breaches = |t: &AggTrade| t.price.0 >= upper || t.price.0 <= lower;
+
+        let (last, body) = members.split_last().unwrap();
+        assert!(!body.iter().any(breaches));
+        let is_completed = completed.iter().any(|b| b.first_id == bar.first_id);
+        assert_eq!(breaches(last), is_completed);
+    }
+
+    assert_eq!(cursor, trades.len());
+    assert_eq!(incomplete.len(), 1);
+}
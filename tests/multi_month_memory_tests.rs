@@ -749,7 +749,9 @@ fn create_test_trade(id: u64, price: f64, timestamp: u64) -> AggTrade {
 
 fn process_batch_style(trades: &[AggTrade], threshold_bps: u32) -> Vec<RangeBar> {
     let mut processor = ExportRangeBarProcessor::new(threshold_bps);
-    processor.process_trades_continuously(trades);
+    processor
+        .process_trades_continuously(trades)
+        .expect("trades should be sorted");
     let mut bars = processor.get_all_completed_bars();
     if let Some(incomplete) = processor.get_incomplete_bar() {
         bars.push(incomplete);
@@ -763,7 +765,9 @@ async fn process_streaming_style(trades: &[AggTrade], threshold_bps: u32) -> Vec
     let mut all_bars = Vec::new();
 
     for chunk in trades.chunks(chunk_size) {
-        range_processor.process_trades_continuously(chunk);
+        range_processor
+            .process_trades_continuously(chunk)
+            .expect("trades should be sorted");
         let chunk_bars = range_processor.get_all_completed_bars();
         all_bars.extend(chunk_bars);
     }
@@ -291,6 +291,13 @@ fn create_test_bar(id: u64) -> RangeBar {
         vwap: base_price,
         buy_turnover: turnover / 2,
         sell_turnover: turnover / 2,
+        close_direction: None,
+        upper_threshold: None,
+        lower_threshold: None,
+        close_reason: None,
+        overshoot: None,
+        overshoot_bps: None,
+        missing_agg_trade_ids: None,
     }
 }
 
@@ -337,10 +344,10 @@ fn get_current_memory_kb() -> u64 {
         if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
             for line in status.lines() {
                 if line.starts_with("VmRSS:") {
-                    if let Some(kb_str) = line.split_whitespace().nth(1) {
-                        if let Ok(rss_kb) = kb_str.parse::<u64>() {
-                            return rss_kb;
-                        }
+                    if let Some(kb_str) = line.split_whitespace().nth(1)
+                        && let Ok(rss_kb) = kb_str.parse::<u64>()
+                    {
+                        return rss_kb;
                     }
                     break;
                 }
@@ -0,0 +1,166 @@
+//! Reordering stage tests
+//!
+//! Trades jittered within the lateness window must produce exactly the bars of
+//! the sorted input; trades later than the window must be rejected or dropped
+//! without touching the bars.
+
+mod common;
+
+use common::XorShift;
+use rangebar::fixed_point::FixedPoint;
+use rangebar::range_bars::{BarProcessor, ProcessingError, RangeBarProcessor};
+use rangebar::reorder::{LatePolicy, LatenessWindow, ReorderConfig, ReorderingProcessor};
+use rangebar::streaming_processor::{StreamingProcessor, StreamingProcessorConfig};
+use rangebar::types::{AggTrade, RangeBar};
+
+fn generate_sorted(seed: u64, count: usize) -> Vec<AggTrade> {
+    let mut rng = XorShift(seed);
+    let mut price: i64 = 30_000 * 100_000_000;
+    let mut timestamp: i64 = 1_640_995_200_000;
+
+    (1..=count as i64)
+        .map(|id| {
+            price += (rng.range(2_001) as i64 - 1_000) * 300_000;
+            timestamp += rng.range(4) as i64; // Frequent same-millisecond trades
+            AggTrade {
+                agg_trade_id: id,
+                price: FixedPoint(price),
+                volume: FixedPoint(rng.range(100_000_000) as i64 + 1),
+                first_trade_id: id,
+                last_trade_id: id,
+                timestamp,
+                is_buyer_maker: rng.range(2) == 0,
+            }
+        })
+        .collect()
+}
+
+/// Delay each trade by up to `max_delay_ms` of arrival time
+fn jitter(trades: &[AggTrade], seed: u64, max_delay_ms: i64) -> Vec<AggTrade> {
+    let mut rng = XorShift(seed);
+    let mut arrivals: Vec<(i64, AggTrade)> = trades
+        .iter()
+        .map(|t| {
+            (
+                t.timestamp + rng.range(max_delay_ms as u64 + 1) as i64,
+                t.clone(),
+            )
+        })
+        .collect();
+    arrivals.sort_by_key(|(arrival, t)| (*arrival, t.agg_trade_id));
+    arrivals.into_iter().map(|(_, t)| t).collect()
+}
+
+fn reference(trades: &[AggTrade]) -> Vec<RangeBar> {
+    RangeBarProcessor::new(10)
+        .process_trades_with_incomplete(trades)
+        .unwrap()
+}
+
+fn drain(processor: &mut impl BarProcessor, trades: &[AggTrade], chunk: usize) -> Vec<RangeBar> {
+    let mut bars = Vec::new();
+    for chunk in trades.chunks(chunk) {
+        bars.extend(processor.process_trades(chunk).unwrap());
+    }
+    bars.extend(processor.finish_routed().into_iter().map(|(_, bar)| bar));
+    bars
+}
+
+#[test]
+fn test_jitter_within_window_matches_sorted_input() {
+    let sorted = generate_sorted(3, 20_000);
+    let arrivals = jitter(&sorted, 9, 20);
+    let ids = |trades: &[AggTrade]| trades.iter().map(|t| t.agg_trade_id).collect::<Vec<_>>();
+    assert_ne!(ids(&sorted), ids(&arrivals));
+
+    // The engine alone refuses the jittered feed
+    assert!(matches!(
+        RangeBarProcessor::new(10).process_trades(&arrivals),
+        Err(ProcessingError::UnsortedTrades { .. })
+    ));
+
+    let expected = reference(&sorted);
+    for chunk in [1, 64, 20_000] {
+        let mut processor = ReorderingProcessor::new(
+            RangeBarProcessor::new(10),
+            ReorderConfig::new(LatenessWindow::Millis(20)),
+        );
+        assert_eq!(
+            expected,
+            drain(&mut processor, &arrivals, chunk),
+            "chunk {}",
+            chunk
+        );
+        assert!(processor.buffer().is_empty());
+    }
+}
+
+#[test]
+fn test_late_trades_are_rejected_or_dropped() {
+    let sorted = generate_sorted(5, 5_000);
+    let mut arrivals = sorted.clone();
+    let late = arrivals.remove(1_000);
+    arrivals.insert(3_000, late.clone());
+
+    // Reject: typed error, bars released before it are not lost
+    let mut processor = ReorderingProcessor::new(
+        RangeBarProcessor::new(10),
+        ReorderConfig::new(LatenessWindow::Ids(100)),
+    );
+    let (head, tail) = arrivals.split_at(3_000);
+    let mut bars = processor.process_trades(head).unwrap();
+    assert!(matches!(
+        processor.process_trades(&tail[..1]),
+        Err(ProcessingError::LateTrade { agg_trade_id, .. }) if agg_trade_id == late.agg_trade_id
+    ));
+    bars.extend(drain(&mut processor, &tail[1..], 500));
+
+    let without_late: Vec<AggTrade> = sorted
+        .iter()
+        .filter(|t| t.agg_trade_id != late.agg_trade_id)
+        .cloned()
+        .collect();
+    assert_eq!(reference(&without_late), bars);
+
+    // Drop: counted, same bars
+    let mut processor = ReorderingProcessor::new(
+        RangeBarProcessor::new(10),
+        ReorderConfig {
+            late: LatePolicy::Drop,
+            ..ReorderConfig::new(LatenessWindow::Ids(100))
+        },
+    );
+    assert_eq!(
+        reference(&without_late),
+        drain(&mut processor, &arrivals, 700)
+    );
+    assert_eq!(processor.buffer().late_trades(), 1);
+}
+
+#[tokio::test]
+async fn test_reordering_in_front_of_streaming() {
+    let sorted = generate_sorted(7, 3_000);
+    let arrivals = jitter(&sorted, 11, 5);
+
+    let processor = ReorderingProcessor::new(
+        RangeBarProcessor::new(10),
+        ReorderConfig::new(LatenessWindow::Millis(5)),
+    );
+    let mut streaming =
+        StreamingProcessor::with_processor(processor, StreamingProcessorConfig::default());
+    let trade_sender = streaming.trade_sender().unwrap();
+    let mut bar_receiver = streaming.bar_receiver().unwrap();
+    let process_task = tokio::spawn(async move { streaming.start_processing().await });
+
+    for trade in arrivals {
+        trade_sender.send(trade).await.unwrap();
+    }
+    drop(trade_sender);
+
+    let mut bars = Vec::new();
+    while let Some(bar) = bar_receiver.recv().await {
+        bars.push(bar);
+    }
+    process_task.await.unwrap().unwrap();
+    assert_eq!(reference(&sorted), bars);
+}
@@ -0,0 +1,104 @@
+//! Multi-symbol routing against one engine per symbol and threshold
+
+mod common;
+
+use common::synthetic_symbol_trade;
+use futures::{StreamExt, stream};
+use rangebar::{
+    RangeBarProcessor, RouterError, SymbolRouter, SymbolRouterConfig, get_tier1_usdt_pairs,
+};
+
+const THRESHOLDS: [u32; 2] = [25, 50];
+
+#[tokio::test]
+async fn test_tier1_feed_with_one_broken_symbol() {
+    const TRADES: i64 = 2_000;
+    let symbols = get_tier1_usdt_pairs();
+    assert_eq!(symbols.len(), 18);
+    let broken = symbols[3].clone();
+
+    // Round-robin interleaving; the broken symbol replays every tenth trade
+    let mut feed = Vec::new();
+    for i in 0..TRADES {
+        for (s, symbol) in symbols.iter().enumerate() {
+            feed.push((symbol.clone(), synthetic_symbol_trade(s, i)));
+            if *symbol == broken && i % 10 == 9 {
+                feed.push((symbol.clone(), synthetic_symbol_trade(s, i - 5)));
+            }
+        }
+    }
+    feed.push(("SHIBUSDT".to_string(), synthetic_symbol_trade(0, 0)));
+
+    let router = SymbolRouter::new(SymbolRouterConfig::tier1(THRESHOLDS)).unwrap();
+    let mut bars = router.into_stream(stream::iter(feed)).with_incomplete();
+    let mut routed = Vec::new();
+    let mut errors = Vec::new();
+    while let Some(result) = bars.next().await {
+        match result {
+            Ok(bar) => routed.push(bar),
+            Err(e) => errors.push(e),
+        }
+    }
+    let router = bars.into_router();
+
+    // Every symbol, the broken one included, matches a standalone engine
+    for (s, symbol) in symbols.iter().enumerate() {
+        let trades: Vec<_> = (0..TRADES).map(|i| synthetic_symbol_trade(s, i)).collect();
+        for threshold in THRESHOLDS {
+            let expected = RangeBarProcessor::new(threshold)
+                .process_trades_with_incomplete(&trades)
+                .unwrap();
+            let actual: Vec<_> = routed
+                .iter()
+                .filter(|r| r.symbol == *symbol && r.range == threshold.into())
+                .map(|r| r.bar.clone())
+                .collect();
+            assert!(expected.len() > 10, "{symbol} produced too few bars");
+            assert_eq!(actual, expected, "{symbol} at {threshold} bps");
+        }
+
+        let metrics = router.metrics(symbol).unwrap();
+        assert_eq!(metrics.trades_processed, TRADES as u64);
+        let expected_errors = if *symbol == broken { TRADES / 10 } else { 0 };
+        assert_eq!(metrics.errors_total, expected_errors as u64, "{symbol}");
+    }
+
+    assert_eq!(errors.len(), TRADES as usize / 10 + 1);
+    assert!(
+        errors[..errors.len() - 1]
+            .iter()
+            .all(|e| e.symbol() == broken && matches!(e, RouterError::Processing { .. }))
+    );
+    assert!(matches!(
+        errors.last(),
+        Some(RouterError::UnknownSymbol { symbol }) if symbol == "SHIBUSDT"
+    ));
+}
+
+#[test]
+fn test_quarantine_drops_only_the_failing_symbol() {
+    let config = SymbolRouterConfig {
+        quarantine_after: Some(3),
+        ..SymbolRouterConfig::new([25])
+    };
+    let mut router = SymbolRouter::new(config).unwrap();
+
+    router
+        .process("BAD", &synthetic_symbol_trade(1, 100))
+        .unwrap();
+    for i in 0..500 {
+        // BAD keeps sending trades older than its first one
+        let _ = router.process("BAD", &synthetic_symbol_trade(1, i));
+        router
+            .process("GOOD", &synthetic_symbol_trade(0, i))
+            .unwrap();
+    }
+
+    let bad = router.metrics("BAD").unwrap();
+    assert!(bad.quarantined);
+    assert_eq!((bad.errors_total, bad.trades_dropped), (3, 497));
+
+    let good = router.metrics("GOOD").unwrap();
+    assert_eq!((good.trades_processed, good.errors_total), (500, 0));
+    assert!(good.bars_generated > 0);
+}
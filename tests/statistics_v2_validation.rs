@@ -129,6 +129,13 @@ fn main() {
             buy_turnover: 0,
             sell_volume: FixedPoint::from_str("4.0").unwrap(),
             sell_turnover: 0,
+            close_direction: None,
+            upper_threshold: None,
+            lower_threshold: None,
+            close_reason: None,
+            overshoot: None,
+            overshoot_bps: None,
+            missing_agg_trade_ids: None,
             buy_trade_count: 25,
             sell_trade_count: 17,
             vwap: FixedPoint::from_str("50075.0").unwrap(),
@@ -149,6 +156,13 @@ fn main() {
             buy_turnover: 0,
             sell_volume: FixedPoint::from_str("6.0").unwrap(),
             sell_turnover: 0,
+            close_direction: None,
+            upper_threshold: None,
+            lower_threshold: None,
+            close_reason: None,
+            overshoot: None,
+            overshoot_bps: None,
+            missing_agg_trade_ids: None,
             buy_trade_count: 38,
             sell_trade_count: 25,
             vwap: FixedPoint::from_str("50125.0").unwrap(),
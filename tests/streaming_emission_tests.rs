@@ -0,0 +1,244 @@
+//! Lossless bar emission through `StreamingProcessor`
+//!
+//! A single trade can complete several bars on one output, e.g. when a
+//! reordering stage releases a burst of held trades. Every one of them must
+//! reach the bar channel, in order, however small the channel.
+
+use proptest::prelude::*;
+use rangebar::fixed_point::FixedPoint;
+use rangebar::ladder::LadderProcessor;
+use rangebar::range_bars::BarProcessor;
+use rangebar::reorder::{LatePolicy, LatenessWindow, ReorderConfig, ReorderingProcessor};
+use rangebar::streaming_processor::{StreamingError, StreamingProcessor, StreamingProcessorConfig};
+use rangebar::types::{AggTrade, RangeBar};
+
+const RUNGS: [u32; 3] = [5, 10, 25];
+
+/// One step of a price path
+#[derive(Debug, Clone)]
+struct Step {
+    /// Price move in bps of the previous price
+    move_bps: i64,
+
+    /// Milliseconds since the previous trade (0 = same millisecond)
+    gap_ms: i64,
+
+    /// `agg_trade_id` jump; large jumps release the whole reorder window at once
+    id_gap: i64,
+
+    /// Arrive after the next trade instead of before it
+    swap: bool,
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    (
+        prop_oneof![
+            Just(0i64),
+            Just(5),
+            Just(-5),
+            Just(10),
+            Just(-10),
+            Just(25),
+            Just(-25),
+            -400i64..=400,
+        ],
+        0i64..=2,
+        prop_oneof![4 => Just(1i64), 1 => 10i64..=40],
+        any::<bool>(),
+    )
+        .prop_map(|(move_bps, gap_ms, id_gap, swap)| Step {
+            move_bps,
+            gap_ms,
+            id_gap,
+            swap,
+        })
+}
+
+/// Sorted trades along the path, and their arrival order
+fn trades_from(steps: &[Step]) -> (Vec<AggTrade>, Vec<AggTrade>) {
+    let mut price: i64 = 50_000 * 100_000_000;
+    let mut timestamp: i64 = 1_640_995_200_000;
+    let mut id = 0;
+
+    let sorted: Vec<_> = steps
+        .iter()
+        .map(|step| {
+            price = (price + price / 10_000 * step.move_bps).max(100_000_000);
+            timestamp += step.gap_ms;
+            id += step.id_gap;
+            AggTrade {
+                agg_trade_id: id,
+                price: FixedPoint(price),
+                volume: FixedPoint(100_000_000),
+                first_trade_id: id,
+                last_trade_id: id,
+                timestamp,
+                is_buyer_maker: step.move_bps < 0,
+            }
+        })
+        .collect();
+
+    // Swap disjoint neighbour pairs so no trade is later than the window
+    let mut arrivals = sorted.clone();
+    let mut i = 0;
+    while i + 1 < arrivals.len() {
+        if steps[i].swap {
+            arrivals.swap(i, i + 1);
+            i += 1;
+        }
+        i += 1;
+    }
+    (sorted, arrivals)
+}
+
+fn engine() -> ReorderingProcessor<LadderProcessor> {
+    ReorderingProcessor::new(
+        LadderProcessor::new(RUNGS).unwrap(),
+        ReorderConfig {
+            late: LatePolicy::Drop,
+            capacity: 16,
+            ..ReorderConfig::new(LatenessWindow::Ids(8))
+        },
+    )
+}
+
+/// Bars of each output from one batch call and a final flush
+fn batch_bars(arrivals: &[AggTrade]) -> Vec<Vec<RangeBar>> {
+    let mut processor = engine();
+    let mut outputs = vec![Vec::new(); RUNGS.len()];
+    let mut routed = processor.process_trades_routed(arrivals).unwrap();
+    routed.extend(processor.finish_routed());
+    for (output, bar) in routed {
+        outputs[output].push(bar);
+    }
+    outputs
+}
+
+/// Bars of each output received through single-slot bar channels
+async fn streamed_bars(arrivals: Vec<AggTrade>) -> Vec<Vec<RangeBar>> {
+    let config = StreamingProcessorConfig {
+        trade_channel_capacity: 4,
+        bar_channel_capacity: 1,
+        ..Default::default()
+    };
+    let mut processor = StreamingProcessor::with_processor(engine(), config);
+    let trade_sender = processor.trade_sender().unwrap();
+    let receivers = processor.bar_receivers().unwrap();
+
+    let process_task = tokio::spawn(async move { processor.start_processing().await });
+    let collectors: Vec<_> = receivers
+        .into_iter()
+        .map(|mut receiver| {
+            tokio::spawn(async move {
+                let mut bars = Vec::new();
+                while let Some(bar) = receiver.recv().await {
+                    bars.push(bar);
+                }
+                bars
+            })
+        })
+        .collect();
+
+    for trade in arrivals {
+        trade_sender.send(trade).await.unwrap();
+    }
+    drop(trade_sender);
+
+    process_task.await.unwrap().unwrap();
+    let mut outputs = Vec::new();
+    for collector in collectors {
+        outputs.push(collector.await.unwrap());
+    }
+    outputs
+}
+
+fn run_streamed(arrivals: Vec<AggTrade>) -> Vec<Vec<RangeBar>> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(streamed_bars(arrivals))
+}
+
+#[test]
+fn test_burst_of_bars_on_one_output_is_delivered() {
+    // Every trade breaches; the id jump at the end releases them all at once
+    let steps: Vec<_> = (0..12)
+        .map(|i| Step {
+            move_bps: if i % 2 == 0 { 60 } else { -60 },
+            gap_ms: 1,
+            id_gap: if i == 11 { 40 } else { 1 },
+            swap: false,
+        })
+        .collect();
+    let (_, arrivals) = trades_from(&steps);
+
+    let expected = batch_bars(&arrivals);
+    let most_in_one_call = {
+        let mut processor = engine();
+        arrivals
+            .iter()
+            .map(|trade| {
+                let bars = processor
+                    .process_trades_routed(std::slice::from_ref(trade))
+                    .unwrap();
+                bars.iter().filter(|(output, _)| *output == 0).count()
+            })
+            .max()
+            .unwrap()
+    };
+    assert!(most_in_one_call > 1, "test path must produce a burst");
+    assert_eq!(run_streamed(arrivals), expected);
+}
+
+#[tokio::test]
+async fn test_undeliverable_bar_stops_processing() {
+    let mut processor = StreamingProcessor::new(25);
+    let trade_sender = processor.trade_sender().unwrap();
+    drop(processor.bar_receiver().unwrap());
+
+    // Every trade moves 1%, so the second trade completes the first bar
+    for i in 0..4 {
+        let trade = AggTrade {
+            agg_trade_id: i,
+            price: FixedPoint((100 + i % 2) * 100_000_000),
+            volume: FixedPoint(100_000_000),
+            first_trade_id: i,
+            last_trade_id: i,
+            timestamp: 1_640_995_200_000 + i,
+            is_buyer_maker: false,
+        };
+        trade_sender.send(trade).await.unwrap();
+    }
+    drop(trade_sender);
+
+    assert!(matches!(
+        processor.start_processing().await,
+        Err(StreamingError::ChannelClosed)
+    ));
+    let summary = processor.metrics().summary();
+    assert_eq!(summary.trades_processed, 2);
+    assert_eq!(summary.bars_undelivered, 1);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_no_bar_lost_or_reordered(steps in prop::collection::vec(step(), 1..300)) {
+        let (sorted, arrivals) = trades_from(&steps);
+        let expected = batch_bars(&arrivals);
+
+        // Reordering is transparent: the same bars as the sorted input
+        let mut ladder = LadderProcessor::new(RUNGS).unwrap();
+        let mut direct = vec![Vec::new(); RUNGS.len()];
+        let mut routed = ladder.process_trades_routed(&sorted).unwrap();
+        routed.extend(ladder.finish_routed());
+        for (output, bar) in routed {
+            direct[output].push(bar);
+        }
+        prop_assert_eq!(&expected, &direct);
+
+        prop_assert_eq!(run_streamed(arrivals), expected);
+    }
+}
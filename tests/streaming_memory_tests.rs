@@ -0,0 +1,185 @@
+//! Memory accounting and threshold policies of `StreamingProcessor`
+//!
+//! Each policy must keep its promise without losing or reordering bars:
+//! pausing waits for consumers, shedding drops trades and counts them, and
+//! spilling parks bars on disk while intake continues.
+
+use rangebar::fixed_point::FixedPoint;
+use rangebar::range_bars::RangeBarProcessor;
+use rangebar::streaming_processor::{
+    MemoryPolicy, MetricsSummary, StreamingProcessor, StreamingProcessorConfig,
+};
+use rangebar::types::{AggTrade, RangeBar};
+use std::time::Duration;
+
+/// Zig-zag of 0.6% swings: every few trades complete a 25 bps bar
+fn create_trades(count: i64) -> Vec<AggTrade> {
+    (0..count)
+        .map(|i| {
+            let swing = if (i / 3) % 2 == 0 { i % 3 } else { 3 - i % 3 };
+            AggTrade {
+                agg_trade_id: i,
+                price: FixedPoint((50_000 + swing * 100) * 100_000_000),
+                volume: FixedPoint(100_000_000),
+                first_trade_id: i,
+                last_trade_id: i,
+                timestamp: 1_640_995_200_000 + i,
+                is_buyer_maker: i % 2 == 0,
+            }
+        })
+        .collect()
+}
+
+fn reference(trades: &[AggTrade]) -> Vec<RangeBar> {
+    let mut processor = RangeBarProcessor::new(25);
+    let mut bars = processor.process_trades(trades).unwrap();
+    bars.extend(processor.finish());
+    bars
+}
+
+/// Stream `trades`, reading bars only after `consumer_delay` per bar
+async fn run(
+    config: StreamingProcessorConfig,
+    trades: Vec<AggTrade>,
+    consumer_delay: Duration,
+) -> (Vec<RangeBar>, MetricsSummary) {
+    let mut processor = StreamingProcessor::with_config(25, config);
+    let trade_sender = processor.trade_sender().unwrap();
+    let mut bar_receiver = processor.bar_receiver().unwrap();
+    let process_task = tokio::spawn(async move {
+        processor.start_processing().await.unwrap();
+        processor.metrics().summary()
+    });
+
+    let send_task = tokio::spawn(async move {
+        for trade in trades {
+            trade_sender.send(trade).await.unwrap();
+        }
+    });
+
+    let mut bars = Vec::new();
+    while let Some(bar) = bar_receiver.recv().await {
+        tokio::time::sleep(consumer_delay).await;
+        bars.push(bar);
+    }
+    send_task.await.unwrap();
+    (bars, process_task.await.unwrap())
+}
+
+#[tokio::test]
+async fn test_usage_is_measured_and_peak_recorded() {
+    let trades = create_trades(600);
+    let config = StreamingProcessorConfig {
+        bar_channel_capacity: 8,
+        ..Default::default()
+    };
+    let (bars, metrics) = run(config, trades.clone(), Duration::from_millis(1)).await;
+
+    assert_eq!(bars, reference(&trades));
+    // At least an open bar was accounted at some point
+    assert!(metrics.peak_memory_usage_bytes >= std::mem::size_of::<RangeBar>() as u64);
+    assert_eq!(
+        (
+            metrics.memory_pauses,
+            metrics.trades_shed,
+            metrics.bars_spilled
+        ),
+        (0, 0, 0)
+    );
+}
+
+#[tokio::test]
+async fn test_pause_intake_waits_for_consumers() {
+    let trades = create_trades(300);
+    let config = StreamingProcessorConfig {
+        bar_channel_capacity: 4,
+        memory_threshold_bytes: 1,
+        memory_policy: MemoryPolicy::PauseIntake,
+        ..Default::default()
+    };
+    let (bars, metrics) = run(config, trades.clone(), Duration::from_millis(2)).await;
+
+    assert_eq!(bars, reference(&trades));
+    assert!(metrics.memory_pauses > 0);
+    assert_eq!(metrics.trades_shed, 0);
+}
+
+#[tokio::test]
+async fn test_shed_counts_dropped_trades_without_errors() {
+    let config = StreamingProcessorConfig {
+        memory_threshold_bytes: 1,
+        memory_policy: MemoryPolicy::Shed,
+        ..Default::default()
+    };
+    let (bars, metrics) = run(config, create_trades(50), Duration::ZERO).await;
+
+    // The first trade is admitted (nothing buffered yet); the open bar then
+    // exceeds the threshold and every later trade is shed
+    assert_eq!(metrics.trades_processed, 1);
+    assert_eq!(metrics.trades_shed, 49);
+    assert_eq!(metrics.errors_total, 0);
+    assert_eq!(bars.len(), 1, "only the force-closed first bar");
+}
+
+#[tokio::test]
+async fn test_spill_to_disk_waits_below_the_threshold() {
+    let dir = std::env::temp_dir().join(format!("rangebar-nospill-test-{}", std::process::id()));
+    let trades = create_trades(300);
+    let config = StreamingProcessorConfig {
+        bar_channel_capacity: 2,
+        memory_threshold_bytes: usize::MAX,
+        memory_policy: MemoryPolicy::SpillToDisk { dir: dir.clone() },
+        ..Default::default()
+    };
+    let (bars, metrics) = run(config, trades.clone(), Duration::from_millis(1)).await;
+
+    // A full channel alone is backpressure, not a reason to spill
+    assert_eq!(bars, reference(&trades));
+    assert_eq!(metrics.bars_spilled, 0);
+    assert!(metrics.backpressure_events > 0);
+    assert!(!dir.exists());
+}
+
+#[tokio::test]
+async fn test_spill_to_disk_keeps_intake_going_and_order_intact() {
+    let dir = std::env::temp_dir().join(format!("rangebar-spill-test-{}", std::process::id()));
+    let trades = create_trades(2_000);
+    let config = StreamingProcessorConfig {
+        bar_channel_capacity: 2,
+        memory_threshold_bytes: 1,
+        memory_policy: MemoryPolicy::SpillToDisk { dir: dir.clone() },
+        ..Default::default()
+    };
+
+    let mut processor = StreamingProcessor::with_config(25, config);
+    let trade_sender = processor.trade_sender().unwrap();
+    let mut bar_receiver = processor.bar_receiver().unwrap();
+    let process_task = tokio::spawn(async move {
+        processor.start_processing().await.unwrap();
+        processor.metrics().summary()
+    });
+
+    // Nobody reads bars until every trade is in: intake must not wait
+    for trade in trades.clone() {
+        tokio::time::timeout(Duration::from_secs(5), trade_sender.send(trade))
+            .await
+            .expect("intake stalled")
+            .unwrap();
+    }
+    drop(trade_sender);
+
+    let mut bars = Vec::new();
+    while let Some(bar) = bar_receiver.recv().await {
+        bars.push(bar);
+    }
+    let metrics = process_task.await.unwrap();
+
+    assert_eq!(bars, reference(&trades));
+    assert!(metrics.bars_spilled > 0);
+    assert_eq!(
+        std::fs::read_dir(&dir).unwrap().count(),
+        0,
+        "spill files are removed with the processor"
+    );
+    std::fs::remove_dir(&dir).unwrap();
+}
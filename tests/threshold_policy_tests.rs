@@ -0,0 +1,189 @@
+//! Non-lookahead and reproducibility tests for threshold policies
+//!
+//! A policy may only use the opening trade and bars completed before it. The
+//! tests prove this empirically: the threshold recorded on a bar must not
+//! change when every trade after the bar's opening trade is removed or
+//! replaced with a different path.
+
+mod common;
+
+use common::XorShift;
+use rangebar::fixed_point::FixedPoint;
+use rangebar::range_bars::{ProcessingError, RangeBarProcessor, RangeBarProcessorConfig};
+use rangebar::threshold::Threshold;
+use rangebar::threshold_policy::{
+    AdaptiveBounds, EwmaRangePolicy, FixedThreshold, RealizedVolatilityPolicy,
+    TargetBarsPerHourPolicy, ThresholdPolicy,
+};
+use rangebar::types::{AggTrade, RangeBar};
+
+/// Random walk with volatility regimes, continuing from `price`, `timestamp` and `first_id`
+fn generate_trades(
+    seed: u64,
+    count: usize,
+    price: i64,
+    timestamp: i64,
+    first_id: i64,
+) -> Vec<AggTrade> {
+    let mut rng = XorShift(seed);
+    let (mut price, mut timestamp) = (price, timestamp);
+
+    (0..count as i64)
+        .map(|i| {
+            // Volatility regime switches every 2000 trades
+            let scale = if (i / 2_000) % 2 == 0 { 1 } else { 5 };
+            price = (price + (rng.range(2_001) as i64 - 1_000) * 200_000 * scale).max(100_000_000);
+            timestamp += rng.range(500) as i64 + 1;
+            AggTrade {
+                agg_trade_id: first_id + i,
+                price: FixedPoint(price),
+                volume: FixedPoint(rng.range(500_000_000) as i64 + 1),
+                first_trade_id: first_id + i,
+                last_trade_id: first_id + i,
+                timestamp,
+                is_buyer_maker: rng.range(2) == 0,
+            }
+        })
+        .collect()
+}
+
+fn policies() -> Vec<Box<dyn ThresholdPolicy>> {
+    let bounds = AdaptiveBounds {
+        initial: Threshold::from_bps(10),
+        min: Threshold::from_bps(2),
+        max: Threshold::from_bps(200),
+    };
+    vec![
+        Box::new(FixedThreshold::from(10)),
+        Box::new(EwmaRangePolicy::new(0.2, 0.7, bounds)),
+        Box::new(RealizedVolatilityPolicy::new(10, 1.0, bounds)),
+        Box::new(TargetBarsPerHourPolicy::new(120.0, 5, bounds)),
+    ]
+}
+
+fn run(policy: Box<dyn ThresholdPolicy>, trades: &[AggTrade]) -> Vec<RangeBar> {
+    let mut processor = RangeBarProcessor::with_policy(policy, RangeBarProcessorConfig::default());
+    processor.process_trades_with_incomplete(trades).unwrap()
+}
+
+#[test]
+fn test_policies_never_use_trades_after_bar_open() {
+    let trades = generate_trades(7, 8_000, 50_000 * 100_000_000, 1_640_995_200_000, 1);
+
+    for (i, full) in policies().into_iter().map(|p| run(p, &trades)).enumerate() {
+        assert!(full.len() > 20, "policy {} produced too few bars", i);
+
+        for bar in full.iter().step_by(7) {
+            let open_index = trades
+                .iter()
+                .position(|t| t.agg_trade_id == bar.first_id)
+                .unwrap();
+            let prefix = &trades[..=open_index];
+
+            // Truncated: nothing after the opening trade exists
+            let truncated = run(policies().swap_remove(i), prefix);
+            let last = truncated.last().unwrap();
+            assert_eq!(last.first_id, bar.first_id);
+            assert_eq!(
+                (last.upper_threshold, last.lower_threshold),
+                (bar.upper_threshold, bar.lower_threshold),
+                "policy {} bar {} changed when future trades were removed",
+                i,
+                bar.first_id
+            );
+
+            // Replaced: a different future path after the opening trade
+            let open = &trades[open_index];
+            let mut altered = prefix.to_vec();
+            altered.extend(generate_trades(
+                99,
+                500,
+                open.price.0 * 2,
+                open.timestamp,
+                open.agg_trade_id + 1,
+            ));
+            let altered_bars = run(policies().swap_remove(i), &altered);
+            let same_bar = altered_bars
+                .iter()
+                .find(|b| b.first_id == bar.first_id)
+                .unwrap();
+            assert_eq!(
+                (same_bar.upper_threshold, same_bar.lower_threshold),
+                (bar.upper_threshold, bar.lower_threshold),
+                "policy {} bar {} changed when future trades were replaced",
+                i,
+                bar.first_id
+            );
+        }
+    }
+}
+
+#[test]
+fn test_fixed_policy_matches_plain_processor() {
+    let trades = generate_trades(3, 5_000, 50_000 * 100_000_000, 1_640_995_200_000, 1);
+    let mut plain = RangeBarProcessor::new(10);
+    assert_eq!(
+        plain.process_trades_with_incomplete(&trades).unwrap(),
+        run(Box::new(FixedThreshold::from(10)), &trades)
+    );
+}
+
+#[test]
+fn test_adaptive_policies_record_changing_thresholds() {
+    let trades = generate_trades(11, 8_000, 50_000 * 100_000_000, 1_640_995_200_000, 1);
+
+    for (i, bars) in policies()
+        .into_iter()
+        .map(|p| run(p, &trades))
+        .enumerate()
+        .skip(1)
+    {
+        let mut widths: Vec<i64> = bars
+            .iter()
+            .map(|b| b.upper_threshold.unwrap().0 - b.open.0)
+            .map(|delta| delta * 100_000 / bars[0].open.0)
+            .collect();
+        widths.dedup();
+        assert!(widths.len() > 1, "policy {} never adapted", i);
+
+        // Every bar still closes on its own recorded thresholds
+        for bar in &bars[..bars.len() - 1] {
+            assert!(
+                bar.close >= bar.upper_threshold.unwrap()
+                    || bar.close <= bar.lower_threshold.unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_adaptive_checkpoint_restore_matches_uninterrupted() {
+    let trades = generate_trades(5, 6_000, 50_000 * 100_000_000, 1_640_995_200_000, 1);
+    let bounds = AdaptiveBounds::default();
+    let policy = || EwmaRangePolicy::new(0.3, 0.5, bounds);
+
+    let mut expected = RangeBarProcessor::with_policy(policy(), RangeBarProcessorConfig::default());
+    let expected = expected.process_trades_with_incomplete(&trades).unwrap();
+
+    let (head, tail) = trades.split_at(3_217);
+    let mut first = RangeBarProcessor::with_policy(policy(), RangeBarProcessorConfig::default());
+    let mut bars = first.process_trades(head).unwrap();
+    let json = serde_json::to_string(&first.checkpoint()).unwrap();
+
+    // A fixed-threshold restore must not silently replace the adaptive policy
+    let checkpoint = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        RangeBarProcessor::restore(checkpoint),
+        Err(ProcessingError::PolicyMismatch { .. })
+    ));
+
+    let checkpoint = serde_json::from_str(&json).unwrap();
+    let mut second = RangeBarProcessor::restore_with_policy(
+        checkpoint,
+        EwmaRangePolicy::new(0.9, 5.0, AdaptiveBounds::default()),
+    )
+    .unwrap();
+    bars.extend(second.process_trades_with_incomplete(tail).unwrap());
+
+    assert_eq!(expected, bars);
+}